@@ -137,6 +137,14 @@ pub const KEYLET_PAGE: u32 = 19;
 pub const KEYLET_ESCROW: u32 = 20;
 pub const KEYLET_PAYCHAN: u32 = 21;
 pub const KEYLET_EMITTED: u32 = 22;
+#[cfg(feature = "api-v2")]
+pub const KEYLET_NFT_OFFER: u32 = 23;
+#[cfg(feature = "api-v2")]
+pub const KEYLET_HOOK_DEFINITION: u32 = 24;
+#[cfg(feature = "api-v2")]
+pub const KEYLET_HOOK_STATE_DIR: u32 = 25;
+#[cfg(feature = "xahau")]
+pub const KEYLET_URITOKEN: u32 = 26;
 pub const COMPARE_EQUAL: u32 = 1;
 pub const COMPARE_LESS: u32 = 2;
 pub const COMPARE_GREATER: u32 = 4;
@@ -250,6 +258,9 @@ pub const sfDeliverMin: u32 = 393226;
 pub const sfMinimumOffer: u32 = 393232;
 pub const sfRippleEscrow: u32 = 393233;
 pub const sfDeliveredAmount: u32 = 393234;
+pub const sfBaseFeeDrops: u32 = 393238;
+pub const sfReserveBaseDrops: u32 = 393239;
+pub const sfReserveIncrementDrops: u32 = 393240;
 pub const sfPublicKey: u32 = 458753;
 pub const sfMessageKey: u32 = 458754;
 pub const sfSigningPubKey: u32 = 458755;
@@ -347,7 +358,10 @@ pub const ENCODE_SEQUENCE_SIZE: u32 = 5;
 pub const ENCODE_FLAGS_SIZE: u32 = 5;
 pub const ENCODE_SIGNING_PUBKEY_SIZE: u32 = 35;
 pub const ENCODE_SIGNING_PUBKEY_NULL_SIZE: u32 = 35;
+#[cfg(not(feature = "api-v2"))]
 pub const PREPARE_PAYMENT_SIMPLE_SIZE: u32 = 237;
+#[cfg(feature = "api-v2")]
+pub const PREPARE_PAYMENT_SIMPLE_SIZE: u32 = 270;
 pub const PREPARE_PAYMENT_SIMPLE_TRUSTLINE_SIZE: u32 = 277;
 extern "C" {
     pub fn hook(reserved: i64) -> i64;
@@ -368,14 +382,14 @@ extern "C" {
     #[doc = " @param read_ptr An optional string to use as a return comment. May be 0."]
     #[doc = " @param read_len The length of the string. May be 0."]
     #[doc = " @return Will never return, terminates the hook."]
-    pub fn accept(read_ptr: u32, read_len: u32, error_code: i64) -> i64;
+    pub fn accept(read_ptr: Ptr, read_len: u32, error_code: i64) -> i64;
 }
 extern "C" {
     #[doc = " Rollback the originating transaction, discard all hook state changes and emitted transactions."]
     #[doc = " @param read_ptr An optional string to use as a return comment. May be 0."]
     #[doc = " @param read_len The length of the string. May be 0."]
     #[doc = " @return Will never return, terminates the hook."]
-    pub fn rollback(read_ptr: u32, read_len: u32, error_code: i64) -> i64;
+    pub fn rollback(read_ptr: Ptr, read_len: u32, error_code: i64) -> i64;
 }
 extern "C" {
     #[doc = " Read a 20 byte account-id from the memory pointed to by read_ptr of length read_len and encode it to a base58-check"]
@@ -386,7 +400,7 @@ extern "C" {
     #[doc = " @param write_len The size of the write buffer."]
     #[doc = " @return On success the length of the r-address will be returned indicating the bytes written to the write buffer."]
     #[doc = "         On failure a negative integer is returned indicating what went wrong."]
-    pub fn util_raddr(write_ptr: u32, write_len: u32, read_ptr: u32, read_len: u32) -> i64;
+    pub fn util_raddr(write_ptr: Ptr, write_len: u32, read_ptr: Ptr, read_len: u32) -> i64;
 }
 extern "C" {
     #[doc = " Read an r-address from the memory pointed to by read_ptr of length read_len and decode it to a 20 byte account id"]
@@ -397,7 +411,7 @@ extern "C" {
     #[doc = " @param write_len The size of the write buffer."]
     #[doc = " @return On success 20 will be returned indicating the bytes written. On failure a negative integer is returned"]
     #[doc = "         indicating what went wrong."]
-    pub fn util_accid(write_ptr: u32, write_len: u32, read_ptr: u32, read_len: u32) -> i64;
+    pub fn util_accid(write_ptr: Ptr, write_len: u32, read_ptr: Ptr, read_len: u32) -> i64;
 }
 extern "C" {
     #[doc = " Verify a cryptographic signature either ED25519 of SECP256k1. Public key should be prefixed with 0xED for 25519."]
@@ -409,11 +423,11 @@ extern "C" {
     #[doc = " @param kread_len The length of the public key"]
     #[doc = " @return True if and only if the signature was verified."]
     pub fn util_verify(
-        dread_ptr: u32,
+        dread_ptr: Ptr,
         dread_len: u32,
-        sread_ptr: u32,
+        sread_ptr: Ptr,
         sread_len: u32,
-        kread_ptr: u32,
+        kread_ptr: Ptr,
         kread_len: u32,
     ) -> i64;
 }
@@ -424,7 +438,7 @@ extern "C" {
     #[doc = " @param read_ptr  The buffer to read data for digest from."]
     #[doc = " @param read_len  The amount of data to read from the buffer."]
     #[doc = " @return The number of bytes written to write_ptr or a negative integer on error."]
-    pub fn util_sha512h(write_ptr: u32, write_len: u32, read_ptr: u32, read_len: u32) -> i64;
+    pub fn util_sha512h(write_ptr: Ptr, write_len: u32, read_ptr: Ptr, read_len: u32) -> i64;
 }
 extern "C" {
     #[doc = " Index into a xrpld serialized object and return the location and length of a subfield. Except for Array subtypes"]
@@ -436,7 +450,7 @@ extern "C" {
     #[doc = " @return high-word (most significant 4 bytes excluding the most significant bit (MSB)) is the field offset relative"]
     #[doc = "         to read_ptr and the low-word (least significant 4 bytes) is its length. MSB is sign bit, if set (negative)"]
     #[doc = "         return value indicates error (typically error means could not find.)"]
-    pub fn sto_subfield(read_ptr: u32, read_len: u32, field_id: u32) -> i64;
+    pub fn sto_subfield(read_ptr: Ptr, read_len: u32, field_id: u32) -> i64;
 }
 extern "C" {
     #[doc = " Index into a xrpld serialized array and return the location and length of an index. Unlike sto_subfield this api"]
@@ -448,42 +462,42 @@ extern "C" {
     #[doc = " @return high-word (most significant 4 bytes excluding the most significant bit (MSB)) is the field offset relative"]
     #[doc = "         to read_ptr and the low-word (least significant 4 bytes) is its length. MSB is sign bit, if set (negative)"]
     #[doc = "         return value indicates error (typically error means could not find.)"]
-    pub fn sto_subarray(read_ptr: u32, read_len: u32, array_id: u32) -> i64;
+    pub fn sto_subarray(read_ptr: Ptr, read_len: u32, array_id: u32) -> i64;
 }
 extern "C" {
-    pub fn sto_validate(read_ptr: u32, read_len: u32) -> i64;
+    pub fn sto_validate(read_ptr: Ptr, read_len: u32) -> i64;
 }
 extern "C" {
     pub fn sto_emplace(
-        write_ptr: u32,
+        write_ptr: Ptr,
         write_len: u32,
-        sread_ptr: u32,
+        sread_ptr: Ptr,
         sread_len: u32,
-        fread_ptr: u32,
+        fread_ptr: Ptr,
         fread_len: u32,
         field_id: u32,
     ) -> i64;
 }
 extern "C" {
     pub fn sto_erase(
-        write_ptr: u32,
+        write_ptr: Ptr,
         write_len: u32,
-        read_ptr: u32,
+        read_ptr: Ptr,
         read_len: u32,
         field_id: u32,
     ) -> i64;
 }
 extern "C" {
     pub fn util_keylet(
-        write_ptr: u32,
+        write_ptr: Ptr,
         write_len: u32,
         keylet_type: u32,
-        a: u32,
-        b: u32,
-        c: u32,
-        d: u32,
-        e: u32,
-        f: u32,
+        a: Ptr,
+        b: Ptr,
+        c: Ptr,
+        d: Ptr,
+        e: Ptr,
+        f: Ptr,
     ) -> i64;
 }
 extern "C" {
@@ -496,14 +510,23 @@ extern "C" {
     #[doc = " @param write_ptr A sufficiently large buffer to write into."]
     #[doc = " @param write_len The length of that buffer."]
     #[doc = " @return The number of bytes written or a negative integer indicating an error."]
-    pub fn etxn_details(write_ptr: u32, write_len: u32) -> i64;
+    pub fn etxn_details(write_ptr: Ptr, write_len: u32) -> i64;
 }
 extern "C" {
     #[doc = " Compute the minimum fee required to be paid by a hypothetically emitted transaction based on its size in bytes."]
     #[doc = " @param The size of the emitted transaction in bytes"]
     #[doc = " @return The minimum fee in drops this transaction should pay to succeed"]
+    #[cfg(not(feature = "api-v2"))]
     pub fn etxn_fee_base(tx_byte_count: u32) -> i64;
 }
+extern "C" {
+    #[doc = " Compute the minimum fee required to be paid by a hypothetically emitted transaction."]
+    #[doc = " @param read_ptr A buffer containing the serialized transaction"]
+    #[doc = " @param read_len The length of the transaction"]
+    #[doc = " @return The minimum fee in drops this transaction should pay to succeed"]
+    #[cfg(feature = "api-v2")]
+    pub fn etxn_fee_base(read_ptr: Ptr, read_len: u32) -> i64;
+}
 extern "C" {
     #[doc = " Inform xrpld that you will be emitting at most @count@ transactions during the course of this hook execution."]
     #[doc = " @param count The number of transactions you intend to emit from this  hook."]
@@ -516,26 +539,84 @@ extern "C" {
     #[doc = " @return The generation of a hypothetically emitted transaction."]
     pub fn etxn_generation() -> i64;
 }
+extern "C" {
+    #[doc = " Generate a 32 byte nonce for use in an emitted transaction. Can be called repeatedly for multiple nonces."]
+    #[doc = " @param write_ptr A buffer of at least 32 bytes to write into."]
+    #[doc = " @param write_len The length of that buffer"]
+    #[doc = " @return The number of bytes written or a negative integer if an error occured."]
+    #[cfg(feature = "api-v2")]
+    pub fn etxn_nonce(write_ptr: Ptr, write_len: u32) -> i64;
+}
 extern "C" {
     #[doc = " Emit a transaction from this hook."]
     #[doc = " @param read_ptr Memory location of a buffer containing the fully formed binary transaction to emit."]
     #[doc = " @param read_len The length of the transaction."]
     #[doc = " @return A negative integer if the emission failed."]
-    pub fn emit(write_ptr: u32, write_len: u32, read_ptr: u32, read_len: u32) -> i64;
+    pub fn emit(write_ptr: Ptr, write_len: u32, read_ptr: Ptr, read_len: u32) -> i64;
 }
 extern "C" {
     #[doc = " Retrieve the account the hook is running on."]
     #[doc = " @param write_ptr A buffer of at least 20 bytes to write into."]
     #[doc = " @param write_len The length of that buffer"]
     #[doc = " @return The number of bytes written into the buffer of a negative integer if an error occured."]
-    pub fn hook_account(write_ptr: u32, write_len: u32) -> i64;
+    pub fn hook_account(write_ptr: Ptr, write_len: u32) -> i64;
 }
 extern "C" {
     #[doc = " Retrieve the hash of the currently executing hook."]
     #[doc = " @param write_ptr A buffer of at least 32 bytes to write into."]
     #[doc = " @param write_len The length of that buffer"]
     #[doc = " @return The number of bytes written into the buffer of a negative integer if an error occured."]
-    pub fn hook_hash(write_ptr: u32, write_len: u32) -> i64;
+    pub fn hook_hash(write_ptr: Ptr, write_len: u32) -> i64;
+}
+extern "C" {
+    #[doc = " Retrieve a parameter of the currently executing hook, set on install by SetHook or later by hook_param_set."]
+    #[doc = " @param write_ptr A buffer to write the parameter value into, at most 256 bytes are written."]
+    #[doc = " @param write_len The length of that buffer"]
+    #[doc = " @param read_ptr A buffer containing the parameter name"]
+    #[doc = " @param read_len The length of the name, at most 32 bytes"]
+    #[doc = " @return The number of bytes written or a negative integer if an error occured."]
+    #[cfg(feature = "api-v2")]
+    pub fn hook_param(write_ptr: Ptr, write_len: u32, read_ptr: Ptr, read_len: u32) -> i64;
+}
+extern "C" {
+    #[doc = " Set a parameter of a hook later in the execution chain, for this execution only."]
+    #[doc = " @param read_ptr A buffer containing the parameter value, empty to delete the parameter"]
+    #[doc = " @param read_len The length of the value, at most 256 bytes"]
+    #[doc = " @param kread_ptr A buffer containing the parameter name"]
+    #[doc = " @param kread_len The length of the name, at most 32 bytes"]
+    #[doc = " @param hread_ptr A buffer containing the hash of the hook the parameter is set for"]
+    #[doc = " @param hread_len The length of the hash (should always be 32)."]
+    #[doc = " @return The number of bytes of the value or a negative integer if an error occured."]
+    #[cfg(feature = "api-v2")]
+    pub fn hook_param_set(
+        read_ptr: Ptr,
+        read_len: u32,
+        kread_ptr: Ptr,
+        kread_len: u32,
+        hread_ptr: Ptr,
+        hread_len: u32,
+    ) -> i64;
+}
+extern "C" {
+    #[doc = " Skip (or stop skipping) a hook later in the execution chain, for this execution only."]
+    #[doc = " @param read_ptr A buffer containing the hash of the hook"]
+    #[doc = " @param read_len The length of the hash (should always be 32)."]
+    #[doc = " @param flags 0 to skip the hook, 1 to stop skipping it"]
+    #[doc = " @return 1 on success or a negative integer if an error occured."]
+    #[cfg(feature = "api-v2")]
+    pub fn hook_skip(read_ptr: Ptr, read_len: u32, flags: u32) -> i64;
+}
+extern "C" {
+    #[doc = " Request a second execution of this hook, after the originating transaction is applied (weak execution)."]
+    #[doc = " @return 1 on success or a negative integer if an error occured."]
+    #[cfg(feature = "api-v2")]
+    pub fn hook_again() -> i64;
+}
+extern "C" {
+    #[doc = " Retrieve the position of the currently executing hook in the hook chain of the account."]
+    #[doc = " @return The position, from 0, of the hook."]
+    #[cfg(feature = "api-v2")]
+    pub fn hook_pos() -> i64;
 }
 extern "C" {
     #[doc = " Retrive the currently recommended minimum fee for a transaction to succeed."]
@@ -546,18 +627,51 @@ extern "C" {
     pub fn ledger_seq() -> i64;
 }
 extern "C" {
-    pub fn ledger_last_hash(write_ptr: u32, write_len: u32) -> i64;
+    pub fn ledger_last_hash(write_ptr: Ptr, write_len: u32) -> i64;
 }
 extern "C" {
     #[doc = " Retrieve a nonce for use in an emitted transaction (or another task). Can be called repeatedly for multiple nonces."]
     #[doc = " @param write_ptr A buffer of at least 32 bytes to write into."]
     #[doc = " @param write_len The length of that buffer"]
     #[doc = " @return The number of bytes written into the buffer of a negative integer if an error occured."]
-    pub fn nonce(write_ptr: u32, write_len: u32) -> i64;
+    #[cfg(not(feature = "api-v2"))]
+    pub fn nonce(write_ptr: Ptr, write_len: u32) -> i64;
+}
+extern "C" {
+    #[doc = " Retrieve the close time of the last closed ledger, in seconds since the ripple epoch."]
+    #[cfg(feature = "api-v2")]
+    pub fn ledger_last_time() -> i64;
+}
+extern "C" {
+    #[doc = " Generate a 32 byte nonce from the last closed ledger. Can be called repeatedly for multiple nonces."]
+    #[doc = " @param write_ptr A buffer of at least 32 bytes to write into."]
+    #[doc = " @param write_len The length of that buffer"]
+    #[doc = " @return The number of bytes written or a negative integer if an error occured."]
+    #[cfg(feature = "api-v2")]
+    pub fn ledger_nonce(write_ptr: Ptr, write_len: u32) -> i64;
+}
+extern "C" {
+    #[doc = " Search for the first ledger object with a keylet between two keylets."]
+    #[doc = " @param write_ptr A buffer of at least 34 bytes to write the found keylet into."]
+    #[doc = " @param write_len The length of that buffer"]
+    #[doc = " @param lread_ptr A buffer containing the low keylet of the search range"]
+    #[doc = " @param lread_len The length of that keylet (should always be 34)."]
+    #[doc = " @param hread_ptr A buffer containing the high keylet of the search range"]
+    #[doc = " @param hread_len The length of that keylet (should always be 34)."]
+    #[doc = " @return The number of bytes written or a negative integer if an error occured."]
+    #[cfg(feature = "api-v2")]
+    pub fn ledger_keylet(
+        write_ptr: Ptr,
+        write_len: u32,
+        lread_ptr: Ptr,
+        lread_len: u32,
+        hread_ptr: Ptr,
+        hread_len: u32,
+    ) -> i64;
 }
 extern "C" {
     #[doc = " Slot functions have not been implemented yet and the api for them is subject to change"]
-    pub fn slot(write_ptr: u32, write_len: u32, slot: u32) -> i64;
+    pub fn slot(write_ptr: Ptr, write_len: u32, slot: u32) -> i64;
 }
 extern "C" {
     pub fn slot_clear(slot: u32) -> i64;
@@ -569,7 +683,7 @@ extern "C" {
     pub fn slot_id(slot: u32) -> i64;
 }
 extern "C" {
-    pub fn slot_set(read_ptr: u32, read_len: u32, slot: i32) -> i64;
+    pub fn slot_set(read_ptr: Ptr, read_len: u32, slot: i32) -> i64;
 }
 extern "C" {
     pub fn slot_size(slot: u32) -> i64;
@@ -587,7 +701,15 @@ extern "C" {
     pub fn slot_float(slot: u32) -> i64;
 }
 extern "C" {
-    pub fn trace_slot(mread_ptr: u32, mread_len: u32, slot: u32) -> i64;
+    #[cfg(feature = "api-v2")]
+    pub fn meta_slot(slot: u32) -> i64;
+}
+extern "C" {
+    #[cfg(feature = "xahau")]
+    pub fn xpop_slot(slot_tx: u32, slot_meta: u32) -> i64;
+}
+extern "C" {
+    pub fn trace_slot(mread_ptr: Ptr, mread_len: u32, slot: u32) -> i64;
 }
 extern "C" {
     pub fn otxn_slot(slot: u32) -> i64;
@@ -599,7 +721,7 @@ extern "C" {
     #[doc = " @param kread_ptr A buffer containing the key"]
     #[doc = " @param kread_len The length of the key"]
     #[doc = " @return The number of bytes stored or a negative integer if an error occured"]
-    pub fn state_set(read_ptr: u32, read_len: u32, kread_ptr: u32, kread_len: u32) -> i64;
+    pub fn state_set(read_ptr: Ptr, read_len: u32, kread_ptr: Ptr, kread_len: u32) -> i64;
 }
 extern "C" {
     #[doc = " Retrieve a value from the hook's key-value map."]
@@ -608,7 +730,7 @@ extern "C" {
     #[doc = " @param kread_ptr A buffer to read the state key from"]
     #[doc = " @param kread_len The length of that key"]
     #[doc = " @return The number of bytes written or a negative integer if an error occured."]
-    pub fn state(write_ptr: u32, write_len: u32, kread_ptr: u32, kread_len: u32) -> i64;
+    pub fn state(write_ptr: Ptr, write_len: u32, kread_ptr: Ptr, kread_len: u32) -> i64;
 }
 extern "C" {
     #[doc = " Retrieve a value from another hook's key-value map."]
@@ -619,12 +741,59 @@ extern "C" {
     #[doc = " @param aread_ptr A buffer containing an account-id of another account containing a hook whose state we are reading"]
     #[doc = " @param aread_len The length of the account-id (should always be 20)."]
     #[doc = " @return The number of bytes written or a negative integer if an error occured."]
+    #[cfg(not(feature = "api-v2"))]
     pub fn state_foreign(
-        write_ptr: u32,
+        write_ptr: Ptr,
         write_len: u32,
-        kread_ptr: u32,
+        kread_ptr: Ptr,
         kread_len: u32,
-        aread_ptr: u32,
+        aread_ptr: Ptr,
+        aread_len: u32,
+    ) -> i64;
+}
+extern "C" {
+    #[doc = " Retrieve a value from another account's hook key-value map, in one of its namespaces."]
+    #[doc = " @param write_ptr A buffer to write the state value into"]
+    #[doc = " @param write_len The length of that buffer"]
+    #[doc = " @param kread_ptr A buffer to read the state key from"]
+    #[doc = " @param kread_len The length of that key"]
+    #[doc = " @param nread_ptr A buffer containing the namespace of the state"]
+    #[doc = " @param nread_len The length of the namespace (should always be 32)."]
+    #[doc = " @param aread_ptr A buffer containing the account-id of the account the state belongs to"]
+    #[doc = " @param aread_len The length of the account-id (should always be 20)."]
+    #[doc = " @return The number of bytes written or a negative integer if an error occured."]
+    #[cfg(feature = "api-v2")]
+    pub fn state_foreign(
+        write_ptr: Ptr,
+        write_len: u32,
+        kread_ptr: Ptr,
+        kread_len: u32,
+        nread_ptr: Ptr,
+        nread_len: u32,
+        aread_ptr: Ptr,
+        aread_len: u32,
+    ) -> i64;
+}
+extern "C" {
+    #[doc = " Set a value in another account's hook key-value map, the other account must have granted this hook."]
+    #[doc = " @param read_ptr A buffer containing the data to store, empty to delete the entry"]
+    #[doc = " @param read_len The length of the data"]
+    #[doc = " @param kread_ptr A buffer containing the key"]
+    #[doc = " @param kread_len The length of the key"]
+    #[doc = " @param nread_ptr A buffer containing the namespace of the state"]
+    #[doc = " @param nread_len The length of the namespace (should always be 32)."]
+    #[doc = " @param aread_ptr A buffer containing the account-id of the account the state belongs to"]
+    #[doc = " @param aread_len The length of the account-id (should always be 20)."]
+    #[doc = " @return The number of bytes stored or a negative integer if an error occured."]
+    #[cfg(feature = "api-v2")]
+    pub fn state_foreign_set(
+        read_ptr: Ptr,
+        read_len: u32,
+        kread_ptr: Ptr,
+        kread_len: u32,
+        nread_ptr: Ptr,
+        nread_len: u32,
+        aread_ptr: Ptr,
         aread_len: u32,
     ) -> i64;
 }
@@ -635,9 +804,9 @@ extern "C" {
     #[doc = " @param as_hex If 0 treat the read_ptr as pointing at a string of text, otherwise treat it as data and print hex"]
     #[doc = " @return The number of bytes output or a negative integer if an error occured."]
     pub fn trace(
-        mread_ptr: u32,
+        mread_ptr: Ptr,
         mread_len: u32,
-        dread_ptr: u32,
+        dread_ptr: Ptr,
         dread_len: u32,
         as_hex: u32,
     ) -> i64;
@@ -649,7 +818,7 @@ extern "C" {
     #[doc = " @param read_len The length of the string to output"]
     #[doc = " @param number Any integer you wish to display after the text"]
     #[doc = " @return A negative value on error"]
-    pub fn trace_num(read_ptr: u32, read_len: u32, number: i64) -> i64;
+    pub fn trace_num(read_ptr: Ptr, read_len: u32, number: i64) -> i64;
 }
 extern "C" {
     #[doc = " Retrieve the burden of the originating transaction (if any)"]
@@ -662,7 +831,8 @@ extern "C" {
     #[doc = " @param write_len The length of the buffer"]
     #[doc = " @param field_id The field code of the field being requested"]
     #[doc = " @return The number of bytes written to write_ptr or a negative integer if an error occured."]
-    pub fn otxn_field_txt(write_ptr: u32, write_len: u32, field_id: u32) -> i64;
+    #[cfg(not(feature = "api-v2"))]
+    pub fn otxn_field_txt(write_ptr: Ptr, write_len: u32, field_id: u32) -> i64;
 }
 extern "C" {
     #[doc = " Retrieve a field from the originating transaction in its raw serialized form."]
@@ -670,7 +840,7 @@ extern "C" {
     #[doc = " @param write_len The length of the buffer."]
     #[doc = " @param field_if The field code of the field being requested"]
     #[doc = " @return The number of bytes written to write_ptr or a negative integer if an error occured."]
-    pub fn otxn_field(write_ptr: u32, write_len: u32, field_id: u32) -> i64;
+    pub fn otxn_field(write_ptr: Ptr, write_len: u32, field_id: u32) -> i64;
 }
 extern "C" {
     #[doc = " Retrieve the generation of the originating transaction (if any)."]
@@ -682,13 +852,33 @@ extern "C" {
     #[doc = " @param write_ptr A buffer at least 32 bytes long"]
     #[doc = " @param write_len The length of the buffer."]
     #[doc = " @return The number of bytes written into the buffer or a negative integer on failure."]
-    pub fn otxn_id(write_ptr: u32, write_len: u32) -> i64;
+    #[cfg(not(feature = "api-v2"))]
+    pub fn otxn_id(write_ptr: Ptr, write_len: u32) -> i64;
+}
+extern "C" {
+    #[doc = " Retrieve the TXNID of the originating transaction."]
+    #[doc = " @param write_ptr A buffer at least 32 bytes long"]
+    #[doc = " @param write_len The length of the buffer."]
+    #[doc = " @param flags 0 for the ID of the originating transaction, other values as defined by the host"]
+    #[doc = " @return The number of bytes written into the buffer or a negative integer on failure."]
+    #[cfg(feature = "api-v2")]
+    pub fn otxn_id(write_ptr: Ptr, write_len: u32, flags: u32) -> i64;
 }
 extern "C" {
     #[doc = " Retrieve the Transaction Type (e.g. ttPayment = 0) of the originating transaction."]
     #[doc = " @return The Transaction Type (tt-code)"]
     pub fn otxn_type() -> i64;
 }
+extern "C" {
+    #[doc = " Retrieve a parameter of the originating transaction, from its HookParameters field."]
+    #[doc = " @param write_ptr A buffer to write the parameter value into, at most 256 bytes are written."]
+    #[doc = " @param write_len The length of that buffer"]
+    #[doc = " @param read_ptr A buffer containing the parameter name"]
+    #[doc = " @param read_len The length of the name, at most 32 bytes"]
+    #[doc = " @return The number of bytes written or a negative integer if an error occured."]
+    #[cfg(feature = "api-v2")]
+    pub fn otxn_param(write_ptr: Ptr, write_len: u32, read_ptr: Ptr, read_len: u32) -> i64;
+}
 extern "C" {
     pub fn float_set(exponent: i32, mantissa: i64) -> i64;
 }
@@ -709,18 +899,18 @@ extern "C" {
 }
 extern "C" {
     pub fn float_sto(
-        write_ptr: u32,
+        write_ptr: Ptr,
         write_len: u32,
-        cread_ptr: u32,
+        cread_ptr: Ptr,
         cread_len: u32,
-        iread_ptr: u32,
+        iread_ptr: Ptr,
         iread_len: u32,
         float1: i64,
         field_code: u32,
     ) -> i64;
 }
 extern "C" {
-    pub fn float_sto_set(read_ptr: u32, read_len: u32) -> i64;
+    pub fn float_sto_set(read_ptr: Ptr, read_len: u32) -> i64;
 }
 extern "C" {
     pub fn float_invert(float1: i64) -> i64;
@@ -728,6 +918,14 @@ extern "C" {
 extern "C" {
     pub fn float_divide(float1: i64, float2: i64) -> i64;
 }
+extern "C" {
+    #[cfg(feature = "api-v2")]
+    pub fn float_root(float1: i64, n: u32) -> i64;
+}
+extern "C" {
+    #[cfg(feature = "api-v2")]
+    pub fn float_log(float1: i64) -> i64;
+}
 extern "C" {
     pub fn float_one() -> i64;
 }
@@ -753,5 +951,5 @@ extern "C" {
     pub fn float_int(float1: i64, decimal_places: u32, abs: u32) -> i64;
 }
 extern "C" {
-    pub fn trace_float(mread_ptr: u32, mread_len: u32, float1: i64) -> i64;
+    pub fn trace_float(mread_ptr: Ptr, mread_len: u32, float1: i64) -> i64;
 }
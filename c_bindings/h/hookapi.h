@@ -7,6 +7,9 @@
  * undesirable output.
  *
  * Find documentation here: https://xrpl-hooks.readme.io/reference/
+ *
+ * Define HOOKS_API_V2 for the Hooks amendment (and Xahau) signatures,
+ * leave it undefined for the 2021 hooks testnet.
  */
     
 
@@ -146,12 +149,22 @@ extern int64_t etxn_burden         (void );
  */
 extern int64_t etxn_details        (uint32_t write_ptr,  uint32_t write_len);
 
+#ifdef HOOKS_API_V2
+/**
+ * Compute the minimum fee required to be paid by a hypothetically emitted transaction.
+ * @param read_ptr A buffer containing the serialized transaction
+ * @param read_len The length of the transaction
+ * @return The minimum fee in drops this transaction should pay to succeed
+ */
+extern int64_t etxn_fee_base       (uint32_t read_ptr,   uint32_t read_len);
+#else
 /**
  * Compute the minimum fee required to be paid by a hypothetically emitted transaction based on its size in bytes.
  * @param The size of the emitted transaction in bytes
  * @return The minimum fee in drops this transaction should pay to succeed
  */
 extern int64_t etxn_fee_base       (uint32_t tx_byte_count);
+#endif
 
 /**
  * Inform xrpld that you will be emitting at most @count@ transactions during the course of this hook execution.
@@ -167,6 +180,14 @@ extern int64_t etxn_reserve        (uint32_t count);
  */
 extern int64_t etxn_generation     (void);
 
+/**
+ * Generate a 32 byte nonce for use in an emitted transaction. Can be called repeatedly for multiple nonces.
+ * @param write_ptr A buffer of at least 32 bytes to write into.
+ * @param write_len The length of that buffer
+ * @return The number of bytes written or a negative integer if an error occured.
+ */
+extern int64_t etxn_nonce          (uint32_t write_ptr,  uint32_t write_len);
+
 /**
  * Emit a transaction from this hook.
  * @param read_ptr Memory location of a buffer containing the fully formed binary transaction to emit.
@@ -191,6 +212,52 @@ extern int64_t hook_account        (uint32_t write_ptr,  uint32_t write_len);
  */
 extern int64_t hook_hash           (uint32_t write_ptr,  uint32_t write_len);
 
+/**
+ * Retrieve a parameter of the currently executing hook, set on install by SetHook or later by hook_param_set.
+ * @param write_ptr A buffer to write the parameter value into, at most 256 bytes are written.
+ * @param write_len The length of that buffer
+ * @param read_ptr A buffer containing the parameter name
+ * @param read_len The length of the name, at most 32 bytes
+ * @return The number of bytes written or a negative integer if an error occured.
+ */
+extern int64_t hook_param          (uint32_t write_ptr,  uint32_t write_len,
+                                    uint32_t read_ptr,   uint32_t read_len);
+
+/**
+ * Set a parameter of a hook later in the execution chain, for this execution only.
+ * @param read_ptr A buffer containing the parameter value, empty to delete the parameter
+ * @param read_len The length of the value, at most 256 bytes
+ * @param kread_ptr A buffer containing the parameter name
+ * @param kread_len The length of the name, at most 32 bytes
+ * @param hread_ptr A buffer containing the hash of the hook the parameter is set for
+ * @param hread_len The length of the hash (should always be 32).
+ * @return The number of bytes of the value or a negative integer if an error occured.
+ */
+extern int64_t hook_param_set      (uint32_t read_ptr,   uint32_t read_len,
+                                    uint32_t kread_ptr,  uint32_t kread_len,
+                                    uint32_t hread_ptr,  uint32_t hread_len);
+
+/**
+ * Skip (or stop skipping) a hook later in the execution chain, for this execution only.
+ * @param read_ptr A buffer containing the hash of the hook
+ * @param read_len The length of the hash (should always be 32).
+ * @param flags 0 to skip the hook, 1 to stop skipping it
+ * @return 1 on success or a negative integer if an error occured.
+ */
+extern int64_t hook_skip           (uint32_t read_ptr,   uint32_t read_len,   uint32_t flags);
+
+/**
+ * Request a second execution of this hook, after the originating transaction is applied (weak execution).
+ * @return 1 on success or a negative integer if an error occured.
+ */
+extern int64_t hook_again          (void);
+
+/**
+ * Retrieve the position of the currently executing hook in the hook chain of the account.
+ * @return The position, from 0, of the hook.
+ */
+extern int64_t hook_pos            (void);
+
 /**
  * Retrive the currently recommended minimum fee for a transaction to succeed.
  */
@@ -203,6 +270,7 @@ extern int64_t ledger_seq          (void);
 
 extern int64_t ledger_last_hash    (uint32_t write_ptr,  uint32_t write_len);
 
+#ifndef HOOKS_API_V2
 /**
  * Retrieve a nonce for use in an emitted transaction (or another task). Can be called repeatedly for multiple nonces.
  * @param write_ptr A buffer of at least 32 bytes to write into.
@@ -210,6 +278,34 @@ extern int64_t ledger_last_hash    (uint32_t write_ptr,  uint32_t write_len);
  * @return The number of bytes written into the buffer of a negative integer if an error occured.
  */
 extern int64_t nonce               (uint32_t write_ptr,  uint32_t write_len);
+#endif
+
+/**
+ * Retrieve the close time of the last closed ledger, in seconds since the ripple epoch.
+ */
+extern int64_t ledger_last_time    (void);
+
+/**
+ * Generate a 32 byte nonce from the last closed ledger. Can be called repeatedly for multiple nonces.
+ * @param write_ptr A buffer of at least 32 bytes to write into.
+ * @param write_len The length of that buffer
+ * @return The number of bytes written or a negative integer if an error occured.
+ */
+extern int64_t ledger_nonce        (uint32_t write_ptr,  uint32_t write_len);
+
+/**
+ * Search for the first ledger object with a keylet between two keylets.
+ * @param write_ptr A buffer of at least 34 bytes to write the found keylet into.
+ * @param write_len The length of that buffer
+ * @param lread_ptr A buffer containing the low keylet of the search range
+ * @param lread_len The length of that keylet (should always be 34).
+ * @param hread_ptr A buffer containing the high keylet of the search range
+ * @param hread_len The length of that keylet (should always be 34).
+ * @return The number of bytes written or a negative integer if an error occured.
+ */
+extern int64_t ledger_keylet       (uint32_t write_ptr,  uint32_t write_len,
+                                    uint32_t lread_ptr,  uint32_t lread_len,
+                                    uint32_t hread_ptr,  uint32_t hread_len);
 
 
 /**
@@ -226,6 +322,8 @@ extern int64_t slot_subarray       (uint32_t parent_slot, uint32_t array_id, uin
 extern int64_t slot_subfield       (uint32_t parent_slot, uint32_t field_id, uint32_t new_slot);
 extern int64_t slot_type           (uint32_t slot, uint32_t flags);
 extern int64_t slot_float          (uint32_t slot);
+extern int64_t meta_slot           (uint32_t slot);
+extern int64_t xpop_slot           (uint32_t slot_tx,   uint32_t slot_meta);
 extern int64_t trace_slot          (uint32_t mread_ptr, uint32_t mread_len, uint32_t slot);
 extern int64_t otxn_slot           (uint32_t slot);
 
@@ -252,6 +350,24 @@ extern int64_t state_set           (uint32_t read_ptr,   uint32_t read_len,
 extern int64_t state               (uint32_t write_ptr,  uint32_t write_len,
                                     uint32_t kread_ptr,  uint32_t kread_len);
 
+#ifdef HOOKS_API_V2
+/**
+ * Retrieve a value from another account's hook key-value map, in one of its namespaces.
+ * @param write_ptr A buffer to write the state value into
+ * @param write_len The length of that buffer
+ * @param kread_ptr A buffer to read the state key from
+ * @param kread_len The length of that key
+ * @param nread_ptr A buffer containing the namespace of the state
+ * @param nread_len The length of the namespace (should always be 32).
+ * @param aread_ptr A buffer containing the account-id of the account the state belongs to
+ * @param aread_len The length of the account-id (should always be 20).
+ * @return The number of bytes written or a negative integer if an error occured.
+ */
+extern int64_t state_foreign       (uint32_t write_ptr,  uint32_t write_len,
+                                    uint32_t kread_ptr,  uint32_t kread_len,
+                                    uint32_t nread_ptr,  uint32_t nread_len,
+                                    uint32_t aread_ptr,  uint32_t aread_len);
+#else
 /**
  * Retrieve a value from another hook's key-value map.
  * @param write_ptr A buffer to write the state value into
@@ -265,6 +381,24 @@ extern int64_t state               (uint32_t write_ptr,  uint32_t write_len,
 extern int64_t state_foreign       (uint32_t write_ptr,  uint32_t write_len,
                                     uint32_t kread_ptr,  uint32_t kread_len,
                                     uint32_t aread_ptr,  uint32_t aread_len);
+#endif
+
+/**
+ * Set a value in another account's hook key-value map, the other account must have granted this hook.
+ * @param read_ptr A buffer containing the data to store, empty to delete the entry
+ * @param read_len The length of the data
+ * @param kread_ptr A buffer containing the key
+ * @param kread_len The length of the key
+ * @param nread_ptr A buffer containing the namespace of the state
+ * @param nread_len The length of the namespace (should always be 32).
+ * @param aread_ptr A buffer containing the account-id of the account the state belongs to
+ * @param aread_len The length of the account-id (should always be 20).
+ * @return The number of bytes stored or a negative integer if an error occured.
+ */
+extern int64_t state_foreign_set   (uint32_t read_ptr,   uint32_t read_len,
+                                    uint32_t kread_ptr,  uint32_t kread_len,
+                                    uint32_t nread_ptr,  uint32_t nread_len,
+                                    uint32_t aread_ptr,  uint32_t aread_len);
 
 /**
  * Print some output to the trace log on xrpld. Any xrpld instance set to "trace" log level will see this.
@@ -292,6 +426,7 @@ extern int64_t trace_num           (uint32_t read_ptr,   uint32_t read_len,   in
  */
 extern int64_t otxn_burden         (void);
 
+#ifndef HOOKS_API_V2
 /**
  * Retrieve a field from the originating transaction as "full text" (The way it is displayed in JSON)
  * @param write_ptr A buffer to write the representation into
@@ -300,6 +435,7 @@ extern int64_t otxn_burden         (void);
  * @return The number of bytes written to write_ptr or a negative integer if an error occured.
  */
 extern int64_t otxn_field_txt      (uint32_t write_ptr,  uint32_t write_len,  uint32_t field_id);
+#endif
 
 /**
  * Retrieve a field from the originating transaction in its raw serialized form.
@@ -316,6 +452,16 @@ extern int64_t otxn_field          (uint32_t write_ptr,  uint32_t write_len,  ui
  */
 extern int64_t otxn_generation     (void);
 
+#ifdef HOOKS_API_V2
+/**
+ * Retrieve the TXNID of the originating transaction.
+ * @param write_ptr A buffer at least 32 bytes long
+ * @param write_len The length of the buffer.
+ * @param flags 0 for the ID of the originating transaction, other values as defined by the host
+ * @return The number of bytes written into the buffer or a negative integer on failure.
+ */
+extern int64_t otxn_id             (uint32_t write_ptr,  uint32_t write_len,  uint32_t flags);
+#else
 /**
  * Retrieve the TXNID of the originating transaction.
  * @param write_ptr A buffer at least 32 bytes long
@@ -323,6 +469,7 @@ extern int64_t otxn_generation     (void);
  * @return The number of bytes written into the buffer or a negative integer on failure.
  */
 extern int64_t otxn_id             (uint32_t write_ptr,  uint32_t write_len);
+#endif
 
 /**
  * Retrieve the Transaction Type (e.g. ttPayment = 0) of the originating transaction.
@@ -330,6 +477,17 @@ extern int64_t otxn_id             (uint32_t write_ptr,  uint32_t write_len);
  */
 extern int64_t otxn_type           (void);
 
+/**
+ * Retrieve a parameter of the originating transaction, from its HookParameters field.
+ * @param write_ptr A buffer to write the parameter value into, at most 256 bytes are written.
+ * @param write_len The length of that buffer
+ * @param read_ptr A buffer containing the parameter name
+ * @param read_len The length of the name, at most 32 bytes
+ * @return The number of bytes written or a negative integer if an error occured.
+ */
+extern int64_t otxn_param          (uint32_t write_ptr,  uint32_t write_len,
+                                    uint32_t read_ptr,   uint32_t read_len);
+
 
 
 extern int64_t  float_set           (int32_t exponent,   int64_t mantissa );
@@ -346,6 +504,8 @@ extern int64_t  float_sto           (uint32_t write_ptr, uint32_t write_len,
 extern int64_t  float_sto_set       (uint32_t read_ptr,  uint32_t read_len );
 extern int64_t  float_invert        (int64_t float1 );
 extern int64_t  float_divide        (int64_t float1,     int64_t float2 );
+extern int64_t  float_root          (int64_t float1,     uint32_t n );
+extern int64_t  float_log           (int64_t float1 );
 extern int64_t  float_one           ();
 
 extern int64_t  float_exponent      (int64_t float1 );
@@ -406,6 +566,12 @@ extern int64_t  trace_float         (uint32_t mread_ptr, uint32_t mread_len, int
 #define KEYLET_ESCROW 20
 #define KEYLET_PAYCHAN 21
 #define KEYLET_EMITTED 22
+// Hooks amendment
+#define KEYLET_NFT_OFFER 23
+#define KEYLET_HOOK_DEFINITION 24
+#define KEYLET_HOOK_STATE_DIR 25
+// Xahau
+#define KEYLET_URITOKEN 26
 
 #define COMPARE_EQUAL 1U
 #define COMPARE_LESS 2U
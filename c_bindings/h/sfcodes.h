@@ -111,6 +111,9 @@
 #define sfMinimumOffer 0x60010UL
 #define sfRippleEscrow 0x60011UL
 #define sfDeliveredAmount 0x60012UL
+#define sfBaseFeeDrops 0x60016UL
+#define sfReserveBaseDrops 0x60017UL
+#define sfReserveIncrementDrops 0x60018UL
 #define sfPublicKey 0x70001UL
 #define sfMessageKey 0x70002UL
 #define sfSigningPubKey 0x70003UL
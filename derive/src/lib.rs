@@ -0,0 +1,280 @@
+//! Derive macros for the [XRPL Hooks API](https://docs.rs/xrpl-hooks/)
+//!
+//! Use them through the `derive` feature of the `xrpl-hooks` crate.
+
+#![deny(
+    warnings,
+    clippy::all,
+    missing_docs,
+    rustdoc::missing_crate_level_docs,
+    non_ascii_idents,
+    unreachable_pub
+)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Index};
+
+/// Derives `StateCodec` for a struct or an enum
+///
+/// The encoding is fixed-size and big-endian, fields are encoded in declaration order.
+/// Enums are encoded as a one byte variant index followed by the variant fields,
+/// padded with zeroes up to the largest variant, so equal values encode to the same bytes.
+///
+/// Also adds an inherent `SIZE` constant,
+/// `to_bytes()` returning `[u8; SIZE]` and `from_bytes(&[u8])`.
+/// Generic types are not supported.
+#[proc_macro_derive(HookState)]
+pub fn derive_hook_state(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match hook_state(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn hook_state(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "HookState can't be derived for generic types",
+        ));
+    }
+
+    let name = &input.ident;
+
+    let (size, encode, decode) = match &input.data {
+        Data::Struct(data) => struct_codec(&data.fields),
+        Data::Enum(data) => {
+            if data.variants.len() > 256 {
+                return Err(Error::new_spanned(
+                    name,
+                    "HookState supports at most 256 enum variants",
+                ));
+            }
+            enum_codec(name, data.variants.iter().map(|v| (&v.ident, &v.fields)))
+        }
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                name,
+                "HookState can't be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl ::xrpl_hooks::StateCodec for #name {
+            const SIZE: usize = #size;
+
+            #[inline(always)]
+            fn encode(
+                &self,
+                writer: &mut ::xrpl_hooks::BufWriter,
+            ) -> ::xrpl_hooks::Result<()> {
+                #encode
+                ::xrpl_hooks::Result::Ok(())
+            }
+
+            #[inline(always)]
+            fn decode(
+                reader: &mut ::xrpl_hooks::BufReader,
+            ) -> ::xrpl_hooks::Result<Self> {
+                #decode
+            }
+        }
+
+        impl #name {
+            /// Encoded size in bytes
+            pub const SIZE: usize = <Self as ::xrpl_hooks::StateCodec>::SIZE;
+
+            /// Encode into a fixed-size array
+            #[inline(always)]
+            pub fn to_bytes(&self) -> ::xrpl_hooks::Result<[u8; #name::SIZE]> {
+                let mut bytes = [0; #name::SIZE];
+                match ::xrpl_hooks::StateCodec::encode(
+                    self,
+                    &mut ::xrpl_hooks::BufWriter::new(&mut bytes),
+                ) {
+                    ::xrpl_hooks::Result::Ok(_) => ::xrpl_hooks::Result::Ok(bytes),
+                    ::xrpl_hooks::Result::Err(e) => ::xrpl_hooks::Result::Err(e),
+                }
+            }
+
+            /// Decode from the beginning of a buffer
+            #[inline(always)]
+            pub fn from_bytes(bytes: &[u8]) -> ::xrpl_hooks::Result<Self> {
+                <Self as ::xrpl_hooks::StateCodec>::decode(
+                    &mut ::xrpl_hooks::BufReader::new(bytes),
+                )
+            }
+        }
+    })
+}
+
+/// Sum of the field sizes
+fn fields_size(fields: &Fields) -> TokenStream2 {
+    let sizes = fields.iter().map(|field| {
+        let ty = &field.ty;
+        quote!(<#ty as ::xrpl_hooks::StateCodec>::SIZE)
+    });
+
+    quote!(0 #(+ #sizes)*)
+}
+
+/// Bindings of the fields when destructuring: `a`, `b` or `field_0`, `field_1`
+fn field_bindings(fields: &Fields) -> Vec<syn::Ident> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => ident.clone(),
+            None => format_ident!("field_{}", i),
+        })
+        .collect()
+}
+
+/// Pattern or constructor of the fields from the bindings
+fn fields_pattern(fields: &Fields, bindings: &[syn::Ident]) -> TokenStream2 {
+    match fields {
+        Fields::Named(_) => quote!({ #(#bindings),* }),
+        Fields::Unnamed(_) => quote!(( #(#bindings),* )),
+        Fields::Unit => quote!(),
+    }
+}
+
+fn encode_fields<'a>(values: impl Iterator<Item = TokenStream2> + 'a) -> TokenStream2 {
+    let encodes = values.map(|value| {
+        quote! {
+            match ::xrpl_hooks::StateCodec::encode(#value, writer) {
+                ::xrpl_hooks::Result::Err(e) => return ::xrpl_hooks::Result::Err(e),
+                ::xrpl_hooks::Result::Ok(_) => {}
+            }
+        }
+    });
+
+    quote!(#(#encodes)*)
+}
+
+fn decode_fields(fields: &Fields, bindings: &[syn::Ident]) -> TokenStream2 {
+    let decodes = fields.iter().zip(bindings).map(|(field, binding)| {
+        let ty = &field.ty;
+        quote! {
+            let #binding = match <#ty as ::xrpl_hooks::StateCodec>::decode(reader) {
+                ::xrpl_hooks::Result::Ok(value) => value,
+                ::xrpl_hooks::Result::Err(e) => return ::xrpl_hooks::Result::Err(e),
+            };
+        }
+    });
+
+    quote!(#(#decodes)*)
+}
+
+fn struct_codec(fields: &Fields) -> (TokenStream2, TokenStream2, TokenStream2) {
+    let size = fields_size(fields);
+
+    let encode = encode_fields(
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| match &field.ident {
+                Some(ident) => quote!(&self.#ident),
+                None => {
+                    let index = Index::from(i);
+                    quote!(&self.#index)
+                }
+            }),
+    );
+
+    let bindings = field_bindings(fields);
+    let decode_fields = decode_fields(fields, &bindings);
+    let pattern = fields_pattern(fields, &bindings);
+    let decode = quote! {
+        #decode_fields
+        ::xrpl_hooks::Result::Ok(Self #pattern)
+    };
+
+    (size, encode, decode)
+}
+
+fn enum_codec<'a>(
+    name: &syn::Ident,
+    variants: impl Iterator<Item = (&'a syn::Ident, &'a Fields)>,
+) -> (TokenStream2, TokenStream2, TokenStream2) {
+    let mut sizes = Vec::new();
+    let mut encode_arms = Vec::new();
+    let mut decode_arms = Vec::new();
+
+    for (index, (ident, fields)) in variants.enumerate() {
+        let tag = index as u8;
+        let size = fields_size(fields);
+        let bindings = field_bindings(fields);
+        let pattern = fields_pattern(fields, &bindings);
+        let encode = encode_fields(bindings.iter().map(|binding| quote!(#binding)));
+        let decode = decode_fields(fields, &bindings);
+
+        encode_arms.push(quote! {
+            Self::#ident #pattern => {
+                match writer.write_u8(#tag) {
+                    ::xrpl_hooks::Result::Err(e) => return ::xrpl_hooks::Result::Err(e),
+                    ::xrpl_hooks::Result::Ok(_) => {}
+                }
+                #encode
+                // zeroes up to the largest variant
+                match writer.write_bytes(
+                    &[0u8; <#name as ::xrpl_hooks::StateCodec>::SIZE - 1 - (#size)],
+                ) {
+                    ::xrpl_hooks::Result::Err(e) => return ::xrpl_hooks::Result::Err(e),
+                    ::xrpl_hooks::Result::Ok(_) => {}
+                }
+            }
+        });
+
+        decode_arms.push(quote! {
+            ::xrpl_hooks::Result::Ok(#tag) => {
+                #decode
+                (Self::#ident #pattern, #size)
+            }
+        });
+
+        sizes.push(size);
+    }
+
+    // the largest variant, evaluated at compile time
+    let size = quote! {
+        1 + {
+            let mut max = 0;
+            #(
+                if #sizes > max {
+                    max = #sizes;
+                }
+            )*
+            max
+        }
+    };
+
+    let max_fields_size = quote!(<Self as ::xrpl_hooks::StateCodec>::SIZE - 1);
+
+    let encode = quote! {
+        match self {
+            #(#encode_arms)*
+        }
+    };
+
+    let decode = quote! {
+        let (value, size) = match reader.read_u8() {
+            #(#decode_arms)*
+            ::xrpl_hooks::Result::Ok(_) => {
+                return ::xrpl_hooks::Result::Err(::xrpl_hooks::Error::InvalidArgument)
+            }
+            ::xrpl_hooks::Result::Err(e) => return ::xrpl_hooks::Result::Err(e),
+        };
+        match reader.skip(#max_fields_size - size) {
+            ::xrpl_hooks::Result::Ok(_) => ::xrpl_hooks::Result::Ok(value),
+            ::xrpl_hooks::Result::Err(e) => ::xrpl_hooks::Result::Err(e),
+        }
+    };
+
+    (size, encode, decode)
+}
@@ -1,4 +1,12 @@
 #![allow(non_upper_case_globals)]
 #![allow(missing_docs)]
 
+/// Address of hook memory handed to the host, 32 bits on wasm32
+///
+/// Pointer-width in tests, so the test host gets the addresses of the native build.
+#[cfg(not(test))]
+pub type Ptr = u32;
+#[cfg(test)]
+pub type Ptr = usize;
+
 include!("../c_bindings/bindings.rs");
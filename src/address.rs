@@ -0,0 +1,256 @@
+use crate::api::*;
+
+/// Longest classic address
+pub const CLASSIC_ADDRESS_MAX_LEN: usize = 35;
+
+const ALPHABET: &[u8; 58] = b"rpshnaf39wBUDNEGHJKLM4PQRST7VWXYZ2bcdeCg65jkm8oFqi1tuvAxyz";
+
+/// Account id type prefix
+const ACCOUNT_ID_PREFIX: u8 = 0;
+
+/// Prefix, account id and checksum
+const PAYLOAD_LEN: usize = 1 + ACC_ID_LEN + 4;
+
+/// Encode an account id as a classic address (`r...`) into `out`, returns the lenght
+///
+/// Returns [Error::TooSmall] if `out` is shorter than the address,
+/// [CLASSIC_ADDRESS_MAX_LEN] always fits.
+pub fn encode_classic_address(account: &AccountId, out: &mut [u8]) -> Result<usize> {
+    let mut payload = [0; PAYLOAD_LEN];
+    payload[0] = ACCOUNT_ID_PREFIX;
+    payload[1..1 + ACC_ID_LEN].copy_from_slice(account);
+    let checksum = checksum(&payload[..1 + ACC_ID_LEN]);
+    payload[1 + ACC_ID_LEN..].copy_from_slice(&checksum);
+
+    // base58 digits, least significant first
+    let mut digits = [0u8; CLASSIC_ADDRESS_MAX_LEN];
+    let mut digits_len = 0;
+
+    for &byte in payload.iter() {
+        let mut carry = byte as u32;
+        for digit in digits[..digits_len].iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits[digits_len] = (carry % 58) as u8;
+            digits_len += 1;
+            carry /= 58;
+        }
+    }
+
+    // leading zero bytes are encoded as leading zero digits
+    let zeros = payload.iter().take_while(|&&byte| byte == 0).count();
+    let len = zeros + digits_len;
+
+    if out.len() < len {
+        return Err(Error::TooSmall);
+    }
+
+    for c in out[..zeros].iter_mut() {
+        *c = ALPHABET[0];
+    }
+    for (c, &digit) in out[zeros..len]
+        .iter_mut()
+        .zip(digits[..digits_len].iter().rev())
+    {
+        *c = ALPHABET[digit as usize];
+    }
+
+    Ok(len)
+}
+
+/// Decode a classic address (`r...`) into an account id
+///
+/// Returns [Error::InvalidAccount] for a malformed address or a wrong checksum.
+pub fn decode_classic_address(address: &[u8]) -> Result<AccountId> {
+    if address.is_empty() || address.len() > CLASSIC_ADDRESS_MAX_LEN {
+        return Err(Error::InvalidAccount);
+    }
+
+    // bytes, least significant first
+    let mut bytes = [0u8; PAYLOAD_LEN];
+    let mut bytes_len = 0;
+
+    for &c in address.iter() {
+        let mut carry = match ALPHABET.iter().position(|&a| a == c) {
+            Some(digit) => digit as u32,
+            None => return Err(Error::InvalidAccount),
+        };
+        for byte in bytes[..bytes_len].iter_mut() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            if bytes_len == PAYLOAD_LEN {
+                return Err(Error::InvalidAccount);
+            }
+            bytes[bytes_len] = carry as u8;
+            bytes_len += 1;
+            carry >>= 8;
+        }
+    }
+
+    let zeros = address.iter().take_while(|&&c| c == ALPHABET[0]).count();
+
+    if zeros + bytes_len != PAYLOAD_LEN {
+        return Err(Error::InvalidAccount);
+    }
+
+    let mut payload = [0; PAYLOAD_LEN];
+    for (dst, &byte) in payload[zeros..]
+        .iter_mut()
+        .zip(bytes[..bytes_len].iter().rev())
+    {
+        *dst = byte;
+    }
+
+    if payload[0] != ACCOUNT_ID_PREFIX
+        || checksum(&payload[..1 + ACC_ID_LEN]) != payload[1 + ACC_ID_LEN..]
+    {
+        return Err(Error::InvalidAccount);
+    }
+
+    let mut account = [0; ACC_ID_LEN];
+    account.copy_from_slice(&payload[1..1 + ACC_ID_LEN]);
+
+    Ok(account)
+}
+
+/// First four bytes of double SHA-256
+fn checksum(data: &[u8]) -> [u8; 4] {
+    let hash = sha256(&sha256(data));
+
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// SHA-256 of a short message, at most 55 bytes (a single block)
+fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    block[..data.len()].copy_from_slice(data);
+    block[data.len()] = 0x80;
+    block[56..].copy_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    let mut w = [0u32; 64];
+    for i in 0..16 {
+        w[i] = u32::from_be_bytes([
+            block[4 * i],
+            block[4 * i + 1],
+            block[4 * i + 2],
+            block[4 * i + 3],
+        ]);
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = hh
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+
+        hh = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    for (h, v) in h.iter_mut().zip([a, b, c, d, e, f, g, hh].iter()) {
+        *h = h.wrapping_add(*v);
+    }
+
+    let mut hash = [0; 32];
+    for (chunk, h) in hash.chunks_mut(4).zip(h.iter()) {
+        chunk.copy_from_slice(&h.to_be_bytes());
+    }
+
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENESIS: AccountId = [
+        0xB5, 0xF7, 0x62, 0x79, 0x8A, 0x53, 0xD5, 0x43, 0xA0, 0x14, 0xCA, 0xF8, 0xB2, 0x97, 0xCF,
+        0xF8, 0xF2, 0xF9, 0x37, 0xE8,
+    ];
+
+    #[test]
+    fn sha256_abc() {
+        assert_eq!(
+            sha256(b"abc"),
+            [
+                0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae,
+                0x22, 0x23, 0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61,
+                0xf2, 0x00, 0x15, 0xad
+            ]
+        );
+    }
+
+    #[test]
+    fn classic_address() {
+        let mut out = [0; CLASSIC_ADDRESS_MAX_LEN];
+
+        let vectors: [(AccountId, &[u8]); 3] = [
+            (GENESIS, b"rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh"),
+            ([0; ACC_ID_LEN], b"rrrrrrrrrrrrrrrrrrrrrhoLvTp"),
+            (
+                {
+                    let mut one = [0; ACC_ID_LEN];
+                    one[ACC_ID_LEN - 1] = 1;
+                    one
+                },
+                b"rrrrrrrrrrrrrrrrrrrrBZbvji",
+            ),
+        ];
+
+        for (account, address) in vectors.iter() {
+            let len = match encode_classic_address(account, &mut out) {
+                Ok(len) => len,
+                Err(_) => panic!("encoding failed"),
+            };
+            assert_eq!(&out[..len], *address);
+            assert!(matches!(decode_classic_address(address), Ok(decoded) if decoded == *account));
+        }
+
+        assert!(decode_classic_address(b"rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTi").is_err());
+        assert!(decode_classic_address(b"rHb9CJAWyB4rj91VRWn96DkukG4bwdty0h").is_err());
+        assert!(encode_classic_address(&GENESIS, &mut out[..20]).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+use crate::api::*;
+use crate::codec::u64_from_buf;
+
+/// Serialized XRP amount lenght
+pub const XRP_AMOUNT_LEN: usize = 8;
+
+/// Serialized amount
+///
+/// XRP in drops or an issued currency value with its currency and issuer.
+#[derive(Clone, Copy)]
+pub enum TypedAmount {
+    /// Native amount in drops
+    Xrp(u64),
+    /// Issued currency amount
+    Iou {
+        /// Value, the serialized value is XFL apart from the "not XRP" bit
+        value: XFL,
+        /// Currency code
+        currency: CurrencyCode,
+        /// Issuer account
+        issuer: AccountId,
+    },
+}
+
+impl TypedAmount {
+    /// Decode a serialized amount, as output by `otxn_field`, `slot` or `sto_subfield`
+    ///
+    /// Returns [Error::NotAnAmount] for a wrong lenght
+    /// and [Error::CantReturnNegative] for a negative XRP amount.
+    #[inline(always)]
+    pub fn from_sto(sto: &[u8]) -> Result<Self> {
+        if sto.len() < XRP_AMOUNT_LEN {
+            return Err(Error::NotAnAmount);
+        }
+
+        let raw = u64_from_buf(&[
+            sto[0], sto[1], sto[2], sto[3], sto[4], sto[5], sto[6], sto[7],
+        ]);
+
+        // "not XRP" bit
+        if raw >> 63 == 0 {
+            let drops = raw & 0x3FFF_FFFF_FFFF_FFFF;
+
+            // positive bit
+            if (raw >> 62) & 1 == 0 && drops != 0 {
+                return Err(Error::CantReturnNegative);
+            }
+
+            return Ok(TypedAmount::Xrp(drops));
+        }
+
+        if sto.len() < AMOUNT_LEN {
+            return Err(Error::NotAnAmount);
+        }
+
+        let mut currency = [0; CURRENCY_CODE_SIZE];
+        let mut issuer = [0; ACC_ID_LEN];
+
+        currency.clone_from_slice(&sto[XRP_AMOUNT_LEN..XRP_AMOUNT_LEN + CURRENCY_CODE_SIZE]);
+        issuer.clone_from_slice(&sto[XRP_AMOUNT_LEN + CURRENCY_CODE_SIZE..AMOUNT_LEN]);
+
+        Ok(TypedAmount::Iou {
+            value: XFL((raw & 0x7FFF_FFFF_FFFF_FFFF) as i64),
+            currency,
+            issuer,
+        })
+    }
+
+    /// Returns `true` for XRP
+    #[inline(always)]
+    pub const fn is_xrp(&self) -> bool {
+        matches!(self, TypedAmount::Xrp(_))
+    }
+
+    /// Drops of an XRP amount
+    #[inline(always)]
+    pub const fn drops(&self) -> Option<u64> {
+        match self {
+            TypedAmount::Xrp(drops) => Some(*drops),
+            TypedAmount::Iou { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xrp() {
+        let sto = [0x40, 0, 0, 0, 0, 0x0F, 0x42, 0x40];
+
+        assert!(matches!(
+            TypedAmount::from_sto(&sto),
+            Ok(TypedAmount::Xrp(1_000_000))
+        ));
+        assert!(TypedAmount::from_sto(&[0x00, 0, 0, 0, 0, 0, 0, 1]).is_err());
+        assert!(TypedAmount::from_sto(&sto[..7]).is_err());
+    }
+
+    #[test]
+    fn iou() {
+        let mut sto = [0; AMOUNT_LEN];
+        // 1 * 10^0 = 1000000000000000 * 10^-15
+        sto[..8].copy_from_slice(&[0xD4, 0x83, 0x8D, 0x7E, 0xA4, 0xC6, 0x80, 0x00]);
+        sto[8 + 12..8 + 15].copy_from_slice(b"USD");
+        sto[28..].copy_from_slice(&[7; 20]);
+
+        match TypedAmount::from_sto(&sto) {
+            Ok(TypedAmount::Iou {
+                value,
+                currency,
+                issuer,
+            }) => {
+                assert_eq!(value.0, 0x5483_8D7E_A4C6_8000);
+                assert_eq!(&currency[12..15], b"USD");
+                assert_eq!(issuer, [7; 20]);
+            }
+            _ => panic!("not an IOU"),
+        }
+
+        assert!(TypedAmount::from_sto(&sto[..8]).is_err());
+    }
+}
@@ -6,6 +6,9 @@ use crate::_c;
 /// the first branch instruction in wasm binary after the beginning of the loop.
 /// In order to achieve this in Rust use the `while` loop with expression block.
 ///
+/// Prefer the [guard!](crate::guard) and [guarded_for!](crate::guarded_for) macros,
+/// they derive a unique guard id for you.
+///
 /// # Example
 ///
 /// ```no_run
@@ -26,11 +29,44 @@ pub fn _g(id: u32, maxiter: u32) {
     }
 }
 
+/// Compute a guard id from a source location
+///
+/// The file path is hashed (FNV-1a) and mixed with the line and the column,
+/// so two different call sites most likely get two different ids. Nothing detects
+/// a collision of two hashes, see [guard_id!](crate::guard_id).
+/// Must be evaluated in a const context, otherwise the loop inside
+/// would end up in the wasm binary unguarded.
+/// Use the [guard_id!](crate::guard_id) macro which takes care of this.
+#[inline(always)]
+pub const fn location_guard_id(file: &str, line: u32, column: u32) -> u32 {
+    const FNV_OFFSET: u32 = 0x811c_9dc5;
+    const FNV_PRIME: u32 = 0x0100_0193;
+
+    let file = file.as_bytes();
+    let mut hash = FNV_OFFSET;
+
+    let mut i = 0;
+    while i < file.len() {
+        hash ^= file[i] as u32;
+        hash = hash.wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+
+    // line and column are mixed in as whole words,
+    // so (line, column) pairs can't overlap within a file
+    hash ^= line;
+    hash = hash.wrapping_mul(FNV_PRIME);
+    hash ^= column;
+    hash = hash.wrapping_mul(FNV_PRIME);
+
+    hash
+}
+
 /// Accept the originating transaction and commit any changes the hook made
 #[inline(always)]
 pub fn accept(msg: &[u8], error_code: i64) -> ! {
     unsafe {
-        _c::accept(msg.as_ptr() as u32, msg.len() as u32, error_code);
+        _c::accept(msg.as_ptr() as _, msg.len() as u32, error_code);
         core::hint::unreachable_unchecked()
     }
 }
@@ -39,7 +75,76 @@ pub fn accept(msg: &[u8], error_code: i64) -> ! {
 #[inline(always)]
 pub fn rollback(msg: &[u8], error_code: i64) -> ! {
     unsafe {
-        _c::rollback(msg.as_ptr() as u32, msg.len() as u32, error_code);
+        _c::rollback(msg.as_ptr() as _, msg.len() as u32, error_code);
         core::hint::unreachable_unchecked()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID_A: u32 = location_guard_id("src/lib.rs", 10, 5);
+    const ID_B: u32 = location_guard_id("src/lib.rs", 10, 6);
+    const ID_C: u32 = location_guard_id("src/lib.rs", 11, 5);
+    const ID_D: u32 = location_guard_id("src/hook.rs", 10, 5);
+
+    #[test]
+    fn guard_id_is_location_unique() {
+        assert_ne!(ID_A, ID_B);
+        assert_ne!(ID_A, ID_C);
+        assert_ne!(ID_A, ID_D);
+        assert_ne!(ID_B, ID_C);
+    }
+
+    #[test]
+    fn guard_id_macro_differs_per_call_site() {
+        let a = crate::guard_id!();
+        let b = crate::guard_id!();
+        let (c, d) = (crate::guard_id!(), crate::guard_id!());
+
+        assert_ne!(a, b);
+        assert_ne!(c, d);
+    }
+
+    #[test]
+    fn guarded_for_stays_within_budget() {
+        let mut buf = [1u8; 8];
+        let mut visited = 0;
+
+        crate::guarded_for!(i in 0..buf.len(), max = 8, {
+            if i == 3 {
+                continue;
+            }
+            buf[i] = 0;
+            visited += 1;
+        });
+
+        assert_eq!(buf, [0, 0, 0, 1, 0, 0, 0, 0]);
+        assert_eq!(visited, 7);
+        // 8 iterations plus the final check
+        assert_eq!(crate::testing::guard_calls(), 9);
+        assert!(!crate::testing::guard_violated());
+    }
+
+    #[test]
+    fn guard_with_explicit_id() {
+        let mut i = 0;
+        while {
+            crate::guard!(7, 3);
+            i < 3
+        } {
+            i += 1;
+        }
+
+        assert_eq!(crate::testing::guard_calls_of(7), 4);
+        assert!(!crate::testing::guard_violated());
+    }
+
+    #[test]
+    fn guarded_for_over_budget_violates_guard() {
+        crate::guarded_for!(_i in 0..5, max = 3, {});
+
+        assert!(crate::testing::guard_violated());
+    }
+}
@@ -13,11 +13,21 @@ pub fn etxn_details(emitdet: &mut [u8]) -> Result<u64> {
 }
 
 /// Estimate the required fee for a txn to be emitted successfully
+#[cfg(not(feature = "api-v2"))]
 #[inline(always)]
 pub fn etxn_fee_base(tx_byte_count: u32) -> Result<u64> {
     api_1arg_call(tx_byte_count, _c::etxn_fee_base)
 }
 
+/// Estimate the required fee for a txn to be emitted successfully
+///
+/// `txn` is the whole serialized transaction.
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn etxn_fee_base(txn: &[u8]) -> Result<u64> {
+    buf_read(txn, _c::etxn_fee_base)
+}
+
 /// Estimate the required fee for a txn to be emitted successfully
 #[inline(always)]
 pub fn etxn_reserve(count: u32) -> Result<u64> {
@@ -30,8 +40,30 @@ pub fn etxn_generation() -> i64 {
     unsafe { _c::etxn_generation() }
 }
 
+/// Generate a 32 byte nonce for use in an emitted transaction
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn etxn_nonce(n: &mut [u8]) -> Result<u64> {
+    buf_write(n, _c::etxn_nonce)
+}
+
 /// Emit a new transaction from the hook
 #[inline(always)]
 pub fn emit(hash: &mut [u8], tx_buf: &[u8]) -> Result<u64> {
     buf_write_read(hash, tx_buf, _c::emit)
 }
+
+#[cfg(all(test, feature = "api-v2"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn etxn_nonces() {
+        let mut first = [0; HASH_LEN];
+        let mut second = [0; HASH_LEN];
+
+        assert!(matches!(etxn_nonce(&mut first), Ok(32)));
+        assert!(matches!(etxn_nonce(&mut second), Ok(32)));
+        assert_ne!(first, second);
+    }
+}
@@ -2,7 +2,7 @@ use super::*;
 
 /// XFL floating point numbers
 #[derive(Clone, Copy)]
-pub struct XFL(pub(super) i64 /* enclosing number */);
+pub struct XFL(pub(crate) i64 /* enclosing number */);
 
 /// Create a float from an exponent and mantissa
 #[inline(always)]
@@ -131,6 +131,24 @@ pub fn float_divide(float1: XFL, float2: XFL) -> Result<XFL> {
     result_xfl(res)
 }
 
+/// Compute the nth root of an XFL floating point number
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn float_root(float: XFL, n: u32) -> Result<XFL> {
+    let res = unsafe { _c::float_root(float.0, n) };
+
+    result_xfl(res)
+}
+
+/// Compute the decimal log of an XFL floating point number
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn float_log(float: XFL) -> Result<XFL> {
+    let res = unsafe { _c::float_log(float.0) };
+
+    result_xfl(res)
+}
+
 /// Return the number 1 represented in an XFL enclosing number
 #[inline(always)]
 pub fn float_one() -> XFL {
@@ -188,3 +206,25 @@ pub fn float_int(float: XFL, decimal_places: u32, absolute: bool) -> Result<u64>
 
     result_u64(res)
 }
+
+#[cfg(all(test, feature = "api-v2"))]
+mod tests {
+    use super::*;
+    use crate::testing::xfl_from_f64;
+
+    #[test]
+    fn roots_and_logs() {
+        let nine = XFL(xfl_from_f64(9.0));
+
+        assert!(matches!(float_root(nine, 2), Ok(root) if root.0 == xfl_from_f64(3.0)));
+        assert!(matches!(float_root(nine, 0), Err(Error::InvalidArgument)));
+        assert!(matches!(
+            float_root(XFL(xfl_from_f64(-8.0)), 3),
+            Err(Error::ComplexNotSupported)
+        ));
+        assert!(
+            matches!(float_log(XFL(xfl_from_f64(1000.0))), Ok(log) if log.0 == xfl_from_f64(3.0))
+        );
+        assert!(matches!(float_log(XFL(0)), Err(Error::InvalidArgument)));
+    }
+}
@@ -12,6 +12,49 @@ pub fn hook_hash(hash: &mut [u8]) -> Result<u64> {
     buf_write(hash, _c::hook_hash)
 }
 
+/// Retreive a parameter of the currently executing Hook, set by SetHook or by [hook_param_set]
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn hook_param(value: &mut [u8], name: &[u8]) -> Result<u64> {
+    buf_write_read(value, name, _c::hook_param)
+}
+
+/// Set a parameter of a Hook later in the execution chain, for this execution only
+///
+/// An empty value deletes the parameter.
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn hook_param_set(value: &[u8], name: &[u8], hook_hash: &[u8]) -> Result<u64> {
+    buf_3_read(value, name, hook_hash, _c::hook_param_set)
+}
+
+/// Skip a Hook later in the execution chain, or stop skipping it, for this execution only
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn hook_skip(hook_hash: &[u8], skip: bool) -> Result<u64> {
+    let flags = if skip { 0 } else { 1 };
+
+    let res = unsafe { _c::hook_skip(hook_hash.as_ptr() as _, hook_hash.len() as u32, flags) };
+
+    result_u64(res)
+}
+
+/// Request a second, weak, execution of the Hook after the originating transaction is applied
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn hook_again() -> Result<u64> {
+    let res = unsafe { _c::hook_again() };
+
+    result_u64(res)
+}
+
+/// Fetch the position of the currently executing Hook in the Hook chain of the account
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn hook_pos() -> i64 {
+    unsafe { _c::hook_pos() }
+}
+
 /// Fetch the fee base of the current ledger
 #[inline(always)]
 pub fn fee_base() -> i64 {
@@ -31,7 +74,110 @@ pub fn ledger_last_hash(hash: &mut [u8]) -> Result<u64> {
 }
 
 /// Generate a 32 byte nonce for use in an emitted transaction
+#[cfg(not(feature = "api-v2"))]
 #[inline(always)]
 pub fn nonce(n: &mut [u8]) -> Result<u64> {
     buf_write(n, _c::nonce)
 }
+
+/// Fetch the close time of the last closed ledger, in seconds since the ripple epoch
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn ledger_last_time() -> i64 {
+    unsafe { _c::ledger_last_time() }
+}
+
+/// Generate a 32 byte nonce from the last closed ledger
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn ledger_nonce(n: &mut [u8]) -> Result<u64> {
+    buf_write(n, _c::ledger_nonce)
+}
+
+/// Find the first ledger object with a keylet between `low` and `high` and write its keylet
+///
+/// Both bounds must be of the same keylet type, [Error::DoesntExist] if there is no such object.
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn ledger_keylet(keylet: &mut [u8], low: &[u8], high: &[u8]) -> Result<u64> {
+    let res = unsafe {
+        _c::ledger_keylet(
+            keylet.as_mut_ptr() as _,
+            keylet.len() as u32,
+            low.as_ptr() as _,
+            low.len() as u32,
+            high.as_ptr() as _,
+            high.len() as u32,
+        )
+    };
+
+    result_u64(res)
+}
+
+#[cfg(all(test, feature = "api-v2"))]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn hook_chain() {
+        testing::host(|host| {
+            host.hook_pos = 2;
+            host.ledger_last_time = 750_000_000;
+        });
+
+        assert_eq!(hook_pos(), 2);
+        assert_eq!(ledger_last_time(), 750_000_000);
+        assert!(matches!(hook_again(), Ok(1)));
+        assert!(matches!(hook_again(), Err(Error::AlreadySet)));
+    }
+
+    #[test]
+    fn ledger_nonces() {
+        let mut first = [0; HASH_LEN];
+        let mut second = [0; HASH_LEN];
+
+        assert!(matches!(ledger_nonce(&mut first), Ok(32)));
+        assert!(matches!(ledger_nonce(&mut second), Ok(32)));
+        assert_ne!(first, second);
+        assert!(matches!(
+            ledger_nonce(&mut first[..16]),
+            Err(Error::TooSmall)
+        ));
+    }
+
+    fn offer_keylet(first: u8) -> Keylet {
+        let mut keylet = [first; KEYLET_LEN];
+        keylet[0] = 0;
+        keylet[1] = 0x6F;
+        keylet
+    }
+
+    #[test]
+    fn ledger_keylets() {
+        testing::host(|host| {
+            host.insert_object(&offer_keylet(0x10), [].to_vec());
+            host.insert_object(&offer_keylet(0x30), [].to_vec());
+            host.insert_object(&offer_keylet(0x20), [].to_vec());
+        });
+
+        let mut keylet = [0; KEYLET_LEN];
+
+        assert!(matches!(
+            ledger_keylet(&mut keylet, &offer_keylet(0x15), &offer_keylet(0xFF)),
+            Ok(34)
+        ));
+        assert_eq!(keylet, offer_keylet(0x20));
+        assert!(matches!(
+            ledger_keylet(&mut keylet, &offer_keylet(0x31), &offer_keylet(0xFF)),
+            Err(Error::DoesntExist)
+        ));
+
+        let mut line = offer_keylet(0xFF);
+        line[1] = 0x72;
+        assert!(matches!(
+            ledger_keylet(&mut keylet, &offer_keylet(0x00), &line),
+            Err(Error::InvalidArgument)
+        ));
+    }
+}
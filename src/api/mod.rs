@@ -35,6 +35,12 @@ pub const LEDGER_HASH_LEN: usize = 32;
 pub const KEYLET_LEN: usize = 34;
 /// State key buffer lenght
 pub const STATE_KEY_LEN: usize = 32;
+/// Maximum state data lenght
+pub const STATE_DATA_MAX_LEN: usize = 128;
+/// Maximum hook parameter name lenght
+pub const HOOK_PARAM_NAME_MAX_LEN: usize = 32;
+/// Maximum hook parameter value lenght
+pub const HOOK_PARAM_VALUE_MAX_LEN: usize = 256;
 /// Nonce buffer lenght
 pub const NONCE_LEN: usize = 32;
 /// Hash buffer lenght
@@ -42,9 +48,20 @@ pub const HASH_LEN: usize = 32;
 /// Amount buffer lenght
 pub const AMOUNT_LEN: usize = 48;
 /// Payment simple transaction buffer lenght
+///
+/// Fits the payment with the largest [EMIT_DETAILS_SIZE] details.
 pub const PREPARE_PAYMENT_SIMPLE_SIZE: usize = _c::PREPARE_PAYMENT_SIMPLE_SIZE as _;
 /// Emit details buffer lenght
+#[cfg(not(feature = "api-v2"))]
 pub const EMIT_DETAILS_SIZE: usize = 105;
+/// Emit details buffer lenght, with an `EmitCallback`
+///
+/// Without a callback the details are [EMIT_DETAILS_NO_CALLBACK_SIZE] bytes.
+#[cfg(feature = "api-v2")]
+pub const EMIT_DETAILS_SIZE: usize = 138;
+/// Emit details lenght without an `EmitCallback`
+#[cfg(feature = "api-v2")]
+pub const EMIT_DETAILS_NO_CALLBACK_SIZE: usize = 116;
 
 /// Buffer of the specified size
 pub type Buffer<const T: usize> = [u8; T];
@@ -68,35 +85,190 @@ pub type EmitDetails = Buffer<EMIT_DETAILS_SIZE>;
 /// Currency code buffer
 pub type CurrencyCode = Buffer<CURRENCY_CODE_SIZE>;
 
-/// Transaction type
-#[allow(missing_docs)]
-#[derive(Clone, Copy)]
-#[repr(u32)]
-pub enum TxnType {
-    Payment = _c::ttPAYMENT,
-    EscrowCreate = 1,
-    EscrowFinish = 2,
-    AccountSet = 3,
-    EscrowCancel = 4,
-    RegularKeySet = 5,
-    OfferCreate = 7,
-    OfferCancel = 8,
-    TicketCreate = 10,
-    TicketCancel = 11,
-    SignerListSet = 12,
-    PaychanCreate = 13,
-    PaychanFund = 14,
-    PaychanClaim = 15,
-    CheckCreate = 16,
-    CheckCash = 17,
-    CheckCancel = 18,
-    DepositPreauth = 19,
-    TrustSet = 20,
-    AccountDelete = 21,
-    HookSet = 22,
-    Amendment = 100,
-    Fee = 101,
-    UnlModify = 102,
+/// Longest [TxnType] name, see [TxnType::write_name]
+pub const TXN_TYPE_NAME_MAX_LEN: usize = 32;
+
+macro_rules! txn_types {
+    ($($(#[$meta:meta])* $variant:ident = $code:literal, $name:literal $(if $feature:literal)?;)*) => {
+        /// Transaction type
+        ///
+        /// Converted from a raw code with [TxnType::try_from] or [TxnType::from_code],
+        /// codes unknown to the crate become [TxnType::Unknown].
+        /// The variants follow the host API profile feature.
+        #[allow(missing_docs)]
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        pub enum TxnType {
+            $($(#[cfg(feature = $feature)])? $(#[$meta])* $variant,)*
+            /// Transaction type unknown to the crate
+            Unknown(u16),
+        }
+
+        impl TxnType {
+            /// Transaction type from its code
+            #[inline(always)]
+            pub const fn from_code(code: u16) -> Self {
+                match code {
+                    $($(#[cfg(feature = $feature)])? $code => TxnType::$variant,)*
+                    code => TxnType::Unknown(code),
+                }
+            }
+
+            /// Transaction type code
+            #[inline(always)]
+            pub const fn code(self) -> u16 {
+                match self {
+                    $($(#[cfg(feature = $feature)])? TxnType::$variant => $code,)*
+                    TxnType::Unknown(code) => code,
+                }
+            }
+
+            /// Canonical name of the transaction type, `Unknown` for [TxnType::Unknown]
+            #[inline(always)]
+            pub const fn name(self) -> &'static [u8] {
+                match self {
+                    $($(#[cfg(feature = $feature)])? TxnType::$variant => $name,)*
+                    TxnType::Unknown(_) => b"Unknown",
+                }
+            }
+        }
+    };
+}
+
+txn_types! {
+    Payment = 0, b"Payment";
+    EscrowCreate = 1, b"EscrowCreate";
+    EscrowFinish = 2, b"EscrowFinish";
+    AccountSet = 3, b"AccountSet";
+    EscrowCancel = 4, b"EscrowCancel";
+    RegularKeySet = 5, b"SetRegularKey";
+    OfferCreate = 7, b"OfferCreate";
+    OfferCancel = 8, b"OfferCancel";
+    TicketCreate = 10, b"TicketCreate";
+    TicketCancel = 11, b"TicketCancel";
+    SignerListSet = 12, b"SignerListSet";
+    PaychanCreate = 13, b"PaymentChannelCreate";
+    PaychanFund = 14, b"PaymentChannelFund";
+    PaychanClaim = 15, b"PaymentChannelClaim";
+    CheckCreate = 16, b"CheckCreate";
+    CheckCash = 17, b"CheckCash";
+    CheckCancel = 18, b"CheckCancel";
+    DepositPreauth = 19, b"DepositPreauth";
+    TrustSet = 20, b"TrustSet";
+    AccountDelete = 21, b"AccountDelete";
+    #[doc(alias = "SetHook")]
+    HookSet = 22, b"SetHook";
+    NFTokenMint = 25, b"NFTokenMint" if "api-v2";
+    NFTokenBurn = 26, b"NFTokenBurn" if "api-v2";
+    NFTokenCreateOffer = 27, b"NFTokenCreateOffer" if "api-v2";
+    NFTokenCancelOffer = 28, b"NFTokenCancelOffer" if "api-v2";
+    NFTokenAcceptOffer = 29, b"NFTokenAcceptOffer" if "api-v2";
+    Clawback = 30, b"Clawback" if "api-v2";
+    AMMCreate = 35, b"AMMCreate" if "api-v2";
+    AMMDeposit = 36, b"AMMDeposit" if "api-v2";
+    AMMWithdraw = 37, b"AMMWithdraw" if "api-v2";
+    AMMVote = 38, b"AMMVote" if "api-v2";
+    AMMBid = 39, b"AMMBid" if "api-v2";
+    AMMDelete = 40, b"AMMDelete" if "api-v2";
+    URITokenMint = 45, b"URITokenMint" if "xahau";
+    URITokenBurn = 46, b"URITokenBurn" if "xahau";
+    URITokenBuy = 47, b"URITokenBuy" if "xahau";
+    URITokenCreateSellOffer = 48, b"URITokenCreateSellOffer" if "xahau";
+    URITokenCancelSellOffer = 49, b"URITokenCancelSellOffer" if "xahau";
+    Remit = 95, b"Remit" if "xahau";
+    GenesisMint = 96, b"GenesisMint" if "xahau";
+    Import = 97, b"Import" if "xahau";
+    ClaimReward = 98, b"ClaimReward" if "xahau";
+    Invoke = 99, b"Invoke" if "xahau";
+    Amendment = 100, b"EnableAmendment";
+    Fee = 101, b"SetFee";
+    UnlModify = 102, b"UNLModify";
+    EmitFailure = 103, b"EmitFailure";
+    UnlReport = 104, b"UNLReport" if "xahau";
+}
+
+impl TxnType {
+    /// Write the name into `buf` for tracing, returns the written lenght
+    ///
+    /// [TxnType::Unknown] is written with its code: `Unknown(1234)`.
+    /// A buffer of [TXN_TYPE_NAME_MAX_LEN] always fits,
+    /// otherwise returns [Error::TooSmall] if the name doesn't fit.
+    ///
+    /// Pay attention to the GUARD_ID parameter.
+    /// This should be unique on every call, through the entire hook code.
+    /// Otherwise you will encounter guard violation during the execution of your hook.
+    #[inline(always)]
+    pub fn write_name<const GUARD_ID: u32>(self, buf: &mut [u8]) -> Result<usize> {
+        let name = self.name();
+        let len = name.len();
+        let code = self.code();
+
+        let suffix_len = match self {
+            TxnType::Unknown(_) => match code {
+                0..=9 => 3,
+                10..=99 => 4,
+                100..=999 => 5,
+                1000..=9999 => 6,
+                _ => 7,
+            },
+            _ => 0,
+        };
+
+        if buf.len() < len + suffix_len {
+            return Err(Error::TooSmall);
+        }
+
+        // guarded loop
+        let mut i = 0;
+        while {
+            _g(GUARD_ID, TXN_TYPE_NAME_MAX_LEN as u32 + 1);
+            i < len
+        } {
+            buf[i] = name[i];
+            i += 1;
+        }
+
+        if suffix_len == 0 {
+            return Ok(len);
+        }
+
+        let mut pos = len;
+        buf[pos] = b'(';
+        pos += 1;
+        if code >= 10000 {
+            buf[pos] = b'0' + (code / 10000 % 10) as u8;
+            pos += 1;
+        }
+        if code >= 1000 {
+            buf[pos] = b'0' + (code / 1000 % 10) as u8;
+            pos += 1;
+        }
+        if code >= 100 {
+            buf[pos] = b'0' + (code / 100 % 10) as u8;
+            pos += 1;
+        }
+        if code >= 10 {
+            buf[pos] = b'0' + (code / 10 % 10) as u8;
+            pos += 1;
+        }
+        buf[pos] = b'0' + (code % 10) as u8;
+        buf[pos + 1] = b')';
+
+        Ok(pos + 2)
+    }
+}
+
+impl core::convert::TryFrom<i64> for TxnType {
+    type Error = Error;
+
+    /// Codes out of the `u16` range, like error codes, are [Error::InvalidArgument]
+    #[inline(always)]
+    fn try_from(code: i64) -> core::result::Result<Self, Self::Error> {
+        if code < 0 || code > u16::MAX as i64 {
+            return core::result::Result::Err(Error::InvalidArgument);
+        }
+
+        core::result::Result::Ok(TxnType::from_code(code as u16))
+    }
 }
 
 /// Account type
@@ -140,7 +312,10 @@ pub enum AmountType {
 #[derive(Clone, Copy)]
 pub enum KeyletType<'a> {
     Hook(&'a [u8]),
+    #[cfg(not(feature = "api-v2"))]
     HookState(&'a [u8], &'a [u8]),
+    #[cfg(feature = "api-v2")]
+    HookState(&'a [u8], &'a [u8], &'a [u8]),
     Account(&'a [u8]),
     Amendments,
     Child(&'a [u8]),
@@ -160,6 +335,14 @@ pub enum KeyletType<'a> {
     Escrow(&'a [u8], u32),
     Paychan(&'a [u8], &'a [u8], u32),
     Emitted(&'a [u8]),
+    #[cfg(feature = "api-v2")]
+    NftOffer(&'a [u8], u32),
+    #[cfg(feature = "api-v2")]
+    HookDefinition(&'a [u8]),
+    #[cfg(feature = "api-v2")]
+    HookStateDir(&'a [u8], &'a [u8]),
+    #[cfg(feature = "xahau")]
+    UriToken(&'a [u8], &'a [u8]),
 }
 
 /// Field or amount type
@@ -298,6 +481,9 @@ pub enum FieldId {
     MinimumOffer = _c::sfMinimumOffer,
     RippleEscrow = _c::sfRippleEscrow,
     DeliveredAmount = _c::sfDeliveredAmount,
+    BaseFeeDrops = _c::sfBaseFeeDrops,
+    ReserveBaseDrops = _c::sfReserveBaseDrops,
+    ReserveIncrementDrops = _c::sfReserveIncrementDrops,
     PublicKey = _c::sfPublicKey,
     MessageKey = _c::sfMessageKey,
     SigningPubKey = _c::sfSigningPubKey,
@@ -526,6 +712,42 @@ pub enum Error {
     NotAnAmount = -32,
     /// An API would have returned a negative integer except that negative integers are reserved for error codes (i.e. what you are reading.)
     CantReturnNegative = -33,
+    /// The Hook is not allowed to do this, a foreign state write without a HookGrant for example.
+    #[cfg(feature = "api-v2")]
+    NotAuthorized = -34,
+    /// A previous failure of the same operation in this execution prevents retrying it.
+    #[cfg(feature = "api-v2")]
+    PreviousFailurePreventsRetry = -35,
+    /// Too many hook parameters were set with [hook_param_set].
+    #[cfg(feature = "api-v2")]
+    TooManyParams = -36,
+    /// The serialized transaction is invalid.
+    #[cfg(feature = "api-v2")]
+    InvalidTxn = -37,
+    /// The account doesn't have the reserve for the new state entries.
+    #[cfg(feature = "api-v2")]
+    ReserveInsufficient = -38,
+    /// The API doesn't support this complex, nested, field.
+    #[cfg(feature = "api-v2")]
+    ComplexNotSupported = -39,
+    /// Two arguments expected to match, two keylets of the same type for example, did not.
+    #[cfg(feature = "api-v2")]
+    DoesNotMatch = -40,
+    /// The provided key is invalid.
+    #[cfg(feature = "api-v2")]
+    InvalidKey = -41,
+    /// The buffer is not a valid string.
+    #[cfg(feature = "api-v2")]
+    NotAString = -42,
+    /// The write buffer overlaps with a read buffer.
+    #[cfg(feature = "xahau")]
+    MemOverlap = -43,
+    /// The Hook modified more state entries than allowed in one execution.
+    #[cfg(feature = "xahau")]
+    TooManyStateModifications = -44,
+    /// The account has more state namespaces than allowed.
+    #[cfg(feature = "xahau")]
+    TooManyNamespaces = -45,
 }
 
 impl Error {
@@ -542,17 +764,14 @@ impl Error {
 }
 
 type Api1ArgsU32 = unsafe extern "C" fn(u32) -> i64;
-type Api2ArgsU32 = unsafe extern "C" fn(u32, u32) -> i64;
 type Api3ArgsU32 = unsafe extern "C" fn(u32, u32, u32) -> i64;
-type Api4ArgsU32 = unsafe extern "C" fn(u32, u32, u32, u32) -> i64;
-type Api6ArgsU32 = unsafe extern "C" fn(u32, u32, u32, u32, u32, u32) -> i64;
 
-type BufWriter = Api2ArgsU32;
-type BufReader = Api2ArgsU32;
-type Buf2Reader = Api4ArgsU32;
-type BufWriterReader = Api4ArgsU32;
-type Buf3Reader = Api6ArgsU32;
-type BufWriter1Arg = Api3ArgsU32;
+type BufWriter = unsafe extern "C" fn(_c::Ptr, u32) -> i64;
+type BufReader = BufWriter;
+type Buf2Reader = unsafe extern "C" fn(_c::Ptr, u32, _c::Ptr, u32) -> i64;
+type BufWriterReader = Buf2Reader;
+type Buf3Reader = unsafe extern "C" fn(_c::Ptr, u32, _c::Ptr, u32, _c::Ptr, u32) -> i64;
+type BufWriter1Arg = unsafe extern "C" fn(_c::Ptr, u32, u32) -> i64;
 
 #[inline(always)]
 fn api_1arg_call(arg: u32, fun: Api1ArgsU32) -> Result<u64> {
@@ -570,21 +789,21 @@ fn api_3arg_call(arg_1: u32, arg_2: u32, arg_3: u32, fun: Api3ArgsU32) -> Result
 
 #[inline(always)]
 fn buf_write(buf_write: &mut [u8], fun: BufWriter) -> Result<u64> {
-    let res = unsafe { fun(buf_write.as_mut_ptr() as u32, buf_write.len() as u32) };
+    let res = unsafe { fun(buf_write.as_mut_ptr() as _, buf_write.len() as u32) };
 
     result_u64(res)
 }
 
 #[inline(always)]
 fn buf_write_1arg(buf_write: &mut [u8], arg: u32, fun: BufWriter1Arg) -> Result<u64> {
-    let res = unsafe { fun(buf_write.as_mut_ptr() as u32, buf_write.len() as u32, arg) };
+    let res = unsafe { fun(buf_write.as_mut_ptr() as _, buf_write.len() as u32, arg) };
 
     result_u64(res)
 }
 
 #[inline(always)]
 fn buf_read(buf: &[u8], fun: BufReader) -> Result<u64> {
-    let res = unsafe { fun(buf.as_ptr() as u32, buf.len() as u32) };
+    let res = unsafe { fun(buf.as_ptr() as _, buf.len() as u32) };
 
     result_u64(res)
 }
@@ -593,9 +812,9 @@ fn buf_read(buf: &[u8], fun: BufReader) -> Result<u64> {
 fn buf_2read(buf_1: &[u8], buf_2: &[u8], fun: Buf2Reader) -> Result<u64> {
     let res = unsafe {
         fun(
-            buf_1.as_ptr() as u32,
+            buf_1.as_ptr() as _,
             buf_1.len() as u32,
-            buf_2.as_ptr() as u32,
+            buf_2.as_ptr() as _,
             buf_2.len() as u32,
         )
     };
@@ -607,9 +826,9 @@ fn buf_2read(buf_1: &[u8], buf_2: &[u8], fun: Buf2Reader) -> Result<u64> {
 fn buf_write_read(buf_write: &mut [u8], buf_read: &[u8], fun: BufWriterReader) -> Result<u64> {
     let res = unsafe {
         fun(
-            buf_write.as_mut_ptr() as u32,
+            buf_write.as_mut_ptr() as _,
             buf_write.len() as u32,
-            buf_read.as_ptr() as u32,
+            buf_read.as_ptr() as _,
             buf_read.len() as u32,
         )
     };
@@ -626,11 +845,11 @@ fn buf_3_read(
 ) -> Result<u64> {
     let res = unsafe {
         fun(
-            buf_read_1.as_ptr() as u32,
+            buf_read_1.as_ptr() as _,
             buf_read_1.len() as u32,
-            buf_read_2.as_ptr() as u32,
+            buf_read_2.as_ptr() as _,
             buf_read_2.len() as u32,
-            buf_read_3.as_ptr() as u32,
+            buf_read_3.as_ptr() as _,
             buf_read_3.len() as u32,
         )
     };
@@ -701,7 +920,7 @@ fn buf_read_and_1_arg(
             keylet_type_c,
             buf_read.as_ptr() as _,
             buf_read.len() as _,
-            arg,
+            arg as _,
             0,
             0,
             0,
@@ -726,8 +945,8 @@ fn buf_read_and_2_args(
             keylet_type_c,
             buf_read.as_ptr() as _,
             buf_read.len() as _,
-            arg_1,
-            arg_2,
+            arg_1 as _,
+            arg_2 as _,
             0,
             0,
         )
@@ -775,3 +994,79 @@ fn result_xfl(res: i64) -> Result<XFL> {
         _ => Err(Error::from_code(res as _)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::TryFrom;
+
+    #[test]
+    fn txn_type_codes() {
+        let mut code = 0;
+        while code < 256 {
+            let tt = TxnType::from_code(code);
+            assert_eq!(tt.code(), code);
+            code += 1;
+        }
+
+        assert!(matches!(
+            TxnType::try_from(22),
+            core::result::Result::Ok(TxnType::HookSet)
+        ));
+        assert!(matches!(
+            TxnType::try_from(23),
+            core::result::Result::Ok(TxnType::Unknown(23))
+        ));
+        assert!(TxnType::try_from(-1).is_err());
+        assert!(TxnType::try_from(0x1_0000).is_err());
+
+        #[cfg(feature = "xahau")]
+        assert_eq!(TxnType::from_code(45), TxnType::URITokenMint);
+        #[cfg(not(feature = "xahau"))]
+        assert_eq!(TxnType::from_code(45), TxnType::Unknown(45));
+        #[cfg(not(feature = "api-v2"))]
+        assert_eq!(TxnType::from_code(25), TxnType::Unknown(25));
+    }
+
+    #[test]
+    fn txn_type_names() {
+        let mut buf = [0; TXN_TYPE_NAME_MAX_LEN];
+
+        assert!(matches!(TxnType::HookSet.write_name::<1>(&mut buf), Ok(7)));
+        assert_eq!(&buf[..7], b"SetHook");
+        assert!(matches!(
+            TxnType::Unknown(65535).write_name::<2>(&mut buf),
+            Ok(14)
+        ));
+        assert_eq!(&buf[..14], b"Unknown(65535)");
+        assert!(matches!(
+            TxnType::Unknown(7).write_name::<3>(&mut buf),
+            Ok(10)
+        ));
+        assert_eq!(&buf[..10], b"Unknown(7)");
+        assert!(TxnType::PaychanCreate
+            .write_name::<4>(&mut buf[..8])
+            .is_err());
+        assert!(!crate::testing::guard_violated());
+    }
+
+    #[cfg(feature = "api-v2")]
+    #[test]
+    fn hook_state_keylet_namespace() {
+        let account = [0xAA; ACC_ID_LEN];
+        let key = [1; STATE_KEY_LEN];
+        let mut first: Keylet = [0; KEYLET_LEN];
+        let mut second: Keylet = [0; KEYLET_LEN];
+
+        assert!(matches!(
+            util_keylet(&mut first, KeyletType::HookState(&account, &key, &[2; 32])),
+            Ok(34)
+        ));
+        assert!(matches!(
+            util_keylet(&mut second, KeyletType::HookState(&account, &key, &[3; 32])),
+            Ok(34)
+        ));
+        assert_eq!(first[..2], [0, 0x76]);
+        assert_ne!(first, second);
+    }
+}
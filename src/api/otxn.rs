@@ -13,6 +13,7 @@ pub fn otxn_field(accid: &mut [u8], field_id: FieldId) -> Result<u64> {
 }
 
 /// Output a field from the originating transaction as a human readable string
+#[cfg(not(feature = "api-v2"))]
 #[inline(always)]
 pub fn otxn_field_txt(acctxt: &mut [u8], field_id: FieldId) -> Result<u64> {
     buf_write_1arg(acctxt, field_id as _, _c::otxn_field_txt)
@@ -25,15 +26,34 @@ pub fn otxn_generation() -> i64 {
 }
 
 /// Output the canonical hash of the originating transaction
+#[cfg(not(feature = "api-v2"))]
 #[inline(always)]
 pub fn otxn_id(hash: &mut [u8]) -> Result<u64> {
     buf_write(hash, _c::otxn_id)
 }
 
+/// Output the canonical hash of the originating transaction
+///
+/// `flags` 0 outputs the hash of the originating transaction, other values are defined by the host.
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn otxn_id(hash: &mut [u8], flags: u32) -> Result<u64> {
+    buf_write_1arg(hash, flags, _c::otxn_id)
+}
+
 /// Get the Transaction Type of the originating transaction
 #[inline(always)]
-pub fn otxn_type() -> i64 {
-    unsafe { _c::otxn_type() }
+pub fn otxn_type() -> Result<TxnType> {
+    let res = unsafe { _c::otxn_type() };
+
+    if res < 0 {
+        return Err(Error::from_code(res as _));
+    }
+
+    match core::convert::TryFrom::try_from(res) {
+        core::result::Result::Ok(tt) => Ok(tt),
+        core::result::Result::Err(e) => Err(e),
+    }
 }
 
 /// Load the originating transaction into a slot
@@ -41,3 +61,10 @@ pub fn otxn_type() -> i64 {
 pub fn otxn_slot(slot_no: u32) -> Result<u64> {
     api_1arg_call(slot_no, _c::otxn_slot)
 }
+
+/// Retreive a parameter of the originating transaction, from its `HookParameters` field
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn otxn_param(value: &mut [u8], name: &[u8]) -> Result<u64> {
+    buf_write_read(value, name, _c::otxn_param)
+}
@@ -27,7 +27,7 @@ pub fn slot_id(slot_no: u32) -> Result<u64> {
 /// Locate an object based on its keylet and place it into a slot
 #[inline(always)]
 pub fn slot_set(keylet: &[u8], slot_no: i32) -> Result<u64> {
-    let res = unsafe { _c::slot_set(keylet.as_ptr() as u32, keylet.len() as u32, slot_no) };
+    let res = unsafe { _c::slot_set(keylet.as_ptr() as _, keylet.len() as u32, slot_no) };
 
     result_u64(res)
 }
@@ -84,3 +84,145 @@ pub fn slot_float(slot_no: u32) -> Result<XFL> {
 
     result_xfl(res)
 }
+
+/// Index into a slotted object and parse the STI_AMOUNT field as an XFL enclosed number
+///
+/// The amount is placed into `new_slot` first, 0 lets the host pick a free slot.
+/// That slot is freed before returning.
+#[inline(always)]
+pub fn slot_subfield_float(parent_slot: u32, field_id: FieldId, new_slot: u32) -> Result<XFL> {
+    let amount_slot = match slot_subfield(parent_slot, field_id, new_slot) {
+        Ok(slot_no) => slot_no as u32,
+        Err(e) => return Err(e),
+    };
+
+    let res = slot_float(amount_slot);
+    let _ = slot_clear(amount_slot);
+
+    res
+}
+
+/// Load the metadata of the originating transaction into a slot
+///
+/// Only available in `cbak` and in the second, weak, execution requested with [hook_again].
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn meta_slot(slot_no: u32) -> Result<u64> {
+    api_1arg_call(slot_no, _c::meta_slot)
+}
+
+/// Load the transaction and the metadata of an `Import` XPOP into slots
+///
+/// Returns the transaction and the metadata slot numbers.
+#[cfg(feature = "xahau")]
+#[inline(always)]
+pub fn xpop_slot(tx_slot: u32, meta_slot: u32) -> Result<(u32, u32)> {
+    let res = unsafe { _c::xpop_slot(tx_slot, meta_slot) };
+
+    match result_u64(res) {
+        Ok(slots) => Ok(((slots >> 16) as u32 & 0xFF, slots as u32 & 0xFF)),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, xfl_from_f64, Sto};
+
+    // 5
+    const FIVE: i64 = 0x5491_C379_37E0_8000;
+
+    #[test]
+    fn amount_subfields() {
+        let account = testing::keylet(KeyletType::Account(&[0xA1; ACC_ID_LEN]));
+        let line = testing::keylet(KeyletType::Line(
+            &[0xA1; ACC_ID_LEN],
+            &[0xB2; ACC_ID_LEN],
+            &[0; 20],
+        ));
+
+        testing::host(|host| {
+            host.insert_object(
+                &account,
+                Sto::new().drops(FieldId::Balance as _, 25_000_000).build(),
+            );
+            host.insert_object(
+                &line,
+                Sto::new()
+                    .iou(FieldId::Balance as _, FIVE, &[0; 20], &[0; 20])
+                    .build(),
+            );
+        });
+
+        let account_slot = match slot_set(&account, 0) {
+            Ok(slot_no) => slot_no as u32,
+            Err(_) => panic!("no account root"),
+        };
+        let line_slot = match slot_set(&line, 0) {
+            Ok(slot_no) => slot_no as u32,
+            Err(_) => panic!("no trust line"),
+        };
+
+        assert!(matches!(
+            slot_subfield_float(account_slot, FieldId::Balance, 0),
+            Ok(balance) if balance.0 == xfl_from_f64(25_000_000.0)
+        ));
+        assert!(matches!(
+            slot_subfield_float(line_slot, FieldId::Balance, 0),
+            Ok(balance) if balance.0 == FIVE
+        ));
+        assert!(matches!(
+            slot_subfield_float(account_slot, FieldId::LowLimit, 0),
+            Err(Error::DoesntExist)
+        ));
+        // only the account root and the trust line stay slotted
+        assert_eq!(testing::host(|host| host.slots_in_use()), 2);
+    }
+
+    #[cfg(feature = "api-v2")]
+    #[test]
+    fn metadata_slot() {
+        assert!(matches!(meta_slot(0), Err(Error::PrerequisiteNotMet)));
+
+        testing::host(|host| {
+            host.meta = Some(
+                Sto::new()
+                    .fixed(FieldId::TransactionResult as _, &[0])
+                    .build(),
+            )
+        });
+
+        assert!(matches!(meta_slot(4), Ok(4)));
+        assert!(matches!(
+            slot_subfield(4, FieldId::TransactionResult, 5),
+            Ok(5)
+        ));
+        let mut result = [0xFF; 1];
+        assert!(matches!(slot(&mut result, 5), Ok(1)));
+        assert_eq!(result, [0]);
+    }
+
+    #[cfg(feature = "xahau")]
+    #[test]
+    fn xpop_slots() {
+        assert!(matches!(xpop_slot(0, 0), Err(Error::PrerequisiteNotMet)));
+
+        let tx = Sto::new().u16(FieldId::TransactionType as _, 97).build();
+        let meta = Sto::new()
+            .fixed(FieldId::TransactionResult as _, &[0])
+            .build();
+        testing::host(|host| host.xpop = Some((tx.clone(), meta.clone())));
+
+        // the transaction slot is in the upper bits, above the metadata one
+        assert!(matches!(xpop_slot(3, 200), Ok((3, 200))));
+        assert!(matches!(xpop_slot(0, 0), Ok((1, 2))));
+        assert!(matches!(xpop_slot(255, 17), Ok((255, 17))));
+
+        testing::host(|host| {
+            assert_eq!(host.slot(3), Some(&tx));
+            assert_eq!(host.slot(200), Some(&meta));
+            assert_eq!(host.slot(2), Some(&meta));
+        });
+    }
+}
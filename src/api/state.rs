@@ -13,15 +13,59 @@ pub fn state_set(data: &[u8], key: &[u8]) -> Result<u64> {
 }
 
 /// Retrieve the data pointed to, on another account, by a Hook State key and write it to an output buffer
+#[cfg(not(feature = "api-v2"))]
 #[inline(always)]
 pub fn state_foreign(data: &mut [u8], key: &[u8], accid: &[u8]) -> Result<u64> {
     let res = unsafe {
         _c::state_foreign(
-            data.as_mut_ptr() as u32,
+            data.as_mut_ptr() as _,
             data.len() as u32,
-            key.as_ptr() as u32,
+            key.as_ptr() as _,
             key.len() as u32,
-            accid.as_ptr() as u32,
+            accid.as_ptr() as _,
+            accid.len() as u32,
+        )
+    };
+
+    result_u64(res)
+}
+
+/// Retrieve the data pointed to, in a namespace of another account, by a Hook State key
+/// and write it to an output buffer
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn state_foreign(data: &mut [u8], key: &[u8], namespace: &[u8], accid: &[u8]) -> Result<u64> {
+    let res = unsafe {
+        _c::state_foreign(
+            data.as_mut_ptr() as _,
+            data.len() as u32,
+            key.as_ptr() as _,
+            key.len() as u32,
+            namespace.as_ptr() as _,
+            namespace.len() as u32,
+            accid.as_ptr() as _,
+            accid.len() as u32,
+        )
+    };
+
+    result_u64(res)
+}
+
+/// Set the Hook State for a given key and value, in a namespace of another account
+///
+/// The other account must have granted this Hook with a HookGrant, an empty `data` deletes the entry.
+#[cfg(feature = "api-v2")]
+#[inline(always)]
+pub fn state_foreign_set(data: &[u8], key: &[u8], namespace: &[u8], accid: &[u8]) -> Result<u64> {
+    let res = unsafe {
+        _c::state_foreign_set(
+            data.as_ptr() as _,
+            data.len() as u32,
+            key.as_ptr() as _,
+            key.len() as u32,
+            namespace.as_ptr() as _,
+            namespace.len() as u32,
+            accid.as_ptr() as _,
             accid.len() as u32,
         )
     };
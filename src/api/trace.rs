@@ -5,9 +5,9 @@ use super::*;
 pub fn trace(msg: &[u8], data: &[u8], data_repr: DataRepr) -> Result<u64> {
     let res = unsafe {
         _c::trace(
-            msg.as_ptr() as u32,
+            msg.as_ptr() as _,
             msg.len() as u32,
-            data.as_ptr() as u32,
+            data.as_ptr() as _,
             data.len() as u32,
             data_repr as _,
         )
@@ -19,7 +19,7 @@ pub fn trace(msg: &[u8], data: &[u8], data_repr: DataRepr) -> Result<u64> {
 /// Write the contents of a slot to the XRPLD trace log
 #[inline(always)]
 pub fn trace_slot(msg: &[u8], slot: u32) -> Result<u64> {
-    let res = unsafe { _c::trace_slot(msg.as_ptr() as u32, msg.len() as u32, slot) };
+    let res = unsafe { _c::trace_slot(msg.as_ptr() as _, msg.len() as u32, slot) };
 
     result_u64(res)
 }
@@ -27,7 +27,7 @@ pub fn trace_slot(msg: &[u8], slot: u32) -> Result<u64> {
 /// Write an integer to the XRPLD trace log
 #[inline(always)]
 pub fn trace_num(msg: &[u8], number: i64) -> Result<u64> {
-    let res = unsafe { _c::trace_num(msg.as_ptr() as u32, msg.len() as u32, number) };
+    let res = unsafe { _c::trace_num(msg.as_ptr() as _, msg.len() as u32, number) };
 
     result_u64(res)
 }
@@ -35,7 +35,7 @@ pub fn trace_num(msg: &[u8], number: i64) -> Result<u64> {
 /// Write a XFL float to the XRPLD trace log
 #[inline(always)]
 pub fn trace_float(msg: &[u8], float: XFL) -> Result<u64> {
-    let res = unsafe { _c::trace_float(msg.as_ptr() as u32, msg.len() as u32, float.0) };
+    let res = unsafe { _c::trace_float(msg.as_ptr() as _, msg.len() as u32, float.0) };
 
     result_u64(res)
 }
@@ -39,10 +39,30 @@ pub fn util_keylet(keylet: &mut [u8], keylet_type: KeyletType) -> Result<u64> {
     match keylet_type {
         KeyletType::Hook(accid) => buf_read_and_zeroes(keylet, accid, _c::KEYLET_HOOK),
 
+        #[cfg(not(feature = "api-v2"))]
         KeyletType::HookState(accid, statekey) => {
             buf_2_read_and_zeroes(keylet, accid, statekey, _c::KEYLET_HOOK_STATE)
         }
 
+        #[cfg(feature = "api-v2")]
+        KeyletType::HookState(accid, statekey, namespace) => {
+            let res = unsafe {
+                _c::util_keylet(
+                    write_ptr,
+                    write_len,
+                    _c::KEYLET_HOOK_STATE,
+                    accid.as_ptr() as _,
+                    accid.len() as _,
+                    statekey.as_ptr() as _,
+                    statekey.len() as _,
+                    namespace.as_ptr() as _,
+                    namespace.len() as _,
+                )
+            };
+
+            result_u64(res)
+        }
+
         KeyletType::Account(accid) => buf_read_and_zeroes(keylet, accid, _c::KEYLET_ACCOUNT),
 
         KeyletType::Amendments => all_zeroes(keylet, _c::KEYLET_AMENDMENTS),
@@ -58,8 +78,8 @@ pub fn util_keylet(keylet: &mut [u8], keylet_type: KeyletType) -> Result<u64> {
                         write_ptr,
                         write_len,
                         _c::KEYLET_SKIP,
-                        ledger_index,
-                        num,
+                        ledger_index as _,
+                        num as _,
                         0,
                         0,
                         0,
@@ -133,7 +153,7 @@ pub fn util_keylet(keylet: &mut [u8], keylet_type: KeyletType) -> Result<u64> {
                     accid_1.len() as _,
                     accid_2.as_ptr() as _,
                     accid_2.len() as _,
-                    num,
+                    num as _,
                     0,
                 )
             };
@@ -142,5 +162,25 @@ pub fn util_keylet(keylet: &mut [u8], keylet_type: KeyletType) -> Result<u64> {
         }
 
         KeyletType::Emitted(key) => buf_read_and_zeroes(keylet, key, _c::KEYLET_EMITTED),
+
+        #[cfg(feature = "api-v2")]
+        KeyletType::NftOffer(accid, num) => {
+            buf_read_and_1_arg(keylet, accid, num, _c::KEYLET_NFT_OFFER)
+        }
+
+        #[cfg(feature = "api-v2")]
+        KeyletType::HookDefinition(hash) => {
+            buf_read_and_zeroes(keylet, hash, _c::KEYLET_HOOK_DEFINITION)
+        }
+
+        #[cfg(feature = "api-v2")]
+        KeyletType::HookStateDir(accid, namespace) => {
+            buf_2_read_and_zeroes(keylet, accid, namespace, _c::KEYLET_HOOK_STATE_DIR)
+        }
+
+        #[cfg(feature = "xahau")]
+        KeyletType::UriToken(issuer, uri) => {
+            buf_2_read_and_zeroes(keylet, issuer, uri, _c::KEYLET_URITOKEN)
+        }
     }
 }
@@ -0,0 +1,610 @@
+use crate::api::*;
+use crate::codec::u64_from_buf;
+use crate::directory::{directory_entries, directory_root, DirEntries};
+use crate::iter::{GuardedIter, GuardedIterator};
+use crate::objects::{
+    DirectoryNode, LedgerObject, Offer, DIRECTORY_PAGE_BUF_LEN, LEDGER_OBJECT_BUF_LEN,
+};
+use crate::uninit_buf;
+
+/// `DirectoryNode` ledger entry type, the first bytes of a book keylet
+const LT_DIR_NODE: [u8; 2] = [0x00, 0x64];
+
+/// `BOOK_DIR` ledger namespace
+const BOOK_DIR_NAMESPACE: [u8; 2] = [0x00, b'B'];
+
+/// Currency and issuer of one side of a book, all zeroes for XRP
+#[derive(Clone, Copy)]
+pub struct Issue {
+    /// Currency code
+    pub currency: CurrencyCode,
+    /// Issuer account
+    pub issuer: AccountId,
+}
+
+impl Issue {
+    /// XRP
+    pub const XRP: Issue = Issue {
+        currency: [0; CURRENCY_CODE_SIZE],
+        issuer: [0; ACC_ID_LEN],
+    };
+
+    /// Issued currency
+    #[inline(always)]
+    pub const fn new(currency: CurrencyCode, issuer: AccountId) -> Self {
+        Issue { currency, issuer }
+    }
+}
+
+/// Order book, the offers taking `taker_pays` for `taker_gets`
+///
+/// The offers of a book are kept in one directory per quality, the rate
+/// `TakerPays / TakerGets` of its offers. The quality is the last 8 bytes of the
+/// directory key, appended to the book base, so the lower key is the better quality.
+///
+/// With `api-v2`, [Book::best_offer] walks to the best quality directory with
+/// [ledger_keylet]. The `api-v1` host can't look up the next key in the ledger,
+/// there [Book::best_offer_in] looks through the qualities the hook knows,
+/// from the offers it tracks ([Offer::book_directory]) or from its price grid.
+///
+/// ``` txt
+/// let book = Book::new(Issue::new(USD, ISSUER), Issue::XRP);
+///
+/// match book.best_offer() {
+///     Ok(Some(offer)) if offer.rate.0 != limit.0 => ...,
+///     Ok(_) => ...,
+///     Err(e) => rollback(b"book", e.code() as _),
+/// }
+/// ```
+#[derive(Clone, Copy)]
+pub struct Book {
+    /// What the taker pays, the currency the offers ask for
+    pub taker_pays: Issue,
+    /// What the taker gets, the currency the offers sell
+    pub taker_gets: Issue,
+}
+
+/// Offer of a book, with its keylet and rate
+#[derive(Clone, Copy)]
+pub struct BookOffer {
+    /// Keylet of the offer
+    pub keylet: Keylet,
+    /// The ledger object
+    pub offer: Offer,
+    /// Quality of the offer, as in the key of its directory
+    pub quality: u64,
+    /// `TakerPays / TakerGets` when the offer was placed, drops for XRP
+    pub rate: XFL,
+}
+
+impl Book {
+    /// Creates the book
+    #[inline(always)]
+    pub const fn new(taker_pays: Issue, taker_gets: Issue) -> Self {
+        Book {
+            taker_pays,
+            taker_gets,
+        }
+    }
+
+    /// The book on the other side, taking `taker_gets` for `taker_pays`
+    #[inline(always)]
+    pub const fn reversed(&self) -> Self {
+        Book::new(self.taker_gets, self.taker_pays)
+    }
+
+    /// Keylet of the book base, the book directory of quality zero
+    #[inline(always)]
+    pub fn base_keylet(&self) -> Result<Keylet> {
+        let mut data = [0; 2 + 4 * ACC_ID_LEN];
+        self.base_preimage(&mut data);
+
+        let mut keylet: Keylet = [0; KEYLET_LEN];
+
+        match util_sha512h(&mut keylet[LT_DIR_NODE.len()..], &data) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+
+        keylet[..LT_DIR_NODE.len()].clone_from_slice(&LT_DIR_NODE);
+        keylet[KEYLET_LEN - 8..].clone_from_slice(&[0; 8]);
+
+        Ok(keylet)
+    }
+
+    /// Keylet of the book directory of the quality, see [rate_quality]
+    #[inline(always)]
+    pub fn quality_keylet(&self, quality: u64) -> Result<Keylet> {
+        let base = match self.base_keylet() {
+            Ok(base) => base,
+            Err(e) => return Err(e),
+        };
+
+        let mut keylet: Keylet = [0; KEYLET_LEN];
+
+        match util_keylet(
+            &mut keylet,
+            KeyletType::Quality(&base, (quality >> 32) as u32, quality as u32),
+        ) {
+            Ok(_) => Ok(keylet),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Offers of the quality directory, as `Unchecked` keylets, see [directory_entries]
+    ///
+    /// `buf` takes a page at a time. Load the offers with [load_book_offer].
+    #[inline(always)]
+    pub fn offers<'a, const GUARD_ID: u32>(
+        &self,
+        quality: u64,
+        buf: &'a mut [u8],
+        max_pages: u32,
+        maxiter: u32,
+    ) -> Result<GuardedIter<DirEntries<'a, GUARD_ID>, GUARD_ID>> {
+        match self.quality_keylet(quality) {
+            Ok(keylet) => Ok(directory_entries::<GUARD_ID>(
+                &directory_root(&keylet),
+                buf,
+                max_pages,
+                maxiter,
+            )),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// First offer of the best quality directory of the book, `None` if the book is empty
+    ///
+    /// Finds the directory with [ledger_keylet], the lowest key above the book base.
+    /// Takes a slot at a time.
+    #[cfg(feature = "api-v2")]
+    #[inline(always)]
+    pub fn best_offer(&self) -> Result<Option<BookOffer>> {
+        let low = match self.base_keylet() {
+            Ok(base) => base,
+            Err(e) => return Err(e),
+        };
+
+        // the last quality of the book
+        let mut high = low;
+        high[KEYLET_LEN - 8..].clone_from_slice(&[0xFF; 8]);
+
+        let mut directory: Keylet = [0; KEYLET_LEN];
+
+        match ledger_keylet(&mut directory, &low, &high) {
+            Ok(_) => {}
+            Err(Error::DoesntExist) => return Ok(None),
+            Err(e) => return Err(e),
+        }
+
+        match first_entry(&directory) {
+            Ok(Some(index)) => match offer_of_entry(&index) {
+                Ok(offer) => Ok(Some(offer)),
+                Err(e) => Err(e),
+            },
+            Ok(None) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// First offer of the first quality with offers, `None` if none has any
+    ///
+    /// `qualities` go from the best, the lowest, to the worst.
+    /// Looks through `maxiter` qualities at most, takes a slot at a time.
+    ///
+    /// Pay attention to the GUARD_ID parameter.
+    /// This should be unique on every call, through the entire hook code.
+    /// Otherwise you will encounter guard violation during the execution of your hook.
+    #[inline(always)]
+    pub fn best_offer_in<const GUARD_ID: u32>(
+        &self,
+        qualities: &[u64],
+        maxiter: u32,
+    ) -> Result<Option<BookOffer>> {
+        for quality in qualities.guarded_iter::<GUARD_ID>(maxiter) {
+            let directory = match self.quality_keylet(*quality) {
+                Ok(keylet) => keylet,
+                Err(e) => return Err(e),
+            };
+
+            match first_entry(&directory) {
+                Ok(Some(index)) => {
+                    return match offer_of_entry(&index) {
+                        Ok(offer) => Ok(Some(offer)),
+                        Err(e) => Err(e),
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(None)
+    }
+
+    #[inline(always)]
+    fn base_preimage(&self, data: &mut [u8; 2 + 4 * ACC_ID_LEN]) {
+        data[..2].clone_from_slice(&BOOK_DIR_NAMESPACE);
+        data[2..22].clone_from_slice(&self.taker_pays.currency);
+        data[22..42].clone_from_slice(&self.taker_gets.currency);
+        data[42..62].clone_from_slice(&self.taker_pays.issuer);
+        data[62..82].clone_from_slice(&self.taker_gets.issuer);
+    }
+}
+
+/// Key of the first entry of the directory, `None` if it doesn't exist
+///
+/// Only the root page can be empty, then the entry is on the next page.
+#[inline(always)]
+fn first_entry(directory: &Keylet) -> Result<Option<Hash>> {
+    let root = match read_page(directory) {
+        Ok(Some(page)) => page,
+        Ok(None) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let next = match root {
+        (Some(index), _) => return Ok(Some(index)),
+        (None, 0) => return Ok(None),
+        (None, next) => next,
+    };
+
+    let mut keylet: Keylet = [0; KEYLET_LEN];
+
+    match util_keylet(
+        &mut keylet,
+        KeyletType::Page(&directory_root(directory), (next >> 32) as u32, next as u32),
+    ) {
+        Err(e) => return Err(e),
+        Ok(_) => {}
+    }
+
+    match read_page(&keylet) {
+        Ok(Some((index, _))) => Ok(index),
+        Ok(None) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Offer of a directory entry
+#[inline(always)]
+fn offer_of_entry(index: &Hash) -> Result<BookOffer> {
+    let mut keylet: Keylet = [0; KEYLET_LEN];
+
+    match util_keylet(&mut keylet, KeyletType::Unchecked(index)) {
+        Ok(_) => load_book_offer(&keylet),
+        Err(e) => Err(e),
+    }
+}
+
+/// First key and `IndexNext` of a directory page, `None` if it doesn't exist
+#[inline(always)]
+fn read_page(keylet: &Keylet) -> Result<Option<(Option<Hash>, u64)>> {
+    let page_slot = match slot_set(keylet, 0) {
+        Ok(slot_no) => slot_no as u32,
+        Err(Error::DoesntExist) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let mut buf: [u8; DIRECTORY_PAGE_BUF_LEN] = uninit_buf!();
+    let page = DirectoryNode::from_slot(page_slot, &mut buf);
+    let _ = slot_clear(page_slot);
+
+    match page {
+        Ok(page) if page.is_empty() => Ok(Some((None, page.index_next))),
+        Ok(page) => {
+            let mut index = [0; HASH_LEN];
+            index.clone_from_slice(&page.indexes[..HASH_LEN]);
+
+            Ok(Some((Some(index), page.index_next)))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Slots the offer of the keylet and decodes it
+///
+/// Uses a slot and frees it before returning.
+#[inline(always)]
+pub fn load_book_offer(keylet: &Keylet) -> Result<BookOffer> {
+    let offer_slot = match slot_set(keylet, 0) {
+        Ok(slot_no) => slot_no as u32,
+        Err(e) => return Err(e),
+    };
+
+    let mut buf: [u8; LEDGER_OBJECT_BUF_LEN] = uninit_buf!();
+    let offer = Offer::from_slot(offer_slot, &mut buf);
+    let _ = slot_clear(offer_slot);
+
+    match offer {
+        Ok(offer) => {
+            let quality = directory_quality(&offer.book_directory);
+            let rate = match quality_rate(quality) {
+                Ok(rate) => rate,
+                Err(e) => return Err(e),
+            };
+
+            Ok(BookOffer {
+                keylet: *keylet,
+                offer,
+                quality,
+                rate,
+            })
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Quality of a book directory, the last 8 bytes of its key
+#[inline(always)]
+pub fn directory_quality(directory: &Hash) -> u64 {
+    let d = &directory[HASH_LEN - 8..];
+
+    u64_from_buf(&[d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7]])
+}
+
+/// Smallest mantissa of a normalized rate, 10^15
+const MIN_MANTISSA: u64 = 1_000_000_000_000_000;
+
+/// Largest mantissa of a normalized rate, 10^16 - 1
+const MAX_MANTISSA: u64 = 9_999_999_999_999_999;
+
+/// Largest top byte of a quality, the largest amount exponent 80 plus 100
+const MAX_QUALITY_EXPONENT: u64 = 180;
+
+/// Rate of a quality, without a host call
+///
+/// The quality holds the exponent plus 100 in the top byte and the mantissa in the rest.
+/// Returns [Error::InvalidFloat] if the exponent is below the XFL range, the top byte
+/// under 3, above the largest amount exponent 80, the top byte over 180,
+/// or if the mantissa isn't normalized.
+#[inline(always)]
+pub const fn quality_rate(quality: u64) -> Result<XFL> {
+    let mantissa = quality & 0x00FF_FFFF_FFFF_FFFF;
+
+    if mantissa == 0 {
+        return Ok(XFL(0));
+    }
+
+    if mantissa < MIN_MANTISSA || mantissa > MAX_MANTISSA {
+        return Err(Error::InvalidFloat);
+    }
+
+    let top = quality >> 56;
+
+    if top < 3 || top > MAX_QUALITY_EXPONENT {
+        return Err(Error::InvalidFloat);
+    }
+
+    // exponent - 100 + 97
+    let exponent = top - 3;
+
+    Ok(XFL(((1 << 62) | (exponent << 54) | mantissa) as i64))
+}
+
+/// Quality of a rate, the reverse of [quality_rate]
+///
+/// Returns [Error::CantReturnNegative] for a negative rate and [Error::Overflow]
+/// if the exponent is above the largest amount exponent 80, a biased XFL exponent
+/// of 178 and above.
+#[inline(always)]
+pub const fn rate_quality(rate: XFL) -> Result<u64> {
+    let raw = rate.0 as u64;
+
+    if raw == 0 {
+        return Ok(0);
+    }
+
+    if raw & (1 << 62) == 0 {
+        return Err(Error::CantReturnNegative);
+    }
+
+    // exponent + 97 - 97 + 100
+    let exponent = ((raw >> 54) & 0xFF) + 3;
+
+    if exponent > MAX_QUALITY_EXPONENT {
+        return Err(Error::Overflow);
+    }
+
+    Ok((exponent << 56) | (raw & 0x003F_FFFF_FFFF_FFFF))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Sto};
+
+    // 5 * 10^0 as XFL
+    const FIVE: XFL = XFL(0x5491_C379_37E0_8000);
+
+    #[test]
+    fn quality() {
+        // 5 * 10^15 * 10^-15, exponent + 100 = 85
+        let quality: u64 = (85 << 56) | 5_000_000_000_000_000;
+
+        assert!(matches!(quality_rate(quality), Ok(rate) if rate.0 == FIVE.0));
+        assert!(matches!(rate_quality(FIVE), Ok(q) if q == quality));
+        assert!(matches!(quality_rate(0), Ok(XFL(0))));
+        assert!(matches!(rate_quality(XFL(0)), Ok(0)));
+        assert!(rate_quality(XFL(FIVE.0 ^ (1 << 62))).is_err());
+
+        let mut directory = [0; HASH_LEN];
+        directory[HASH_LEN - 8..].clone_from_slice(&quality.to_be_bytes());
+        assert_eq!(directory_quality(&directory), quality);
+    }
+
+    #[test]
+    fn quality_bounds() {
+        let mantissa = 5_000_000_000_000_000;
+
+        // top bytes 3 and 180, the lowest XFL exponent and the largest amount exponent
+        let lowest: u64 = (3 << 56) | mantissa;
+        let highest: u64 = (180 << 56) | mantissa;
+        let lowest_rate = match quality_rate(lowest) {
+            Ok(rate) => rate,
+            Err(_) => panic!("lowest quality"),
+        };
+        let highest_rate = match quality_rate(highest) {
+            Ok(rate) => rate,
+            Err(_) => panic!("highest quality"),
+        };
+        assert!(lowest_rate.0 > 0 && highest_rate.0 > 0);
+        assert!(matches!(rate_quality(lowest_rate), Ok(q) if q == lowest));
+        assert!(matches!(rate_quality(highest_rate), Ok(q) if q == highest));
+
+        // outside of the exponent range
+        for top in (0..3u64).chain(181..=255) {
+            assert!(matches!(
+                quality_rate((top << 56) | mantissa),
+                Err(Error::InvalidFloat)
+            ));
+        }
+
+        // mantissas out of the normalized range, spilling into the exponent
+        assert!(matches!(
+            quality_rate((85 << 56) | 999_999_999_999_999),
+            Err(Error::InvalidFloat)
+        ));
+        assert!(matches!(
+            quality_rate((85 << 56) | 0x00FF_FFFF_FFFF_FFFF),
+            Err(Error::InvalidFloat)
+        ));
+
+        // biased XFL exponents 178 and above are past the largest amount exponent
+        for exponent in 177..=255u64 {
+            let rate = XFL(((1 << 62) | (exponent << 54) | mantissa) as i64);
+            if exponent == 177 {
+                assert!(matches!(rate_quality(rate), Ok(q) if q == highest));
+            } else {
+                assert!(matches!(rate_quality(rate), Err(Error::Overflow)));
+            }
+        }
+    }
+
+    const OWNER: AccountId = [0x0A; ACC_ID_LEN];
+    const USD: CurrencyCode = [
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, b'U', b'S', b'D', 0, 0, 0, 0, 0,
+    ];
+
+    fn usd_book() -> Book {
+        Book::new(Issue::new(USD, [0x1B; ACC_ID_LEN]), Issue::XRP)
+    }
+
+    /// Puts a quality directory with a full root page in the ledger,
+    /// `offer` first, and the offer with every field it can have
+    fn insert_quality(quality: u64, offer: u8) -> Keylet {
+        let directory = match usd_book().quality_keylet(quality) {
+            Ok(keylet) => keylet,
+            Err(_) => panic!("no quality keylet"),
+        };
+
+        let mut indexes = [0xEE; 32 * HASH_LEN];
+        indexes[..HASH_LEN].clone_from_slice(&[offer; HASH_LEN]);
+        let page = Sto::new()
+            .u16(FieldId::LedgerEntryType as _, 0x64)
+            .u32(FieldId::Flags as _, 0)
+            .u64(FieldId::IndexNext as _, 0)
+            .u64(FieldId::IndexPrevious as _, 0)
+            .u64(FieldId::ExchangeRate as _, quality)
+            .fixed(FieldId::RootIndex as _, &directory_root(&directory))
+            .fixed(FieldId::TakerPaysCurrency as _, &USD)
+            .fixed(FieldId::TakerPaysIssuer as _, &[0x1B; ACC_ID_LEN])
+            .fixed(FieldId::TakerGetsCurrency as _, &[0; 20])
+            .fixed(FieldId::TakerGetsIssuer as _, &[0; 20])
+            .vl_field(FieldId::Indexes as _, &indexes)
+            .build();
+        assert!(page.len() > LEDGER_OBJECT_BUF_LEN && page.len() <= DIRECTORY_PAGE_BUF_LEN);
+
+        let object = Sto::new()
+            .u16(FieldId::LedgerEntryType as _, 0x6F)
+            .u32(FieldId::Flags as _, 0)
+            .u32(FieldId::Sequence as _, offer as u32)
+            .u32(FieldId::PreviousTxnLgrSeq as _, 1)
+            .u32(FieldId::Expiration as _, 750_000_000)
+            .u64(FieldId::BookNode as _, 0)
+            .u64(FieldId::OwnerNode as _, 0)
+            .fixed(FieldId::PreviousTxnID as _, &[0; HASH_LEN])
+            .fixed(FieldId::BookDirectory as _, &directory_root(&directory))
+            .iou(FieldId::TakerPays as _, FIVE.0, &USD, &[0x1B; ACC_ID_LEN])
+            .drops(FieldId::TakerGets as _, 1_000_000)
+            .vl_field(FieldId::Account as _, &OWNER)
+            .build();
+        assert!(object.len() <= LEDGER_OBJECT_BUF_LEN);
+
+        let offer_keylet = testing::keylet(KeyletType::Unchecked(&[offer; HASH_LEN]));
+        testing::host(|host| {
+            host.insert_object(&directory, page);
+            host.insert_object(&offer_keylet, object);
+        });
+
+        directory
+    }
+
+    #[test]
+    fn best_offer_in_qualities() {
+        let better: u64 = (85 << 56) | 5_000_000_000_000_000;
+        let worse: u64 = (86 << 56) | 5_000_000_000_000_000;
+        insert_quality(worse, 2);
+
+        let offer = match usd_book().best_offer_in::<1>(&[better, worse], 2) {
+            Ok(Some(offer)) => offer,
+            _ => panic!("no offer"),
+        };
+        assert_eq!(offer.offer.sequence, 2);
+        assert_eq!(offer.quality, worse);
+        assert_eq!(offer.offer.account, OWNER);
+        assert_eq!(testing::host(|host| host.slots_in_use()), 0);
+
+        assert!(matches!(
+            usd_book().best_offer_in::<3>(&[better], 1),
+            Ok(None)
+        ));
+        assert!(!testing::guard_violated());
+    }
+
+    #[cfg(feature = "api-v2")]
+    #[test]
+    fn best_offer_walk() {
+        assert!(matches!(usd_book().best_offer(), Ok(None)));
+
+        let better: u64 = (85 << 56) | 5_000_000_000_000_000;
+        let worse: u64 = (86 << 56) | 1_000_000_000_000_000;
+        insert_quality(worse, 2);
+        insert_quality(better, 1);
+        // the best quality of another book doesn't count
+        let other = Book::new(Issue::new(USD, [0x1C; ACC_ID_LEN]), Issue::XRP);
+        let other_directory = match other.quality_keylet(1) {
+            Ok(keylet) => keylet,
+            Err(_) => panic!("no quality keylet"),
+        };
+        testing::host(|host| host.insert_object(&other_directory, [].to_vec()));
+
+        let offer = match usd_book().best_offer() {
+            Ok(Some(offer)) => offer,
+            _ => panic!("no offer"),
+        };
+        assert_eq!(offer.offer.sequence, 1);
+        assert_eq!(offer.quality, better);
+        assert_eq!(offer.rate.0, FIVE.0);
+        assert_eq!(testing::host(|host| host.slots_in_use()), 0);
+    }
+
+    #[test]
+    fn preimage() {
+        let book = Book::new(
+            Issue::new([1; CURRENCY_CODE_SIZE], [2; ACC_ID_LEN]),
+            Issue::XRP,
+        );
+        let mut data = [0xFF; 2 + 4 * ACC_ID_LEN];
+        book.base_preimage(&mut data);
+
+        assert_eq!(&data[..2], &[0x00, 0x42]);
+        assert_eq!(&data[2..22], &[1; 20]);
+        assert_eq!(&data[22..42], &[0; 20]);
+        assert_eq!(&data[42..62], &[2; 20]);
+        assert_eq!(&data[62..82], &[0; 20]);
+
+        let reversed = book.reversed();
+        assert_eq!(reversed.taker_gets.issuer, [2; ACC_ID_LEN]);
+    }
+}
@@ -0,0 +1,319 @@
+use crate::api::*;
+
+/// Zeroize a buffer
+///
+/// Writes are volatile, so they are not optimized away
+/// even if the buffer is never read afterwards.
+///
+/// Guard budget: `buf.len()` iterations.
+///
+/// Pay attention to the GUARD_ID parameter.
+/// This should be unique on every call, through the entire hook code.
+/// Otherwise you will encounter guard violation during the execution of your hook.
+#[inline(always)]
+pub fn zeroize<const GUARD_ID: u32>(buf: &mut [u8]) {
+    let buf_len = buf.len();
+    let ptr = buf.as_mut_ptr();
+    // guarded loop
+    let mut i = 0;
+    while {
+        _g(GUARD_ID, buf_len as u32 + 1);
+        i < buf_len
+    } {
+        // SAFETY: i < buf_len
+        unsafe { core::ptr::write_volatile(ptr.add(i), 0) };
+        i += 1;
+    }
+}
+
+/// Fill a buffer with a value
+///
+/// Guard budget: `buf.len()` iterations.
+///
+/// Pay attention to the GUARD_ID parameter.
+/// This should be unique on every call, through the entire hook code.
+/// Otherwise you will encounter guard violation during the execution of your hook.
+#[inline(always)]
+pub fn fill<const GUARD_ID: u32>(buf: &mut [u8], value: u8) {
+    let buf_len = buf.len();
+    // guarded loop
+    let mut i = 0;
+    while {
+        _g(GUARD_ID, buf_len as u32 + 1);
+        i < buf_len
+    } {
+        buf[i] = value;
+        i += 1;
+    }
+}
+
+/// Copy `src` to the beginning of `dst`
+///
+/// Returns [Error::TooSmall] if `dst` is shorter than `src`.
+///
+/// Guard budget: `src.len()` iterations.
+///
+/// Pay attention to the GUARD_ID parameter.
+/// This should be unique on every call, through the entire hook code.
+/// Otherwise you will encounter guard violation during the execution of your hook.
+#[inline(always)]
+pub fn copy<const GUARD_ID: u32>(dst: &mut [u8], src: &[u8]) -> Result<()> {
+    let src_len = src.len();
+
+    if dst.len() < src_len {
+        return Err(Error::TooSmall);
+    }
+
+    // guarded loop
+    let mut i = 0;
+    while {
+        _g(GUARD_ID, src_len as u32 + 1);
+        i < src_len
+    } {
+        dst[i] = src[i];
+        i += 1;
+    }
+
+    Ok(())
+}
+
+/// Swap the contents of two buffers of the same length
+///
+/// Returns [Error::InvalidArgument] if the lengths differ.
+///
+/// Guard budget: `buf_1.len()` iterations.
+///
+/// Pay attention to the GUARD_ID parameter.
+/// This should be unique on every call, through the entire hook code.
+/// Otherwise you will encounter guard violation during the execution of your hook.
+#[inline(always)]
+pub fn swap<const GUARD_ID: u32>(buf_1: &mut [u8], buf_2: &mut [u8]) -> Result<()> {
+    let buf_len = buf_1.len();
+
+    if buf_len != buf_2.len() {
+        return Err(Error::InvalidArgument);
+    }
+
+    // guarded loop
+    let mut i = 0;
+    while {
+        _g(GUARD_ID, buf_len as u32 + 1);
+        i < buf_len
+    } {
+        core::mem::swap(&mut buf_1[i], &mut buf_2[i]);
+        i += 1;
+    }
+
+    Ok(())
+}
+
+/// Tests two buffers for equality in constant time
+///
+/// Every byte is compared regardless of where the first difference is,
+/// use it for secrets (e.g. hashes of preimages).
+/// Buffers of different lengths are not equal, the length itself is not hidden.
+///
+/// Guard budget: `buf_1.len()` iterations.
+///
+/// Pay attention to the GUARD_ID parameter.
+/// This should be unique on every call, through the entire hook code.
+/// Otherwise you will encounter guard violation during the execution of your hook.
+#[inline(always)]
+pub fn ct_equal<const GUARD_ID: u32>(buf_1: &[u8], buf_2: &[u8]) -> bool {
+    let buf_len = buf_1.len();
+
+    if buf_len != buf_2.len() {
+        return false;
+    }
+
+    let mut diff = 0;
+    // guarded loop
+    let mut i = 0;
+    while {
+        _g(GUARD_ID, buf_len as u32 + 1);
+        i < buf_len
+    } {
+        diff |= buf_1[i] ^ buf_2[i];
+        i += 1;
+    }
+
+    // SAFETY: diff is a valid u8 on the stack
+    unsafe { core::ptr::read_volatile(&diff) == 0 }
+}
+
+/// Checks whether a buffer starts with the prefix
+///
+/// Guard budget: `prefix.len()` iterations.
+///
+/// Pay attention to the GUARD_ID parameter.
+/// This should be unique on every call, through the entire hook code.
+/// Otherwise you will encounter guard violation during the execution of your hook.
+#[inline(always)]
+pub fn starts_with<const GUARD_ID: u32>(buf: &[u8], prefix: &[u8]) -> bool {
+    let prefix_len = prefix.len();
+
+    if buf.len() < prefix_len {
+        return false;
+    }
+
+    // guarded loop
+    let mut i = 0;
+    while {
+        _g(GUARD_ID, prefix_len as u32 + 1);
+        i < prefix_len
+    } {
+        if buf[i] != prefix[i] {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
+
+/// Find the first occurrence of `needle` in `haystack`
+///
+/// Returns the position of the occurrence, an empty needle is found at `0`.
+///
+/// Guard budget: [find_budget] iterations.
+///
+/// Pay attention to the GUARD_ID parameter.
+/// This should be unique on every call, through the entire hook code.
+/// Otherwise you will encounter guard violation during the execution of your hook.
+#[inline(always)]
+pub fn find<const GUARD_ID: u32>(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    let haystack_len = haystack.len();
+    let needle_len = needle.len();
+
+    if haystack_len < needle_len {
+        return None;
+    }
+
+    let maxiter = find_budget(haystack_len, needle_len);
+
+    // a single guarded loop walking (position, offset) pairs
+    let mut pos = 0;
+    let mut offset = 0;
+    while {
+        _g(GUARD_ID, maxiter + 1);
+        pos + needle_len <= haystack_len
+    } {
+        if offset == needle_len {
+            return Some(pos);
+        }
+
+        if haystack[pos + offset] == needle[offset] {
+            offset += 1;
+        } else {
+            pos += 1;
+            offset = 0;
+        }
+    }
+
+    None
+}
+
+/// Guard budget of [find]
+///
+/// Every position of the haystack costs at most `needle_len + 1` iterations.
+#[inline(always)]
+pub const fn find_budget(haystack_len: usize, needle_len: usize) -> u32 {
+    if haystack_len < needle_len {
+        return 0;
+    }
+
+    ((haystack_len - needle_len + 1) * (needle_len + 1)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{guard_calls, guard_violated};
+
+    #[test]
+    fn zeroize_clears_every_byte() {
+        let mut buf = [0xFF; 20];
+
+        zeroize::<1>(&mut buf);
+
+        assert_eq!(buf, [0; 20]);
+        assert_eq!(guard_calls(), 21);
+        assert!(!guard_violated());
+    }
+
+    #[test]
+    fn fill_buffer() {
+        let mut buf = [0; 8];
+
+        fill::<1>(&mut buf, 0xAB);
+
+        assert_eq!(buf, [0xAB; 8]);
+        assert_eq!(guard_calls(), 9);
+        assert!(!guard_violated());
+    }
+
+    #[test]
+    fn copy_buffer() {
+        let mut dst = [0; 6];
+
+        assert!(copy::<1>(&mut dst, &[1, 2, 3, 4]).is_ok());
+        assert_eq!(dst, [1, 2, 3, 4, 0, 0]);
+        assert_eq!(guard_calls(), 5);
+        assert!(!guard_violated());
+
+        assert!(copy::<2>(&mut dst[..2], &[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn swap_buffers() {
+        let mut buf_1 = [1, 2, 3];
+        let mut buf_2 = [4, 5, 6];
+
+        assert!(swap::<1>(&mut buf_1, &mut buf_2).is_ok());
+        assert_eq!(buf_1, [4, 5, 6]);
+        assert_eq!(buf_2, [1, 2, 3]);
+        assert_eq!(guard_calls(), 4);
+        assert!(!guard_violated());
+
+        assert!(swap::<2>(&mut buf_1, &mut [0; 2]).is_err());
+    }
+
+    #[test]
+    fn ct_equal_checks_every_byte() {
+        assert!(ct_equal::<1>(b"secret", b"secret"));
+        assert!(!ct_equal::<2>(b"secret", b"Secret"));
+        assert!(!ct_equal::<3>(b"secret", b"secrets"));
+        // no early exit on the first difference
+        assert_eq!(guard_calls(), 14);
+        assert!(!guard_violated());
+    }
+
+    #[test]
+    fn starts_with_prefix() {
+        assert!(starts_with::<1>(b"ABCDEF", b"ABC"));
+        assert!(!starts_with::<2>(b"ABCDEF", b"ABD"));
+        assert!(!starts_with::<3>(b"AB", b"ABC"));
+        assert!(starts_with::<4>(b"AB", b""));
+        assert!(!guard_violated());
+    }
+
+    #[test]
+    fn find_needle() {
+        assert_eq!(find::<1>(b"hello world", b"world"), Some(6));
+        assert_eq!(find::<2>(b"aaab", b"ab"), Some(2));
+        assert_eq!(find::<3>(b"hello", b"xyz"), None);
+        assert_eq!(find::<4>(b"hi", b"hello"), None);
+        assert_eq!(find::<5>(b"hello", b""), Some(0));
+        assert!(!guard_violated());
+    }
+
+    #[test]
+    fn find_worst_case_within_budget() {
+        let haystack = [b'a'; 16];
+        let needle = [b'a', b'a', b'a', b'b'];
+
+        assert_eq!(find::<1>(&haystack, &needle), None);
+        assert!(guard_calls() <= find_budget(16, 4) + 1);
+        assert!(!guard_violated());
+    }
+}
@@ -0,0 +1,272 @@
+use crate::api::*;
+use crate::state::{StateCodec, StateMap, StateNamespace};
+use crate::txn::OriginatingTxn;
+
+/// `cbak` argument of a transaction emitted and applied to a ledger
+pub const CBAK_EMITTED_APPLIED: u32 = 0;
+/// `cbak` argument of a transaction emitted, but failed to apply before its `LastLedgerSequence`
+pub const CBAK_EMIT_FAILURE: u32 = 1;
+
+/// `tesSUCCESS` transaction result
+pub const TES_SUCCESS: u8 = 0;
+
+/// Context of a `cbak` call, made for a transaction the hook emitted
+///
+/// In the callback the originating transaction is the emitted one.
+///
+/// ``` txt
+/// #[no_mangle]
+/// pub extern "C" fn cbak(reserved: u32) -> i64 {
+///     let ctx = CallbackContext::new(reserved);
+///     let pending: EmittedTags<Pending, u64> = EmittedTags::new();
+///
+///     let order_id = match ctx.take_tag(&pending) {
+///         Ok(Some(order_id)) => order_id,
+///         _ => accept(b"not ours", 0),
+///     };
+///
+///     match ctx.succeeded(1) {
+///         Ok(true) => ...,
+///         _ => ...,
+///     }
+/// }
+/// ```
+#[derive(Clone, Copy)]
+pub struct CallbackContext {
+    reserved: u32,
+}
+
+impl CallbackContext {
+    /// Creates the context from the `cbak` argument
+    #[inline(always)]
+    pub const fn new(reserved: u32) -> Self {
+        CallbackContext { reserved }
+    }
+
+    /// The emitted transaction didn't make it into a ledger, it has no metadata
+    #[inline(always)]
+    pub const fn emit_failed(&self) -> bool {
+        self.reserved == CBAK_EMIT_FAILURE
+    }
+
+    /// Hash of the emitted transaction, the one `emit` returned
+    #[inline(always)]
+    pub fn emitted_txn_id(&self) -> Result<Hash> {
+        self.txn().hash()
+    }
+
+    /// Typed view of the emitted transaction
+    #[inline(always)]
+    pub const fn txn(&self) -> OriginatingTxn {
+        OriginatingTxn
+    }
+
+    /// `TransactionResult` of the emitted transaction, `None` if the emission failed
+    ///
+    /// Loads the metadata into `slot_no` with [meta_slot], 0 lets the host pick a free slot.
+    /// The slots are freed before returning.
+    #[cfg(feature = "api-v2")]
+    #[inline(always)]
+    pub fn result(&self, slot_no: u32) -> Result<Option<u8>> {
+        if self.emit_failed() {
+            return Ok(None);
+        }
+
+        let meta = match meta_slot(slot_no) {
+            Ok(slot_no) => slot_no as u32,
+            Err(e) => return Err(e),
+        };
+
+        let res = match slot_subfield(meta, FieldId::TransactionResult, 0) {
+            Ok(result_slot) => {
+                let mut result = [0; 1];
+                let res = slot(&mut result, result_slot as u32);
+                let _ = slot_clear(result_slot as u32);
+
+                match res {
+                    Ok(_) => Ok(Some(result[0])),
+                    Err(e) => Err(e),
+                }
+            }
+            Err(e) => Err(e),
+        };
+
+        let _ = slot_clear(meta);
+
+        res
+    }
+
+    /// The emitted transaction was applied with `tesSUCCESS`, see [CallbackContext::result]
+    #[cfg(feature = "api-v2")]
+    #[inline(always)]
+    pub fn succeeded(&self, slot_no: u32) -> Result<bool> {
+        match self.result(slot_no) {
+            Ok(result) => Ok(result == Some(TES_SUCCESS)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Removes and returns the tag of the emitted transaction, see [EmittedTags]
+    #[inline(always)]
+    pub fn take_tag<N: StateNamespace, T: StateCodec>(
+        &self,
+        tags: &EmittedTags<N, T>,
+    ) -> Result<Option<T>> {
+        match self.emitted_txn_id() {
+            Ok(hash) => tags.take(&hash),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Hook-defined tags of emitted transactions, kept in the hook state
+///
+/// Insert a tag under the hash `emit` writes, take it back in `cbak`
+/// with [CallbackContext::take_tag]. Every pending tag holds a state entry,
+/// so a tag must be taken, also when the emission fails.
+///
+/// ``` txt
+/// struct Pending;
+///
+/// impl StateNamespace for Pending {
+///     const NAME: &'static [u8] = b"pending";
+/// }
+///
+/// let mut hash = [0; HASH_LEN];
+/// emit(&mut hash, &txn).unwrap();
+///
+/// EmittedTags::<Pending, u64>::new().insert(&hash, &order_id).unwrap();
+/// ```
+pub struct EmittedTags<N, T> {
+    map: StateMap<N, Hash, T>,
+}
+
+impl<N: StateNamespace, T: StateCodec> Default for EmittedTags<N, T> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: StateNamespace, T: StateCodec> EmittedTags<N, T> {
+    /// Creates the table
+    #[inline(always)]
+    pub const fn new() -> Self {
+        EmittedTags {
+            map: StateMap::new(),
+        }
+    }
+
+    /// Tags the emitted transaction
+    #[inline(always)]
+    pub fn insert(&self, emitted_hash: &Hash, tag: &T) -> Result<()> {
+        self.map.set(emitted_hash, tag)
+    }
+
+    /// The tag of the emitted transaction
+    #[inline(always)]
+    pub fn get(&self, emitted_hash: &Hash) -> Result<Option<T>> {
+        self.map.get(emitted_hash)
+    }
+
+    /// Removes and returns the tag of the emitted transaction
+    #[inline(always)]
+    pub fn take(&self, emitted_hash: &Hash) -> Result<Option<T>> {
+        let entry = match self.map.entry(emitted_hash) {
+            Ok(entry) => entry,
+            Err(e) => return Err(e),
+        };
+
+        let tag = match entry.load() {
+            Ok(Some(tag)) => tag,
+            Ok(None) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        match entry.remove() {
+            Ok(_) => Ok(Some(tag)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Sto};
+
+    struct Pending;
+
+    impl StateNamespace for Pending {
+        const NAME: &'static [u8] = b"pending";
+    }
+
+    #[test]
+    fn emit_failed() {
+        assert!(!CallbackContext::new(CBAK_EMITTED_APPLIED).emit_failed());
+        assert!(CallbackContext::new(CBAK_EMIT_FAILURE).emit_failed());
+    }
+
+    #[cfg(feature = "api-v2")]
+    fn metadata(result: u8) {
+        let meta = Sto::new()
+            .fixed(FieldId::TransactionResult as _, &[result])
+            .build();
+
+        testing::host(|host| host.meta = Some(meta));
+    }
+
+    #[cfg(feature = "api-v2")]
+    #[test]
+    fn callback_result() {
+        let ctx = CallbackContext::new(CBAK_EMITTED_APPLIED);
+        assert!(matches!(ctx.result(0), Err(Error::PrerequisiteNotMet)));
+
+        metadata(TES_SUCCESS);
+        assert!(matches!(ctx.result(0), Ok(Some(TES_SUCCESS))));
+        assert!(matches!(ctx.succeeded(0), Ok(true)));
+
+        // tecUNFUNDED_PAYMENT
+        metadata(104);
+        assert!(matches!(ctx.result(3), Ok(Some(104))));
+        assert!(matches!(ctx.succeeded(0), Ok(false)));
+        assert_eq!(testing::host(|host| host.slots_in_use()), 0);
+
+        // no metadata to look at
+        let failed = CallbackContext::new(CBAK_EMIT_FAILURE);
+        assert!(matches!(failed.result(0), Ok(None)));
+        assert!(matches!(failed.succeeded(0), Ok(false)));
+    }
+
+    #[test]
+    fn emitted_tags() {
+        let tags: EmittedTags<Pending, u64> = EmittedTags::new();
+        let hash = [0xE7; HASH_LEN];
+
+        assert!(tags.insert(&hash, &99).is_ok());
+        assert!(matches!(tags.get(&hash), Ok(Some(99))));
+        assert!(matches!(tags.take(&hash), Ok(Some(99))));
+        assert!(matches!(tags.take(&hash), Ok(None)));
+        assert!(testing::host(|host| host.state.is_empty()));
+    }
+
+    #[test]
+    fn callback_tags() {
+        testing::host(|host| host.otxn = Sto::new().u16(FieldId::TransactionType as _, 0).build());
+
+        let ctx = CallbackContext::new(CBAK_EMITTED_APPLIED);
+        let tags: EmittedTags<Pending, u64> = EmittedTags::new();
+
+        let hash = match ctx.emitted_txn_id() {
+            Ok(hash) => hash,
+            Err(_) => panic!("no transaction id"),
+        };
+        assert!(tags.insert(&hash, &7).is_ok());
+        assert!(tags.insert(&[0; HASH_LEN], &8).is_ok());
+
+        assert!(matches!(ctx.take_tag(&tags), Ok(Some(7))));
+        assert!(matches!(ctx.take_tag(&tags), Ok(None)));
+        // the tag of the other transaction is left
+        assert!(matches!(tags.get(&[0; HASH_LEN]), Ok(Some(8))));
+    }
+}
@@ -0,0 +1,401 @@
+use crate::api::*;
+
+/// Encode u16 as big-endian bytes, an analogue of `UINT16_TO_BUF`
+#[inline(always)]
+pub const fn u16_to_buf(i: u16) -> [u8; 2] {
+    i.to_be_bytes()
+}
+
+/// Decode u16 from big-endian bytes, an analogue of `UINT16_FROM_BUF`
+#[inline(always)]
+pub const fn u16_from_buf(buf: &[u8; 2]) -> u16 {
+    u16::from_be_bytes(*buf)
+}
+
+/// Encode u32 as big-endian bytes, an analogue of `UINT32_TO_BUF`
+#[inline(always)]
+pub const fn u32_to_buf(i: u32) -> [u8; 4] {
+    i.to_be_bytes()
+}
+
+/// Decode u32 from big-endian bytes, an analogue of `UINT32_FROM_BUF`
+#[inline(always)]
+pub const fn u32_from_buf(buf: &[u8; 4]) -> u32 {
+    u32::from_be_bytes(*buf)
+}
+
+/// Encode u64 as big-endian bytes, an analogue of `UINT64_TO_BUF`
+#[inline(always)]
+pub const fn u64_to_buf(i: u64) -> [u8; 8] {
+    i.to_be_bytes()
+}
+
+/// Decode u64 from big-endian bytes, an analogue of `UINT64_FROM_BUF`
+#[inline(always)]
+pub const fn u64_from_buf(buf: &[u8; 8]) -> u64 {
+    u64::from_be_bytes(*buf)
+}
+
+/// Encode i64 as big-endian two's complement bytes, an analogue of `INT64_TO_BUF`
+#[inline(always)]
+pub const fn i64_to_buf(i: i64) -> [u8; 8] {
+    i.to_be_bytes()
+}
+
+/// Decode i64 from big-endian two's complement bytes
+///
+/// Unlike `INT64_FROM_BUF` this is the exact inverse of [i64_to_buf].
+#[inline(always)]
+pub const fn i64_from_buf(buf: &[u8; 8]) -> i64 {
+    i64::from_be_bytes(*buf)
+}
+
+/// Encode the XFL enclosing number as big-endian bytes
+#[inline(always)]
+pub const fn xfl_to_buf(float: XFL) -> [u8; 8] {
+    i64_to_buf(float.0)
+}
+
+/// Decode the XFL enclosing number from big-endian bytes
+///
+/// Returns [Error::InvalidFloat] for a negative enclosing number.
+#[inline(always)]
+pub const fn xfl_from_buf(buf: &[u8; 8]) -> Result<XFL> {
+    match i64_from_buf(buf) {
+        enclosing if enclosing >= 0 => Ok(XFL(enclosing)),
+        _ => Err(Error::InvalidFloat),
+    }
+}
+
+/// Sequential big-endian writer over a byte buffer
+///
+/// Every write is bounds-checked and returns [Error::TooSmall]
+/// instead of writing past the end. Writes are fixed-size, so no guards are needed.
+pub struct BufWriter<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl<'a> BufWriter<'a> {
+    /// Creates a writer at the beginning of the buffer
+    #[inline(always)]
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        BufWriter { buf, pos: 0 }
+    }
+
+    /// Number of bytes written so far
+    #[inline(always)]
+    pub const fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Number of bytes left
+    #[inline(always)]
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    /// The written part of the buffer
+    #[inline(always)]
+    pub fn written(&self) -> &[u8] {
+        &self.buf[..self.pos]
+    }
+
+    /// Skip `n` bytes, leaving them as they are
+    #[inline(always)]
+    pub fn skip(&mut self, n: usize) -> Result<()> {
+        if self.remaining() < n {
+            return Err(Error::TooSmall);
+        }
+
+        self.pos += n;
+
+        Ok(())
+    }
+
+    /// Write a fixed-size array
+    #[inline(always)]
+    pub fn write_bytes<const N: usize>(&mut self, bytes: &[u8; N]) -> Result<()> {
+        let end = match self.pos.checked_add(N) {
+            Some(end) => end,
+            None => return Err(Error::TooSmall),
+        };
+
+        match self.buf.get_mut(self.pos..end) {
+            Some(dst) => {
+                dst.copy_from_slice(bytes);
+                self.pos += N;
+                Ok(())
+            }
+            None => Err(Error::TooSmall),
+        }
+    }
+
+    /// Write a slice
+    ///
+    /// The copy has no guards, keep the length known at compile time
+    /// (a constant or a fixed-size buffer) so it is unrolled in the wasm binary.
+    #[inline(always)]
+    pub fn write_slice(&mut self, bytes: &[u8]) -> Result<()> {
+        let end = match self.pos.checked_add(bytes.len()) {
+            Some(end) => end,
+            None => return Err(Error::TooSmall),
+        };
+
+        match self.buf.get_mut(self.pos..end) {
+            Some(dst) => {
+                dst.copy_from_slice(bytes);
+                self.pos += bytes.len();
+                Ok(())
+            }
+            None => Err(Error::TooSmall),
+        }
+    }
+
+    /// Write a byte
+    #[inline(always)]
+    pub fn write_u8(&mut self, i: u8) -> Result<()> {
+        self.write_bytes(&[i])
+    }
+
+    /// Write a big-endian u16
+    #[inline(always)]
+    pub fn write_u16(&mut self, i: u16) -> Result<()> {
+        self.write_bytes(&u16_to_buf(i))
+    }
+
+    /// Write a big-endian u32
+    #[inline(always)]
+    pub fn write_u32(&mut self, i: u32) -> Result<()> {
+        self.write_bytes(&u32_to_buf(i))
+    }
+
+    /// Write a big-endian u64
+    #[inline(always)]
+    pub fn write_u64(&mut self, i: u64) -> Result<()> {
+        self.write_bytes(&u64_to_buf(i))
+    }
+
+    /// Write a big-endian i64
+    #[inline(always)]
+    pub fn write_i64(&mut self, i: i64) -> Result<()> {
+        self.write_bytes(&i64_to_buf(i))
+    }
+
+    /// Write an XFL enclosing number
+    #[inline(always)]
+    pub fn write_xfl(&mut self, float: XFL) -> Result<()> {
+        self.write_bytes(&xfl_to_buf(float))
+    }
+}
+
+/// Sequential big-endian reader over a byte buffer
+///
+/// Every read is bounds-checked and returns [Error::TooSmall]
+/// instead of reading past the end. Reads are fixed-size, so no guards are needed.
+#[derive(Clone, Copy)]
+pub struct BufReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BufReader<'a> {
+    /// Creates a reader at the beginning of the buffer
+    #[inline(always)]
+    pub const fn new(buf: &'a [u8]) -> Self {
+        BufReader { buf, pos: 0 }
+    }
+
+    /// Number of bytes read so far
+    #[inline(always)]
+    pub const fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Number of bytes left
+    #[inline(always)]
+    pub const fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    /// Skip `n` bytes
+    #[inline(always)]
+    pub fn skip(&mut self, n: usize) -> Result<()> {
+        if self.remaining() < n {
+            return Err(Error::TooSmall);
+        }
+
+        self.pos += n;
+
+        Ok(())
+    }
+
+    /// Read a fixed-size array
+    #[inline(always)]
+    pub fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let end = match self.pos.checked_add(N) {
+            Some(end) => end,
+            None => return Err(Error::TooSmall),
+        };
+
+        match self.buf.get(self.pos..end) {
+            Some(src) => {
+                let mut bytes = [0; N];
+                bytes.copy_from_slice(src);
+                self.pos += N;
+                Ok(bytes)
+            }
+            None => Err(Error::TooSmall),
+        }
+    }
+
+    /// Borrow the next `n` bytes
+    #[inline(always)]
+    pub fn read_slice(&mut self, n: usize) -> Result<&'a [u8]> {
+        let end = match self.pos.checked_add(n) {
+            Some(end) => end,
+            None => return Err(Error::TooSmall),
+        };
+
+        match self.buf.get(self.pos..end) {
+            Some(src) => {
+                self.pos += n;
+                Ok(src)
+            }
+            None => Err(Error::TooSmall),
+        }
+    }
+
+    /// Read a byte
+    #[inline(always)]
+    pub fn read_u8(&mut self) -> Result<u8> {
+        match self.read_bytes::<1>() {
+            Ok(bytes) => Ok(bytes[0]),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Read a big-endian u16
+    #[inline(always)]
+    pub fn read_u16(&mut self) -> Result<u16> {
+        match self.read_bytes() {
+            Ok(bytes) => Ok(u16_from_buf(&bytes)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Read a big-endian u32
+    #[inline(always)]
+    pub fn read_u32(&mut self) -> Result<u32> {
+        match self.read_bytes() {
+            Ok(bytes) => Ok(u32_from_buf(&bytes)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Read a big-endian u64
+    #[inline(always)]
+    pub fn read_u64(&mut self) -> Result<u64> {
+        match self.read_bytes() {
+            Ok(bytes) => Ok(u64_from_buf(&bytes)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Read a big-endian i64
+    #[inline(always)]
+    pub fn read_i64(&mut self) -> Result<i64> {
+        match self.read_bytes() {
+            Ok(bytes) => Ok(i64_from_buf(&bytes)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Read an XFL enclosing number
+    #[inline(always)]
+    pub fn read_xfl(&mut self) -> Result<XFL> {
+        match self.read_bytes() {
+            Ok(bytes) => xfl_from_buf(&bytes),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_match_c_macros() {
+        assert_eq!(u16_to_buf(0x1234), [0x12, 0x34]);
+        assert_eq!(u32_to_buf(0x1234_5678), [0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(u64_to_buf(0x0102_0304_0506_0708), [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(u32_from_buf(&[0x12, 0x34, 0x56, 0x78]), 0x1234_5678);
+        assert_eq!(i64_to_buf(-1), [0xFF; 8]);
+        assert_eq!(i64_from_buf(&i64_to_buf(-42)), -42);
+        assert_eq!(i64_from_buf(&i64_to_buf(i64::MIN)), i64::MIN);
+    }
+
+    #[test]
+    fn xfl_roundtrip() {
+        let float = XFL(6089866696204910592);
+
+        assert!(matches!(
+            xfl_from_buf(&xfl_to_buf(float)),
+            Ok(XFL(6089866696204910592))
+        ));
+        assert!(xfl_from_buf(&i64_to_buf(-1)).is_err());
+    }
+
+    #[test]
+    fn writer_reader_roundtrip() {
+        let mut buf = [0; 23];
+        let mut writer = BufWriter::new(&mut buf);
+
+        assert!(writer.write_u8(7).is_ok());
+        assert!(writer.write_u16(0xBEEF).is_ok());
+        assert!(writer.write_u32(0xDEAD_BEEF).is_ok());
+        assert!(writer.write_i64(-5).is_ok());
+        assert!(writer.write_bytes(&[1, 2, 3, 4, 5, 6, 7, 8]).is_ok());
+        assert_eq!(writer.position(), 23);
+        assert!(writer.write_u8(0).is_err());
+
+        let mut reader = BufReader::new(&buf);
+
+        assert!(matches!(reader.read_u8(), Ok(7)));
+        assert!(matches!(reader.read_u16(), Ok(0xBEEF)));
+        assert!(matches!(reader.read_u32(), Ok(0xDEAD_BEEF)));
+        assert!(matches!(reader.read_i64(), Ok(-5)));
+        assert!(matches!(
+            reader.read_slice(8),
+            Ok(&[1, 2, 3, 4, 5, 6, 7, 8])
+        ));
+        assert_eq!(reader.remaining(), 0);
+        assert!(reader.read_u8().is_err());
+    }
+
+    #[test]
+    fn writer_does_not_write_partially() {
+        let mut buf = [0; 3];
+        let mut writer = BufWriter::new(&mut buf);
+
+        assert!(writer.write_u32(0xFFFF_FFFF).is_err());
+        assert_eq!(writer.position(), 0);
+        assert_eq!(buf, [0; 3]);
+    }
+
+    #[test]
+    fn reader_lengths_past_usize() {
+        let buf = [1, 2, 3];
+        let mut reader = BufReader::new(&buf);
+
+        assert!(matches!(reader.read_u8(), Ok(1)));
+        assert!(matches!(
+            reader.read_slice(usize::MAX),
+            Err(Error::TooSmall)
+        ));
+        assert!(matches!(reader.skip(usize::MAX), Err(Error::TooSmall)));
+        assert_eq!(reader.position(), 1);
+        assert!(matches!(reader.read_slice(2), Ok(&[2, 3])));
+    }
+}
@@ -0,0 +1,396 @@
+use core::ops::Range;
+
+use crate::api::*;
+use crate::iter::{GuardedIter, GuardedIterator};
+use crate::objects::{DirectoryNode, LedgerObject};
+
+/// Entries of the owner directory of the account, see [directory_entries]
+#[inline(always)]
+pub fn owner_directory<'a, const GUARD_ID: u32>(
+    account: &AccountId,
+    buf: &'a mut [u8],
+    max_pages: u32,
+    maxiter: u32,
+) -> Result<GuardedIter<DirEntries<'a, GUARD_ID>, GUARD_ID>> {
+    let mut keylet: Keylet = [0; KEYLET_LEN];
+
+    match util_keylet(&mut keylet, KeyletType::OwnerDir(account)) {
+        Ok(_) => Ok(directory_entries::<GUARD_ID>(
+            &directory_root(&keylet),
+            buf,
+            max_pages,
+            maxiter,
+        )),
+        Err(e) => Err(e),
+    }
+}
+
+/// Entries of the directory with the `root` key, as `Unchecked` keylets
+///
+/// Slots the pages one at a time, following `IndexNext` from the root,
+/// and frees the slot before moving on. `buf` takes a page at a time,
+/// [DIRECTORY_PAGE_BUF_LEN](crate::objects::DIRECTORY_PAGE_BUF_LEN) bytes fit any page. Reads `max_pages` pages at most,
+/// then yields [Error::TooBig] if the directory goes on. A directory that
+/// doesn't exist has no entries. The iteration stops after an error.
+///
+/// `maxiter` is the maximum number of entries taken, through the entire hook execution.
+///
+/// Pay attention to the GUARD_ID parameter.
+/// This should be unique on every call, through the entire hook code.
+/// Otherwise you will encounter guard violation during the execution of your hook.
+/// `GUARD_ID` guards the entries and `GUARD_ID + 1` the pages, keep both unique.
+///
+/// ``` txt
+/// let mut page: [u8; DIRECTORY_PAGE_BUF_LEN] = uninit_buf!();
+///
+/// for keylet in owner_directory::<{ guard_id!() }>(&account, &mut page, 4, 128).unwrap() {
+///     let keylet = match keylet {
+///         Ok(keylet) => keylet,
+///         Err(e) => rollback(b"directory", e.code() as _),
+///     };
+///
+///     let escrow = slot_set(&keylet, 0).unwrap();
+///     // ...
+/// }
+/// ```
+#[inline(always)]
+pub fn directory_entries<'a, const GUARD_ID: u32>(
+    root: &Hash,
+    buf: &'a mut [u8],
+    max_pages: u32,
+    maxiter: u32,
+) -> GuardedIter<DirEntries<'a, GUARD_ID>, GUARD_ID> {
+    DirEntries {
+        root: *root,
+        buf,
+        indexes: 0..0,
+        next_page: Some(0),
+        pages: 0,
+        max_pages,
+        maxiter,
+        done: false,
+    }
+    .guarded_iter::<GUARD_ID>(maxiter)
+}
+
+/// Key of the directory, the last 32 bytes of its keylet
+#[inline(always)]
+pub fn directory_root(keylet: &Keylet) -> Hash {
+    let mut root = [0; HASH_LEN];
+    root.clone_from_slice(&keylet[KEYLET_LEN - HASH_LEN..]);
+
+    root
+}
+
+/// Iterator over the entries of a directory
+///
+/// Obtained guarded from [directory_entries] or [owner_directory].
+pub struct DirEntries<'a, const GUARD_ID: u32> {
+    root: Hash,
+    buf: &'a mut [u8],
+    indexes: Range<usize>,
+    next_page: Option<u64>,
+    pages: u32,
+    max_pages: u32,
+    maxiter: u32,
+    done: bool,
+}
+
+impl<'a, const GUARD_ID: u32> Iterator for DirEntries<'a, GUARD_ID> {
+    type Item = Result<Keylet>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        // once per entry and once per page, the empty root page included
+        let page_guard_max = self
+            .maxiter
+            .saturating_add(self.max_pages)
+            .saturating_add(1);
+
+        // guarded loop
+        while {
+            _g(GUARD_ID.wrapping_add(1), page_guard_max.saturating_add(1));
+            !self.done && self.indexes.is_empty()
+        } {
+            let page = match self.next_page {
+                Some(page) => page,
+                None => {
+                    self.done = true;
+                    break;
+                }
+            };
+
+            if self.pages >= self.max_pages {
+                self.done = true;
+                return Some(Err(Error::TooBig));
+            }
+
+            match self.load_page(page) {
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+                Ok(_) => {}
+            }
+        }
+
+        let index = self.take_index()?;
+
+        let mut keylet: Keylet = [0; KEYLET_LEN];
+
+        match util_keylet(&mut keylet, KeyletType::Unchecked(&index)) {
+            Ok(_) => Some(Ok(keylet)),
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<'a, const GUARD_ID: u32> DirEntries<'a, GUARD_ID> {
+    /// Slots the page and keeps its entries in the buffer
+    #[inline(always)]
+    fn load_page(&mut self, page: u64) -> Result<()> {
+        let mut keylet: Keylet = [0; KEYLET_LEN];
+
+        match util_keylet(
+            &mut keylet,
+            KeyletType::Page(&self.root, (page >> 32) as u32, page as u32),
+        ) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+
+        self.pages += 1;
+
+        let page_slot = match slot_set(&keylet, 0) {
+            Ok(slot_no) => slot_no as u32,
+            // no root, no directory
+            Err(Error::DoesntExist) if page == 0 => {
+                self.next_page = None;
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        let res = slot(self.buf, page_slot);
+        let _ = slot_clear(page_slot);
+
+        let len = match res {
+            Ok(len) => len as usize,
+            Err(e) => return Err(e),
+        };
+
+        let (indexes, index_next) = match DirectoryNode::from_sto(&self.buf[..len]) {
+            Ok(node) => {
+                let start = node.indexes.as_ptr() as usize - self.buf.as_ptr() as usize;
+                (start..start + node.len() * HASH_LEN, node.index_next)
+            }
+            Err(e) => return Err(e),
+        };
+
+        self.indexes = indexes;
+        self.next_page = if index_next == 0 {
+            None
+        } else {
+            Some(index_next)
+        };
+
+        Ok(())
+    }
+
+    /// Takes the next key of the loaded page
+    #[inline(always)]
+    fn take_index(&mut self) -> Option<Hash> {
+        if self.indexes.is_empty() {
+            return None;
+        }
+
+        let start = self.indexes.start;
+        let mut index = [0; HASH_LEN];
+        index.clone_from_slice(&self.buf[start..start + HASH_LEN]);
+        self.indexes.start += HASH_LEN;
+
+        Some(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::DIRECTORY_PAGE_BUF_LEN;
+    use crate::testing::{self, Sto};
+
+    const ROOT: Hash = [0xD1; HASH_LEN];
+
+    fn entry(n: u8) -> Hash {
+        [n; HASH_LEN]
+    }
+
+    /// Puts the page `page` of the [ROOT] directory in the ledger
+    fn insert_page(page: u64, entries: &[Hash], index_next: u64) {
+        let keylet = testing::keylet(KeyletType::Page(&ROOT, (page >> 32) as u32, page as u32));
+        let mut indexes = [0; 32 * HASH_LEN];
+        for (i, entry) in entries.iter().enumerate() {
+            indexes[i * HASH_LEN..(i + 1) * HASH_LEN].copy_from_slice(entry);
+        }
+        let object = Sto::new()
+            .u16(FieldId::LedgerEntryType as _, 0x64)
+            .u64(FieldId::IndexNext as _, index_next)
+            .fixed(FieldId::RootIndex as _, &ROOT)
+            .vl_field(FieldId::Indexes as _, &indexes[..entries.len() * HASH_LEN])
+            .build();
+
+        testing::host(|host| host.insert_object(&keylet, object));
+    }
+
+    /// Three pages: 1 and 2 on the root, 3 on page 1, 4 and 5 on page 7
+    fn insert_directory() {
+        insert_page(0, &[entry(1), entry(2)], 1);
+        insert_page(1, &[entry(3)], 7);
+        insert_page(7, &[entry(4), entry(5)], 0);
+    }
+
+    #[test]
+    fn traversal() {
+        let mut page = [0; DIRECTORY_PAGE_BUF_LEN];
+        insert_directory();
+
+        let mut entries = directory_entries::<10>(&ROOT, &mut page, 3, 5);
+        for n in 1..=5 {
+            match entries.next() {
+                Some(Ok(keylet)) => assert_eq!(keylet[2..], entry(n)),
+                _ => panic!("entry {} missing", n),
+            }
+        }
+        assert!(entries.next().is_none());
+
+        assert_eq!(testing::host(|host| host.slots_in_use()), 0);
+        assert!(!testing::guard_violated());
+    }
+
+    #[test]
+    fn too_many_pages() {
+        let mut page = [0; DIRECTORY_PAGE_BUF_LEN];
+        insert_directory();
+
+        let mut entries = directory_entries::<20>(&ROOT, &mut page, 2, 5);
+        for n in 1..=3 {
+            match entries.next() {
+                Some(Ok(keylet)) => assert_eq!(keylet[2..], entry(n)),
+                _ => panic!("entry {} missing", n),
+            }
+        }
+        assert!(matches!(entries.next(), Some(Err(Error::TooBig))));
+        assert!(entries.next().is_none());
+
+        assert_eq!(testing::host(|host| host.slots_in_use()), 0);
+    }
+
+    #[test]
+    fn missing_root() {
+        let mut page = [0; DIRECTORY_PAGE_BUF_LEN];
+        let mut entries = directory_entries::<30>(&ROOT, &mut page, 4, 16);
+
+        assert!(entries.next().is_none());
+        assert!(entries.next().is_none());
+        assert!(!testing::guard_violated());
+    }
+
+    #[test]
+    fn page_guard_budget() {
+        let mut page = [0; DIRECTORY_PAGE_BUF_LEN];
+        insert_directory();
+
+        // as many entries and pages as the directory holds
+        let count = directory_entries::<40>(&ROOT, &mut page, 3, 5)
+            .filter(|entry| entry.is_ok())
+            .count();
+
+        assert_eq!(count, 5);
+        // the whole budget of the page guard, and no more
+        assert_eq!(testing::guard_calls_of(41), 5 + 3 + 1);
+        assert!(!testing::guard_violated());
+
+        // an empty root page still costs a call
+        testing::host(|host| host.ledger.clear());
+        insert_page(0, &[], 0);
+        assert_eq!(directory_entries::<50>(&ROOT, &mut page, 1, 0).count(), 0);
+        assert_eq!(testing::guard_calls_of(51), 2);
+        assert!(!testing::guard_violated());
+
+        // no overflow when the pages and the entries overflow the budget
+        assert_eq!(
+            directory_entries::<60>(&ROOT, &mut page, u32::MAX, 16).count(),
+            0
+        );
+        assert!(!testing::guard_violated());
+    }
+
+    #[test]
+    fn full_page_size() {
+        // 32 entries, the most the host puts in a page, with the fields of owner and book pages
+        let keylet = testing::keylet(KeyletType::Page(&ROOT, 0, 0));
+        let object = Sto::new()
+            .u16(FieldId::LedgerEntryType as _, 0x64)
+            .u32(FieldId::Flags as _, 0)
+            .u64(FieldId::IndexNext as _, 0)
+            .u64(FieldId::IndexPrevious as _, 0)
+            .u64(FieldId::ExchangeRate as _, 0)
+            .fixed(FieldId::RootIndex as _, &ROOT)
+            .fixed(FieldId::TakerPaysCurrency as _, &[0; 20])
+            .fixed(FieldId::TakerPaysIssuer as _, &[0; 20])
+            .fixed(FieldId::TakerGetsCurrency as _, &[0; 20])
+            .fixed(FieldId::TakerGetsIssuer as _, &[0; 20])
+            .vl_field(FieldId::Owner as _, &[0; ACC_ID_LEN])
+            .vl_field(FieldId::Indexes as _, &[0x5A; 32 * HASH_LEN])
+            .build();
+        assert!(object.len() <= DIRECTORY_PAGE_BUF_LEN);
+        testing::host(|host| host.insert_object(&keylet, object));
+
+        let mut page = [0; DIRECTORY_PAGE_BUF_LEN];
+        let count = directory_entries::<70>(&ROOT, &mut page, 1, 32)
+            .filter(|entry| entry.is_ok())
+            .count();
+        assert_eq!(count, 32);
+
+        // the host doesn't serialize the page into a shorter buffer
+        let mut entries = directory_entries::<80>(&ROOT, &mut page[..1024], 1, 32);
+        assert!(matches!(entries.next(), Some(Err(Error::TooSmall))));
+        assert_eq!(testing::host(|host| host.slots_in_use()), 0);
+    }
+
+    #[test]
+    fn page_indexes() {
+        let mut page = [0; DIRECTORY_PAGE_BUF_LEN];
+        let mut entries = DirEntries::<1> {
+            root: [0; HASH_LEN],
+            buf: &mut page,
+            indexes: 8..8 + 2 * HASH_LEN,
+            next_page: None,
+            pages: 1,
+            max_pages: 1,
+            maxiter: 2,
+            done: false,
+        };
+        entries.buf[8] = 1;
+        entries.buf[8 + HASH_LEN] = 2;
+
+        assert_eq!(entries.take_index().map(|index| index[0]), Some(1));
+        assert_eq!(entries.take_index().map(|index| index[0]), Some(2));
+        assert_eq!(entries.take_index(), None);
+    }
+
+    #[test]
+    fn root() {
+        let mut keylet = [0; KEYLET_LEN];
+        keylet[1] = 0x64;
+        keylet[2] = 0xAB;
+
+        let root = directory_root(&keylet);
+
+        assert_eq!(root[0], 0xAB);
+        assert_eq!(root[HASH_LEN - 1], 0);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::api::*;
+
+/// Emission budget of a hook execution
+///
+/// Reserves the emissions once with [etxn_reserve] and counts them down,
+/// so going over the budget is an error before `emit` is called.
+///
+/// ``` txt
+/// let mut plan = EmissionPlan::reserve(2).unwrap();
+///
+/// let mut txn = [0; PREPARE_PAYMENT_SIMPLE_SIZE];
+/// let len = prepare_payment_simple(&mut txn, drops, 0, &to, 0, 0).unwrap();
+/// plan.fill_fee(&mut txn[..len]).unwrap();
+///
+/// let hash = match plan.emit(&txn[..len]) {
+///     Ok(hash) => hash,
+///     Err(e) => rollback(b"emit failed", e.code() as _),
+/// };
+/// ```
+#[derive(Clone, Copy)]
+pub struct EmissionPlan {
+    reserved: u32,
+    emitted: u32,
+}
+
+impl EmissionPlan {
+    /// Reserves `count` emissions, call it once per execution
+    #[inline(always)]
+    pub fn reserve(count: u32) -> Result<Self> {
+        match etxn_reserve(count) {
+            Ok(_) => Ok(Self::from_reserved(count)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Plan for `count` emissions already reserved with [etxn_reserve]
+    #[inline(always)]
+    pub const fn from_reserved(count: u32) -> Self {
+        EmissionPlan {
+            reserved: count,
+            emitted: 0,
+        }
+    }
+
+    /// Reserved emissions
+    #[inline(always)]
+    pub const fn reserved(&self) -> u32 {
+        self.reserved
+    }
+
+    /// Emitted so far
+    #[inline(always)]
+    pub const fn emitted(&self) -> u32 {
+        self.emitted
+    }
+
+    /// Emissions left
+    #[inline(always)]
+    pub const fn remaining(&self) -> u32 {
+        self.reserved - self.emitted
+    }
+
+    /// Minimum fee of the serialized transaction, see [etxn_fee_base]
+    ///
+    /// `txn` must be the whole transaction, `EmitDetails` included.
+    #[inline(always)]
+    pub fn fee(&self, txn: &[u8]) -> Result<u64> {
+        #[cfg(feature = "api-v2")]
+        let res = etxn_fee_base(txn);
+        #[cfg(not(feature = "api-v2"))]
+        let res = etxn_fee_base(txn.len() as u32);
+
+        res
+    }
+
+    /// Writes the minimum fee into the `Fee` field of the serialized transaction, returns the fee
+    ///
+    /// The fee is computed for `txn`, which must be the whole transaction,
+    /// `EmitDetails` included. The `Fee` field must be present, [Error::NotAnAmount]
+    /// if it is not an XRP amount. The length doesn't change, so the fee stays valid.
+    #[inline(always)]
+    pub fn fill_fee(&self, txn: &mut [u8]) -> Result<u64> {
+        let fee = match self.fee(txn) {
+            Ok(fee) => fee,
+            Err(e) => return Err(e),
+        };
+
+        let range = match sto_subfield(txn, FieldId::Fee) {
+            Ok(field) => {
+                let start = field.as_ptr() as usize - txn.as_ptr() as usize;
+                start..start + field.len()
+            }
+            Err(e) => return Err(e),
+        };
+
+        match encode_fee(&mut txn[range], fee) {
+            Ok(_) => Ok(fee),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Emits the transaction, returns its hash
+    ///
+    /// Returns [Error::TooManyEmittedTxn] without calling `emit` if nothing is left.
+    /// Only a successful emission counts off the budget.
+    #[inline(always)]
+    pub fn emit(&mut self, txn: &[u8]) -> Result<Hash> {
+        if self.remaining() == 0 {
+            return Err(Error::TooManyEmittedTxn);
+        }
+
+        let mut hash = [0; HASH_LEN];
+
+        match emit(&mut hash, txn) {
+            Ok(_) => {
+                self.emitted += 1;
+                Ok(hash)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Encode drops into the payload of an XRP amount field
+#[inline(always)]
+fn encode_fee(amount: &mut [u8], drops: u64) -> Result<()> {
+    // positive XRP amount, 62 bits of drops at most
+    if amount.len() != 8 || amount[0] & 0x80 != 0 || drops >> 62 != 0 {
+        return Err(Error::NotAnAmount);
+    }
+
+    amount.clone_from_slice(&(drops | 0x4000_0000_0000_0000).to_be_bytes());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{helpers::prepare_payment_simple, testing};
+
+    fn payment() -> TxnPaymentSimple {
+        testing::host(|host| {
+            host.hook_account = [0xAA; ACC_ID_LEN];
+            host.ledger_seq = 100;
+            // the details fill the buffer only with a callback
+            #[cfg(feature = "api-v2")]
+            {
+                host.has_callback = true;
+            }
+        });
+
+        let mut txn = [0; PREPARE_PAYMENT_SIMPLE_SIZE];
+
+        match prepare_payment_simple(&mut txn, 1_000, 0, &[0xBB; ACC_ID_LEN], 0, 0) {
+            Ok(len) if len == txn.len() => txn,
+            _ => panic!("payment preparation failed"),
+        }
+    }
+
+    #[test]
+    fn budget() {
+        let txn = payment();
+        let mut plan = match EmissionPlan::reserve(2) {
+            Ok(plan) => plan,
+            Err(_) => panic!("reservation failed"),
+        };
+
+        // a failed emission keeps the budget
+        testing::host(|host| host.emit_error = Some(Error::EmissionFailure));
+        assert!(matches!(plan.emit(&txn), Err(Error::EmissionFailure)));
+        assert_eq!(plan.emitted(), 0);
+        assert_eq!(plan.remaining(), 2);
+
+        testing::host(|host| host.emit_error = None);
+        assert!(plan.emit(&txn).is_ok());
+        assert_eq!(plan.remaining(), 1);
+        assert!(matches!(plan.emit(&txn), Ok(hash) if hash != [0; HASH_LEN]));
+        assert!(matches!(plan.emit(&txn), Err(Error::TooManyEmittedTxn)));
+        assert_eq!(plan.emitted(), 2);
+        assert_eq!(plan.remaining(), 0);
+
+        // the host was never asked for more than the reservation
+        assert_eq!(testing::host(|host| host.emitted.len()), 2);
+    }
+
+    #[test]
+    fn payment_fee() {
+        let mut txn = payment();
+        let plan = EmissionPlan::from_reserved(1);
+
+        // the stub charges 10 drops plus a drop per byte
+        let fee = 10 + PREPARE_PAYMENT_SIMPLE_SIZE as u64;
+        assert!(matches!(plan.fill_fee(&mut txn), Ok(f) if f == fee));
+
+        let expected = (fee | 0x4000_0000_0000_0000).to_be_bytes();
+        assert!(matches!(sto_subfield(&txn, FieldId::Fee), Ok(field) if field == expected));
+        // `Fee` follows the 9 bytes `Amount` field, its header is 0x68
+        assert_eq!(txn[44], 0x68);
+        assert_eq!(txn[45..53], expected);
+
+        #[cfg(feature = "api-v2")]
+        assert_eq!(
+            testing::host(|host| host.fee_txns.clone()),
+            [payment().to_vec()]
+        );
+    }
+
+    #[test]
+    fn fee_encoding() {
+        let mut amount = [0x40, 0, 0, 0, 0, 0, 0, 0];
+
+        assert!(encode_fee(&mut amount, 12).is_ok());
+        assert_eq!(amount, [0x40, 0, 0, 0, 0, 0, 0, 12]);
+
+        let mut iou = [0xD4, 0, 0, 0, 0, 0, 0, 0];
+        assert!(encode_fee(&mut iou, 12).is_err());
+        assert!(encode_fee(&mut amount[..7], 12).is_err());
+    }
+}
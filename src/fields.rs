@@ -0,0 +1,156 @@
+use crate::amount::TypedAmount;
+use crate::api::*;
+use crate::codec::{u16_from_buf, u32_from_buf, u64_from_buf};
+use crate::uninit_buf;
+
+/// Where typed fields are read from: a serialized object or the originating transaction
+pub(crate) trait FieldSource {
+    /// The field, `Ok(None)` if it's missing
+    ///
+    /// `buf` takes the field when the source has to copy it, it's big enough for the fields read.
+    fn field<'a>(&'a self, field_id: FieldId, buf: &'a mut [u8]) -> Result<Option<&'a [u8]>>;
+}
+
+impl FieldSource for [u8] {
+    #[inline(always)]
+    fn field<'a>(&'a self, field_id: FieldId, _: &'a mut [u8]) -> Result<Option<&'a [u8]>> {
+        field(self, field_id)
+    }
+}
+
+/// Field of a serialized object, `Ok(None)` if it's missing
+#[inline(always)]
+pub(crate) fn field(sto: &[u8], field_id: FieldId) -> Result<Option<&[u8]>> {
+    match sto_subfield(sto, field_id) {
+        Ok(field) => Ok(Some(field)),
+        Err(Error::DoesntExist) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Field of exactly `N` bytes
+#[inline(always)]
+pub(crate) fn fixed_field<const N: usize>(
+    source: &(impl FieldSource + ?Sized),
+    field_id: FieldId,
+) -> Result<Option<[u8; N]>> {
+    let mut buf: [u8; N] = uninit_buf!();
+
+    match source.field(field_id, &mut buf) {
+        Ok(Some(field)) if field.len() == N => {
+            let mut out: [u8; N] = uninit_buf!();
+            out.copy_from_slice(field);
+            Ok(Some(out))
+        }
+        Ok(Some(_)) => Err(Error::InvalidField),
+        Ok(None) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[inline(always)]
+pub(crate) fn u16_field(
+    source: &(impl FieldSource + ?Sized),
+    field_id: FieldId,
+) -> Result<Option<u16>> {
+    match fixed_field::<2>(source, field_id) {
+        Ok(buf) => Ok(buf.map(|buf| u16_from_buf(&buf))),
+        Err(e) => Err(e),
+    }
+}
+
+#[inline(always)]
+pub(crate) fn u32_field(
+    source: &(impl FieldSource + ?Sized),
+    field_id: FieldId,
+) -> Result<Option<u32>> {
+    match fixed_field::<4>(source, field_id) {
+        Ok(buf) => Ok(buf.map(|buf| u32_from_buf(&buf))),
+        Err(e) => Err(e),
+    }
+}
+
+#[inline(always)]
+pub(crate) fn u64_field(
+    source: &(impl FieldSource + ?Sized),
+    field_id: FieldId,
+) -> Result<Option<u64>> {
+    match fixed_field::<8>(source, field_id) {
+        Ok(buf) => Ok(buf.map(|buf| u64_from_buf(&buf))),
+        Err(e) => Err(e),
+    }
+}
+
+#[inline(always)]
+pub(crate) fn hash_field(
+    source: &(impl FieldSource + ?Sized),
+    field_id: FieldId,
+) -> Result<Option<Hash>> {
+    fixed_field::<HASH_LEN>(source, field_id)
+}
+
+#[inline(always)]
+pub(crate) fn account_field(
+    source: &(impl FieldSource + ?Sized),
+    field_id: FieldId,
+) -> Result<Option<AccountId>> {
+    fixed_field::<ACC_ID_LEN>(source, field_id)
+}
+
+#[inline(always)]
+pub(crate) fn amount_field(
+    source: &(impl FieldSource + ?Sized),
+    field_id: FieldId,
+) -> Result<Option<TypedAmount>> {
+    let mut buf: Amount = uninit_buf!();
+
+    match source.field(field_id, &mut buf) {
+        Ok(Some(field)) => match TypedAmount::from_sto(field) {
+            Ok(amount) => Ok(Some(amount)),
+            Err(e) => Err(e),
+        },
+        Ok(None) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// XRP amount field in drops
+#[inline(always)]
+pub(crate) fn drops_field(
+    source: &(impl FieldSource + ?Sized),
+    field_id: FieldId,
+) -> Result<Option<u64>> {
+    match amount_field(source, field_id) {
+        Ok(Some(TypedAmount::Xrp(drops))) => Ok(Some(drops)),
+        Ok(Some(TypedAmount::Iou { .. })) => Err(Error::NotAnAmount),
+        Ok(None) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Required issued currency amount: value, currency and issuer
+#[inline(always)]
+pub(crate) fn iou_field(
+    source: &(impl FieldSource + ?Sized),
+    field_id: FieldId,
+) -> Result<(XFL, CurrencyCode, AccountId)> {
+    match required(amount_field(source, field_id)) {
+        Ok(TypedAmount::Iou {
+            value,
+            currency,
+            issuer,
+        }) => Ok((value, currency, issuer)),
+        Ok(TypedAmount::Xrp(_)) => Err(Error::NotIouAmount),
+        Err(e) => Err(e),
+    }
+}
+
+/// Missing field as [Error::DoesntExist]
+#[inline(always)]
+pub(crate) fn required<T>(field: Result<Option<T>>) -> Result<T> {
+    match field {
+        Ok(Some(value)) => Ok(value),
+        Ok(None) => Err(Error::DoesntExist),
+        Err(e) => Err(e),
+    }
+}
@@ -1,6 +1,9 @@
 use core::ops::Range;
 
 use crate::api::*;
+use crate::buffers;
+use crate::codec::BufWriter;
+use crate::memo::{encode_memos, Memo};
 use crate::uninit_buf;
 
 /// Tests two buffers for equality
@@ -33,21 +36,14 @@ pub fn is_buffer_equal<const GUARD_ID: u32>(buf_1: &[u8], buf_2: &[u8]) -> bool
 
 /// Zeroize a buffer
 ///
+/// See [buffers::zeroize].
+///
 /// Pay attention to the GUARD_ID parameter.
 /// This should be unique on every call, through the entire hook code.
 /// Otherwise you will encounter guard violation during the execution of your hook.
 #[inline(always)]
 pub fn buffer_zeroize<const GUARD_ID: u32>(buf: &mut [u8]) {
-    let buf_len = buf.len();
-    // guarded loop
-    let mut i = 0;
-    while {
-        _g(GUARD_ID, buf_len as u32 + 1);
-        i < buf_len
-    } {
-        buf[0] = 0;
-        i += 1;
-    }
+    buffers::zeroize::<GUARD_ID>(buf)
 }
 
 /// Checks whether the transaction is outgoing
@@ -110,6 +106,9 @@ pub const fn amount_to_drops(amount_buf: &Amount) -> Result<u64> {
 }
 
 /// Prepares payment for emitting
+///
+/// Returns the length of the transaction written to `buf_out`,
+/// which is shorter than the buffer when the host leaves out the `EmitCallback`.
 #[inline(always)]
 pub fn prepare_payment_simple(
     buf_out: &mut TxnPaymentSimple,
@@ -118,30 +117,109 @@ pub fn prepare_payment_simple(
     to_address: &AccountId,
     dest_tag: u32,
     src_tag: u32,
-) -> Result<()> {
-    const TT_RANGE: Range<usize> = Range { start: 0, end: 3 };
-    const FLAGS_RANGE: Range<usize> = Range { start: 3, end: 8 };
-    const TAG_SRC_RANGE: Range<usize> = Range { start: 8, end: 13 };
-    const SEQUENCE_RANGE: Range<usize> = Range { start: 13, end: 18 };
-    const TAG_DST_RANGE: Range<usize> = Range { start: 18, end: 23 };
-    const FLS_RANGE: Range<usize> = Range { start: 23, end: 29 };
-    const LLS_RANGE: Range<usize> = Range { start: 29, end: 35 };
-    const DROPS_RANGE: Range<usize> = Range { start: 35, end: 44 };
-    const DROPS_FEE_RANGE: Range<usize> = Range { start: 44, end: 53 };
-    const SIGNING_PUBKEY_RANGE: Range<usize> = Range { start: 53, end: 88 };
-    const ACCOUNT_SRC_RANGE: Range<usize> = Range {
-        start: 88,
-        end: 110,
-    };
-    const ACCOUNT_DST_RANGE: Range<usize> = Range {
-        start: 110,
-        end: 132,
+) -> Result<usize> {
+    encode_payment_simple(
+        &mut buf_out[..],
+        drops_amount,
+        drops_fee,
+        to_address,
+        dest_tag,
+        src_tag,
+    )
+}
+
+/// Prepares payment with memos for emitting
+///
+/// The same payment as [prepare_payment_simple] followed by the `Memos` array,
+/// see [encode_memos]. The fee is computed for the resulting length with [etxn_fee_base].
+///
+/// Returns the length of the transaction written to `buf_out`.
+///
+/// Pay attention to the GUARD_ID parameter.
+/// This should be unique on every call, through the entire hook code.
+/// Otherwise you will encounter guard violation during the execution of your hook.
+/// `GUARD_ID` and `GUARD_ID + 1` are used, see [encode_memos].
+#[inline(always)]
+pub fn prepare_payment_simple_with_memos<const GUARD_ID: u32>(
+    buf_out: &mut [u8],
+    drops_amount: u64,
+    to_address: &AccountId,
+    dest_tag: u32,
+    src_tag: u32,
+    memos: &[Memo],
+) -> Result<usize> {
+    if buf_out.len() < PREPARE_PAYMENT_SIMPLE_SIZE {
+        return Err(Error::TooSmall);
+    }
+
+    // the fee is known only when the length is
+    let payment_len = match encode_payment_simple(
+        &mut buf_out[..PREPARE_PAYMENT_SIMPLE_SIZE],
+        drops_amount,
+        0,
+        to_address,
+        dest_tag,
+        src_tag,
+    ) {
+        Err(e) => return Err(e),
+        Ok(len) => len,
     };
-    const ETXN_DETAILS_RANGE: Range<usize> = Range {
-        start: 132,
-        end: 237,
+
+    let mut writer = BufWriter::new(&mut buf_out[payment_len..]);
+    match encode_memos::<GUARD_ID>(&mut writer, memos) {
+        Err(e) => return Err(e),
+        Ok(_) => {}
+    }
+
+    let len = payment_len + writer.position();
+
+    #[cfg(feature = "api-v2")]
+    let res = etxn_fee_base(&buf_out[..len]);
+    #[cfg(not(feature = "api-v2"))]
+    let res = etxn_fee_base(len as u32);
+
+    let drops_fee = match res {
+        Err(e) => return Err(e),
+        Ok(fee) => fee,
     };
 
+    encode_drops_fee(&mut buf_out[DROPS_FEE_RANGE], drops_fee);
+
+    Ok(len)
+}
+
+const TT_RANGE: Range<usize> = Range { start: 0, end: 3 };
+const FLAGS_RANGE: Range<usize> = Range { start: 3, end: 8 };
+const TAG_SRC_RANGE: Range<usize> = Range { start: 8, end: 13 };
+const SEQUENCE_RANGE: Range<usize> = Range { start: 13, end: 18 };
+const TAG_DST_RANGE: Range<usize> = Range { start: 18, end: 23 };
+const FLS_RANGE: Range<usize> = Range { start: 23, end: 29 };
+const LLS_RANGE: Range<usize> = Range { start: 29, end: 35 };
+const DROPS_RANGE: Range<usize> = Range { start: 35, end: 44 };
+const DROPS_FEE_RANGE: Range<usize> = Range { start: 44, end: 53 };
+const SIGNING_PUBKEY_RANGE: Range<usize> = Range { start: 53, end: 88 };
+const ACCOUNT_SRC_RANGE: Range<usize> = Range {
+    start: 88,
+    end: 110,
+};
+const ACCOUNT_DST_RANGE: Range<usize> = Range {
+    start: 110,
+    end: 132,
+};
+const ETXN_DETAILS_RANGE: Range<usize> = Range {
+    start: 132,
+    end: PREPARE_PAYMENT_SIMPLE_SIZE,
+};
+
+#[inline(always)]
+fn encode_payment_simple(
+    buf_out: &mut [u8],
+    drops_amount: u64,
+    drops_fee: u64,
+    to_address: &AccountId,
+    dest_tag: u32,
+    src_tag: u32,
+) -> Result<usize> {
     let mut acc: AccountId = uninit_buf!();
     match hook_account(&mut acc) {
         Err(e) => return Err(e),
@@ -163,18 +241,16 @@ pub fn prepare_payment_simple(
     encode_account_src(&mut buf_out[ACCOUNT_SRC_RANGE], &acc);
     encode_account_dst(&mut buf_out[ACCOUNT_DST_RANGE], to_address);
     match etxn_details(&mut buf_out[ETXN_DETAILS_RANGE]) {
-        Err(e) => return Err(e),
-        Ok(_) => {}
+        Err(e) => Err(e),
+        Ok(len) => Ok(ETXN_DETAILS_RANGE.start + len as usize),
     }
-
-    Ok(())
 }
 
 #[inline(always)]
 fn encode_tt(buf_out: &mut [u8], tt: TxnType) {
     buf_out[0] = 0x12;
-    buf_out[1] = ((tt as u16 >> 8) & 0xFF) as u8;
-    buf_out[2] = ((tt as u16 >> 0) & 0xFF) as u8;
+    buf_out[1] = ((tt.code() >> 8) & 0xFF) as u8;
+    buf_out[2] = ((tt.code() >> 0) & 0xFF) as u8;
 }
 
 #[inline(always)]
@@ -276,12 +352,29 @@ fn encode_account(buf_out: &mut [u8], account_id: &AccountId, account_type: Acco
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::_c;
+    use crate::{_c, testing};
 
     const ACCOUNT_ID: AccountId = [
         1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20,
     ];
 
+    #[test]
+    fn buf_zeroize() {
+        let mut buf = [0xFF; 8];
+
+        buffer_zeroize::<1>(&mut buf);
+
+        assert_eq!(buf, [0; 8]);
+        assert!(!testing::guard_violated());
+    }
+
+    #[test]
+    fn buf_equal() {
+        assert!(is_buffer_equal::<1>(&ACCOUNT_ID, &ACCOUNT_ID));
+        assert!(!is_buffer_equal::<2>(&ACCOUNT_ID, &ACCOUNT_ID[1..]));
+        assert!(!testing::guard_violated());
+    }
+
     #[test]
     fn enc_account() {
         let mut encoded: [u8; _c::ENCODE_ACCOUNT_SIZE as usize] = uninit_buf!();
@@ -308,4 +401,110 @@ mod tests {
             ]
         )
     }
+
+    #[test]
+    fn payment_sizes() {
+        testing::host(|host| host.hook_account = [0xAA; ACC_ID_LEN]);
+
+        // the host wants room for the whole details object
+        let mut details = [0; EMIT_DETAILS_SIZE];
+
+        #[cfg(not(feature = "api-v2"))]
+        {
+            assert!(matches!(
+                etxn_details(&mut details[..EMIT_DETAILS_SIZE - 1]),
+                Err(Error::TooSmall)
+            ));
+            assert!(matches!(etxn_details(&mut details), Ok(105)));
+        }
+
+        #[cfg(feature = "api-v2")]
+        {
+            assert!(matches!(
+                etxn_details(&mut details[..EMIT_DETAILS_NO_CALLBACK_SIZE - 1]),
+                Err(Error::TooSmall)
+            ));
+            assert!(matches!(etxn_details(&mut details), Ok(116)));
+
+            testing::host(|host| host.has_callback = true);
+            assert!(matches!(
+                etxn_details(&mut details[..EMIT_DETAILS_SIZE - 1]),
+                Err(Error::TooSmall)
+            ));
+            assert!(matches!(etxn_details(&mut details), Ok(138)));
+        }
+
+        // with a callback the payment fills the buffer
+        let mut txn = [0; PREPARE_PAYMENT_SIMPLE_SIZE];
+        assert!(matches!(
+            prepare_payment_simple(&mut txn, 1_000, 10, &ACCOUNT_ID, 0, 0),
+            Ok(len) if len == PREPARE_PAYMENT_SIMPLE_SIZE
+        ));
+        #[cfg(not(feature = "api-v2"))]
+        assert_eq!(PREPARE_PAYMENT_SIMPLE_SIZE, 237);
+        #[cfg(feature = "api-v2")]
+        assert_eq!(PREPARE_PAYMENT_SIMPLE_SIZE, 270);
+        assert_eq!(txn[ETXN_DETAILS_RANGE][EMIT_DETAILS_SIZE - 1], 0xE1);
+    }
+
+    #[test]
+    fn payment_with_memos_fee() {
+        testing::host(|host| {
+            host.hook_account = [0xAA; ACC_ID_LEN];
+            host.ledger_seq = 100;
+        });
+
+        let memos = [Memo {
+            ty: b"ref",
+            data: &[1, 2, 3],
+            format: &[],
+        }];
+        let mut txn = [0; 300];
+
+        let len = match prepare_payment_simple_with_memos::<1>(
+            &mut txn,
+            1_000,
+            &ACCOUNT_ID,
+            7,
+            8,
+            &memos,
+        ) {
+            Ok(len) => len,
+            Err(_) => panic!("payment preparation failed"),
+        };
+        // the hook has no callback, the memos follow the shorter details
+        #[cfg(feature = "api-v2")]
+        let payment_len = ETXN_DETAILS_RANGE.start + EMIT_DETAILS_NO_CALLBACK_SIZE;
+        #[cfg(not(feature = "api-v2"))]
+        let payment_len = PREPARE_PAYMENT_SIMPLE_SIZE;
+        assert_eq!(len, payment_len + 2 + memos[0].encoded_size());
+        assert_eq!(txn[payment_len], 0xF9);
+
+        // the stub charges 10 drops plus a drop per byte of the whole transaction
+        let mut fee = [0; 9];
+        encode_drops_fee(&mut fee, 10 + len as u64);
+        assert_eq!(txn[DROPS_FEE_RANGE], fee);
+        assert!(matches!(sto_subfield(&txn[..len], FieldId::Fee), Ok(field) if field == &fee[1..]));
+
+        let seen = testing::host(|host| host.fee_txns.clone());
+        assert_eq!(seen.len(), 1);
+
+        // the transaction is passed as written, before the fee
+        #[cfg(feature = "api-v2")]
+        {
+            assert_eq!(seen[0].len(), len);
+            assert_eq!(
+                seen[0][..DROPS_FEE_RANGE.start],
+                txn[..DROPS_FEE_RANGE.start]
+            );
+            assert_eq!(
+                seen[0][DROPS_FEE_RANGE.end..],
+                txn[DROPS_FEE_RANGE.end..len]
+            );
+        }
+        #[cfg(not(feature = "api-v2"))]
+        assert_eq!(seen[0], (len as u32).to_be_bytes());
+
+        assert!(!testing::guard_violated());
+    }
 }
@@ -0,0 +1,134 @@
+use crate::api::*;
+
+/// Iterator adapter calling the guard function on every `next()`
+///
+/// Created by [GuardedIterator::guarded_iter].
+/// As `next()` is the first call in the body of a `for` loop
+/// (and of `zip`, `enumerate`, `position` and friends),
+/// such loops are accepted by the guard checker.
+///
+/// Pay attention to the GUARD_ID parameter.
+/// This should be unique on every call, through the entire hook code.
+/// Otherwise you will encounter guard violation during the execution of your hook.
+/// Use the [guard_id!](crate::guard_id) macro to get one.
+#[derive(Clone)]
+pub struct GuardedIter<I, const GUARD_ID: u32> {
+    inner: I,
+    maxiter: u32,
+}
+
+impl<I: Iterator, const GUARD_ID: u32> Iterator for GuardedIter<I, GUARD_ID> {
+    type Item = I::Item;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        _g(GUARD_ID, self.maxiter + 1);
+        self.inner.next()
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<I: DoubleEndedIterator, const GUARD_ID: u32> DoubleEndedIterator for GuardedIter<I, GUARD_ID> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        _g(GUARD_ID, self.maxiter + 1);
+        self.inner.next_back()
+    }
+}
+
+impl<I: ExactSizeIterator, const GUARD_ID: u32> ExactSizeIterator for GuardedIter<I, GUARD_ID> {}
+
+/// Guarded iteration over slices, ranges and everything else that is [IntoIterator]
+///
+/// # Example
+///
+/// ``` txt
+/// for (i, byte) in buf.guarded_iter::<{ guard_id!() }>(32).enumerate() {
+///     // your code
+/// }
+/// ```
+pub trait GuardedIterator: IntoIterator + Sized {
+    /// Wraps the iterator into [GuardedIter]
+    ///
+    /// `maxiter` is the maximum number of items the loop will take,
+    /// through the entire hook execution.
+    #[inline(always)]
+    fn guarded_iter<const GUARD_ID: u32>(
+        self,
+        maxiter: u32,
+    ) -> GuardedIter<Self::IntoIter, GUARD_ID> {
+        GuardedIter {
+            inner: self.into_iter(),
+            maxiter,
+        }
+    }
+}
+
+impl<T: IntoIterator> GuardedIterator for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{guard_calls, guard_violated};
+
+    const BUF: [u8; 5] = [1, 2, 3, 4, 5];
+
+    #[test]
+    fn for_loop() {
+        let mut sum = 0;
+
+        for byte in BUF.guarded_iter::<1>(5) {
+            sum += byte;
+        }
+
+        assert_eq!(sum, 15);
+        assert_eq!(guard_calls(), 6);
+        assert!(!guard_violated());
+    }
+
+    #[test]
+    fn range() {
+        let mut count = 0;
+
+        for _ in (0..10).guarded_iter::<1>(10).rev() {
+            count += 1;
+        }
+
+        assert_eq!(count, 10);
+        assert!(!guard_violated());
+    }
+
+    #[test]
+    fn zip_and_enumerate() {
+        let other = [1, 2, 0, 4, 5];
+        let mut diff = None;
+
+        for (i, (a, b)) in BUF.iter().guarded_iter::<1>(5).zip(&other).enumerate() {
+            if a != b {
+                diff = Some(i);
+            }
+        }
+
+        assert_eq!(diff, Some(2));
+        assert!(!guard_violated());
+    }
+
+    #[test]
+    fn position() {
+        let pos = BUF.guarded_iter::<1>(5).position(|b| b == 3);
+
+        assert_eq!(pos, Some(2));
+        assert_eq!(guard_calls(), 3);
+    }
+
+    #[test]
+    fn over_budget() {
+        for _ in BUF.guarded_iter::<1>(4) {}
+
+        assert!(guard_violated());
+    }
+}
@@ -9,6 +9,17 @@
 //!
 //! For a quick start and to view examples,
 //! use the [hook template](https://github.com/otov4its/xrpl-hook-template/)
+//!
+//! # Host API profiles
+//!
+//! The host functions, [Error](api::Error) codes, [KeyletType](api::KeyletType) members
+//! and [TxnType](api::TxnType) values follow the network picked with a cargo feature:
+//! `api-v1` for the 2021 hooks testnet, `api-v2` for the Hooks amendment
+//! and `xahau`, the default. Using a function the network doesn't have fails at compile time.
+//!
+//! ``` txt
+//! xrpl-hooks = { version = "0.3", default-features = false, features = ["api-v2"] }
+//! ```
 
 #![no_std]
 #![deny(
@@ -24,6 +35,9 @@
 #![doc(test(attr(deny(warnings))))]
 #![doc(html_root_url = "https://docs.rs/xrpl-hooks/0.3.1")]
 
+// lets the derive macros refer to `::xrpl_hooks` inside this crate too
+extern crate self as xrpl_hooks;
+
 mod macros;
 
 /// # Low-level unsafe C bindings
@@ -37,13 +51,85 @@ pub mod api;
 /// A few utilities
 pub mod helpers;
 
+/// Guarded iterators
+pub mod iter;
+
+/// Guarded buffer operations
+pub mod buffers;
+
+/// Big-endian integer codecs
+pub mod codec;
+
+/// Typed hook state
+pub mod state;
+
+/// Transaction memos
+pub mod memo;
+
+/// Typed amounts
+pub mod amount;
+
+/// Typed originating transaction
+pub mod txn;
+
+/// Typed hook and transaction parameters
+#[cfg(feature = "api-v2")]
+pub mod param;
+
+/// Callbacks of emitted transactions
+pub mod callback;
+
+/// Emission budget
+pub mod emission;
+
+// Typed fields shared by the transaction and ledger object views
+mod fields;
+
+/// Ledger objects decoded from slots
+pub mod objects;
+
+/// Trust lines seen from one of their accounts
+pub mod trustline;
+
+/// Account reserve and spendable balance
+pub mod reserve;
+
+/// Owner and offer directories
+pub mod directory;
+
+/// Order books and their quality directories
+pub mod book;
+
+/// Classic addresses
+///
+/// Encoding and decoding loop unguarded, they are meant for the host side,
+/// deployment tooling for example, not for hooks.
+pub mod address;
+
+/// Hook installation: `HookOn` values and SetHook transactions
+pub mod sethook;
+
+#[cfg(test)]
+mod testing;
+
 // Prelude
-pub use {api::*, helpers::*};
+pub use {
+    address::*, amount::*, api::*, book::*, callback::*, codec::*, directory::*, emission::*,
+    helpers::*, iter::*, memo::*, objects::*, reserve::*, sethook::*, state::*, trustline::*,
+    txn::*,
+};
+
+#[cfg(feature = "api-v2")]
+pub use param::*;
+
+/// Derive macro for [StateCodec], requires the `derive` feature
+#[cfg(feature = "derive")]
+pub use xrpl_hooks_derive::HookState;
 
-#[cfg(not(test))]
+#[cfg(all(not(test), target_arch = "wasm32"))]
 use core::panic::PanicInfo;
 /// You should use rollback() instead of native panic!() macro
-#[cfg(not(test))]
+#[cfg(all(not(test), target_arch = "wasm32"))]
 #[inline(always)]
 #[panic_handler]
 fn panic(_: &PanicInfo<'_>) -> ! {
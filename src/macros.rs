@@ -15,3 +15,84 @@ macro_rules! uninit_buf {
         unsafe { ::core::mem::MaybeUninit::uninit().assume_init() }
     };
 }
+
+/// Unique guard id of the call site
+///
+/// Computed at compile time from `file!()`, `line!()` and `column!()`.
+/// Handy for helpers taking a `GUARD_ID` const parameter.
+///
+/// The id is a 32-bit hash of the location, nothing checks that two call sites
+/// don't hash to the same id. A collision shows up as a guard violation on the host,
+/// pass explicit ids there instead: a literal `GUARD_ID` or [guard!](crate::guard)`(id, maxiter)`.
+///
+/// # Example
+///
+/// ``` txt
+/// let equal = is_buffer_equal::<{ guard_id!() }>(&buf_1, &buf_2);
+/// ```
+#[macro_export]
+macro_rules! guard_id {
+    () => {{
+        const GUARD_ID: u32 = $crate::location_guard_id(file!(), line!(), column!());
+        GUARD_ID
+    }};
+}
+
+/// Guard macro, an analogue of the `GUARD(maxiter)` C macro
+///
+/// Calls [_g](crate::_g) with the [guard_id!](crate::guard_id) of the call site
+/// and `maxiter + 1` as the iteration limit, `guard!(id, maxiter)` takes an explicit id.
+/// Must be the first call in the loop condition block.
+///
+/// Keep in mind that a loop inside an `#[inline(always)]` function
+/// shares its guard id between all the places the function is inlined to.
+///
+/// # Example
+///
+/// ``` txt
+/// let mut i = 0;
+/// while {
+///     guard!(MAXITER);
+///     i < MAXITER
+/// } {
+///     // your code
+///     i += 1;
+/// }
+/// ```
+#[macro_export]
+macro_rules! guard {
+    ($maxiter:expr) => {
+        $crate::_g($crate::guard_id!(), ($maxiter) as u32 + 1)
+    };
+    ($id:expr, $maxiter:expr) => {
+        $crate::_g($id, ($maxiter) as u32 + 1)
+    };
+}
+
+/// Guarded `for` loop over a range
+///
+/// Expands to a `while` loop with a [guard!](crate::guard) call in its condition.
+/// `continue` and `break` work as expected.
+///
+/// # Example
+///
+/// ``` txt
+/// guarded_for!(i in 0..buf.len(), max = 32, {
+///     buf[i] = 0;
+/// });
+/// ```
+#[macro_export]
+macro_rules! guarded_for {
+    ($i:ident in $range:expr, max = $max:expr, $body:block) => {{
+        let range: ::core::ops::Range<_> = $range;
+        let mut next = range.start;
+        while {
+            $crate::guard!($max);
+            next < range.end
+        } {
+            let $i = next;
+            next += 1;
+            $body
+        }
+    }};
+}
@@ -0,0 +1,660 @@
+use crate::api::*;
+use crate::codec::BufWriter;
+use crate::iter::{GuardedIter, GuardedIterator};
+
+/// A memo of a transaction
+///
+/// Borrows the fields from a serialized `Memos` array,
+/// absent fields are empty slices.
+#[derive(Clone, Copy)]
+pub struct Memo<'a> {
+    /// `MemoType` field
+    pub ty: &'a [u8],
+    /// `MemoData` field
+    pub data: &'a [u8],
+    /// `MemoFormat` field
+    pub format: &'a [u8],
+}
+
+impl<'a> Memo<'a> {
+    /// Parse an element of a serialized `Memos` array
+    #[inline(always)]
+    pub fn from_sto(element: &'a [u8]) -> Result<Memo<'a>> {
+        // array elements are wrapped into a `Memo` object
+        let memo = match sto_subfield(element, FieldId::Memo) {
+            Ok(memo) => memo,
+            Err(e) => return Err(e),
+        };
+
+        let ty = match memo_subfield(memo, FieldId::MemoType) {
+            Ok(ty) => ty,
+            Err(e) => return Err(e),
+        };
+
+        let data = match memo_subfield(memo, FieldId::MemoData) {
+            Ok(data) => data,
+            Err(e) => return Err(e),
+        };
+
+        let format = match memo_subfield(memo, FieldId::MemoFormat) {
+            Ok(format) => format,
+            Err(e) => return Err(e),
+        };
+
+        Ok(Memo { ty, data, format })
+    }
+
+    /// Size of the memo serialized into a `Memos` array, empty fields are omitted
+    #[inline(always)]
+    pub const fn encoded_size(&self) -> usize {
+        2 + vl_field_size(self.ty.len())
+            + vl_field_size(self.data.len())
+            + vl_field_size(self.format.len())
+    }
+
+    /// `MemoData` decoded from hex into `out`, returns the decoded length
+    ///
+    /// See [hex_decode].
+    ///
+    /// Pay attention to the GUARD_ID parameter.
+    /// This should be unique on every call, through the entire hook code.
+    /// Otherwise you will encounter guard violation during the execution of your hook.
+    #[inline(always)]
+    pub fn data_hex<const GUARD_ID: u32>(&self, out: &mut [u8]) -> Result<usize> {
+        hex_decode::<GUARD_ID>(out, self.data)
+    }
+
+    /// `MemoData` as UTF-8 text
+    ///
+    /// See [utf8_str].
+    ///
+    /// Pay attention to the GUARD_ID parameter.
+    /// This should be unique on every call, through the entire hook code.
+    /// Otherwise you will encounter guard violation during the execution of your hook.
+    #[inline(always)]
+    pub fn data_utf8<const GUARD_ID: u32>(&self) -> Result<&'a str> {
+        utf8_str::<GUARD_ID>(self.data)
+    }
+}
+
+#[inline(always)]
+fn memo_subfield(memo: &[u8], field_id: FieldId) -> Result<&[u8]> {
+    match sto_subfield(memo, field_id) {
+        Ok(field) => Ok(field),
+        Err(Error::DoesntExist) => Ok(&[]),
+        Err(e) => Err(e),
+    }
+}
+
+/// A serialized `Memos` array
+#[derive(Clone, Copy)]
+pub struct Memos<'a> {
+    sto: &'a [u8],
+}
+
+impl<'a> Memos<'a> {
+    /// Wraps a serialized `Memos` array
+    #[inline(always)]
+    pub const fn from_sto(sto: &'a [u8]) -> Self {
+        Memos { sto }
+    }
+
+    /// Guarded iterator over the memos
+    ///
+    /// `maxiter` is the maximum number of memos the loop will take,
+    /// through the entire hook execution.
+    ///
+    /// Pay attention to the GUARD_ID parameter.
+    /// This should be unique on every call, through the entire hook code.
+    /// Otherwise you will encounter guard violation during the execution of your hook.
+    #[inline(always)]
+    pub fn iter<const GUARD_ID: u32>(&self, maxiter: u32) -> GuardedIter<MemoIter<'a>, GUARD_ID> {
+        MemoIter {
+            sto: self.sto,
+            index: 0,
+            done: self.sto.is_empty(),
+        }
+        .guarded_iter::<GUARD_ID>(maxiter)
+    }
+
+    /// Find the first memo with the `MemoType`
+    ///
+    /// Looks through `maxiter` memos at most.
+    ///
+    /// Pay attention to the GUARD_ID parameter.
+    /// This should be unique on every call, through the entire hook code.
+    /// Otherwise you will encounter guard violation during the execution of your hook.
+    /// `GUARD_ID` guards the memo loop and `GUARD_ID + 1` the comparison of the types
+    /// (like `GUARDM` in C), keep both unique.
+    #[inline(always)]
+    pub fn find<const GUARD_ID: u32>(&self, ty: &[u8], maxiter: u32) -> Result<Option<Memo<'a>>> {
+        let ty_len = ty.len();
+        let cmp_guard_id = GUARD_ID.wrapping_add(1);
+        let cmp_maxiter = maxiter * ty_len as u32;
+
+        for memo in self.iter::<GUARD_ID>(maxiter) {
+            let memo = match memo {
+                Ok(memo) => memo,
+                Err(e) => return Err(e),
+            };
+
+            if memo.ty.len() != ty_len {
+                continue;
+            }
+
+            // guarded loop
+            let mut i = 0;
+            while {
+                _g(cmp_guard_id, cmp_maxiter + 1);
+                i < ty_len && memo.ty[i] == ty[i]
+            } {
+                i += 1;
+            }
+
+            if i == ty_len {
+                return Ok(Some(memo));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Iterator over the memos of a [Memos] array
+///
+/// Obtained guarded from [Memos::iter].
+pub struct MemoIter<'a> {
+    sto: &'a [u8],
+    index: u32,
+    done: bool,
+}
+
+impl<'a> Iterator for MemoIter<'a> {
+    type Item = Result<Memo<'a>>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let element = match sto_subarray(self.sto, self.index) {
+            Ok(element) => element,
+            Err(Error::DoesntExist) => {
+                self.done = true;
+                return None;
+            }
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e));
+            }
+        };
+
+        self.index += 1;
+
+        let memo = Memo::from_sto(element);
+        self.done = memo.is_err();
+
+        Some(memo)
+    }
+}
+
+/// Memos of the originating transaction
+///
+/// Writes the serialized `Memos` field to `buf`,
+/// a transaction without memos has an empty [Memos].
+#[inline(always)]
+pub fn otxn_memos(buf: &mut [u8]) -> Result<Memos<'_>> {
+    match otxn_field(buf, FieldId::Memos) {
+        Ok(len) => Ok(Memos::from_sto(&buf[..len as usize])),
+        Err(Error::DoesntExist) => Ok(Memos::from_sto(&[])),
+        Err(e) => Err(e),
+    }
+}
+
+/// Largest `MemoType`, `MemoData` or `MemoFormat` length [encode_memos] supports
+pub const MEMO_FIELD_MAX_LEN: usize = 12480;
+
+/// Serialize memos as a `Memos` array, nothing is written for no memos
+///
+/// Empty fields are omitted. Returns [Error::TooBig] for a field longer
+/// than [MEMO_FIELD_MAX_LEN] and [Error::TooSmall] if the writer runs out of space.
+///
+/// Pay attention to the GUARD_ID parameter.
+/// This should be unique on every call, through the entire hook code.
+/// Otherwise you will encounter guard violation during the execution of your hook.
+/// `GUARD_ID` guards the memo loop and `GUARD_ID + 1` the copying of the bytes
+/// (like `GUARDM` in C), keep both unique.
+#[inline(always)]
+pub fn encode_memos<const GUARD_ID: u32>(writer: &mut BufWriter, memos: &[Memo]) -> Result<()> {
+    let count = memos.len();
+
+    if count == 0 {
+        return Ok(());
+    }
+
+    // every field takes at least two bytes of header besides the copied ones,
+    // so the remaining space bounds the byte loop iterations with the exit checks
+    let copy_guard_id = GUARD_ID.wrapping_add(1);
+    let copy_maxiter = writer.remaining() as u32;
+
+    match writer.write_u8(0xF9) {
+        Err(e) => return Err(e),
+        Ok(_) => {}
+    }
+
+    // guarded loop
+    let mut i = 0;
+    while {
+        _g(GUARD_ID, count as u32 + 1);
+        i < count
+    } {
+        let memo = &memos[i];
+
+        match writer.write_u8(0xEA) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+
+        match encode_vl_field(writer, 0x7C, memo.ty, copy_guard_id, copy_maxiter) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+
+        match encode_vl_field(writer, 0x7D, memo.data, copy_guard_id, copy_maxiter) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+
+        match encode_vl_field(writer, 0x7E, memo.format, copy_guard_id, copy_maxiter) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+
+        match writer.write_u8(0xE1) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+
+        i += 1;
+    }
+
+    writer.write_u8(0xF1)
+}
+
+#[inline(always)]
+const fn vl_field_size(len: usize) -> usize {
+    match len {
+        0 => 0,
+        1..=192 => 2 + len,
+        _ => 3 + len,
+    }
+}
+
+#[inline(always)]
+fn encode_vl_field(
+    writer: &mut BufWriter,
+    header: u8,
+    bytes: &[u8],
+    guard_id: u32,
+    maxiter: u32,
+) -> Result<()> {
+    let len = bytes.len();
+
+    if len == 0 {
+        return Ok(());
+    }
+
+    if len > MEMO_FIELD_MAX_LEN {
+        return Err(Error::TooBig);
+    }
+
+    if writer.remaining() < vl_field_size(len) {
+        return Err(Error::TooSmall);
+    }
+
+    match writer.write_u8(header) {
+        Err(e) => return Err(e),
+        Ok(_) => {}
+    }
+
+    let vl = if len <= 192 {
+        writer.write_u8(len as u8)
+    } else {
+        let len = len - 193;
+        match writer.write_u8(193 + (len >> 8) as u8) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+        writer.write_u8((len & 0xFF) as u8)
+    };
+
+    match vl {
+        Err(e) => return Err(e),
+        Ok(_) => {}
+    }
+
+    // guarded loop
+    let mut i = 0;
+    while {
+        _g(guard_id, maxiter + 1);
+        i < len
+    } {
+        match writer.write_u8(bytes[i]) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+        i += 1;
+    }
+
+    Ok(())
+}
+
+/// Decode hex (upper or lower case) into `out`, returns the decoded length
+///
+/// Returns [Error::InvalidArgument] for an odd length or a non-hex digit
+/// and [Error::TooSmall] if `out` can't hold the result.
+///
+/// Guard budget: `hex.len() / 2` iterations.
+///
+/// Pay attention to the GUARD_ID parameter.
+/// This should be unique on every call, through the entire hook code.
+/// Otherwise you will encounter guard violation during the execution of your hook.
+#[inline(always)]
+pub fn hex_decode<const GUARD_ID: u32>(out: &mut [u8], hex: &[u8]) -> Result<usize> {
+    let out_len = hex.len() / 2;
+
+    if hex.len() & 1 == 1 {
+        return Err(Error::InvalidArgument);
+    }
+
+    if out.len() < out_len {
+        return Err(Error::TooSmall);
+    }
+
+    // guarded loop
+    let mut i = 0;
+    while {
+        _g(GUARD_ID, out_len as u32 + 1);
+        i < out_len
+    } {
+        match (hex_digit(hex[2 * i]), hex_digit(hex[2 * i + 1])) {
+            (Some(high), Some(low)) => out[i] = (high << 4) | low,
+            _ => return Err(Error::InvalidArgument),
+        }
+        i += 1;
+    }
+
+    Ok(out_len)
+}
+
+#[inline(always)]
+const fn hex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Validate UTF-8 and return it as `&str`
+///
+/// A guarded replacement of [core::str::from_utf8], which loops unguarded.
+/// Returns [Error::InvalidArgument] for invalid UTF-8.
+///
+/// Guard budget: `bytes.len()` iterations.
+///
+/// Pay attention to the GUARD_ID parameter.
+/// This should be unique on every call, through the entire hook code.
+/// Otherwise you will encounter guard violation during the execution of your hook.
+#[inline(always)]
+pub fn utf8_str<const GUARD_ID: u32>(bytes: &[u8]) -> Result<&str> {
+    let len = bytes.len();
+    // continuation bytes left and the allowed range of the next one
+    let mut need = 0;
+    let mut low = 0x80;
+    let mut high = 0xBF;
+
+    // guarded loop
+    let mut i = 0;
+    while {
+        _g(GUARD_ID, len as u32 + 1);
+        i < len
+    } {
+        let b = bytes[i];
+
+        if need == 0 {
+            // the ranges of Unicode Table 3-7, well-formed UTF-8 byte sequences
+            match b {
+                0x00..=0x7F => {}
+                0xC2..=0xDF => need = 1,
+                0xE0 => {
+                    need = 2;
+                    low = 0xA0;
+                }
+                0xE1..=0xEC | 0xEE..=0xEF => need = 2,
+                0xED => {
+                    need = 2;
+                    high = 0x9F;
+                }
+                0xF0 => {
+                    need = 3;
+                    low = 0x90;
+                }
+                0xF1..=0xF3 => need = 3,
+                0xF4 => {
+                    need = 3;
+                    high = 0x8F;
+                }
+                _ => return Err(Error::InvalidArgument),
+            }
+        } else {
+            if b < low || b > high {
+                return Err(Error::InvalidArgument);
+            }
+            need -= 1;
+            low = 0x80;
+            high = 0xBF;
+        }
+
+        i += 1;
+    }
+
+    if need != 0 {
+        return Err(Error::InvalidArgument);
+    }
+
+    // SAFETY: validated above
+    Ok(unsafe { core::str::from_utf8_unchecked(bytes) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, guard_violated};
+
+    const MEMOS: [Memo; 3] = [
+        Memo {
+            ty: b"ref",
+            data: &[0xAB, 0xCD],
+            format: &[],
+        },
+        Memo {
+            ty: b"invoice",
+            data: b"0042",
+            format: b"text/plain",
+        },
+        Memo {
+            ty: b"ref",
+            data: &[0xEF],
+            format: &[],
+        },
+    ];
+
+    #[test]
+    fn memos_encoding() {
+        let memos = [
+            Memo {
+                ty: b"ref",
+                data: &[0xAB, 0xCD],
+                format: &[],
+            },
+            Memo {
+                ty: &[],
+                data: &[0xEF; 200],
+                format: b"hex",
+            },
+        ];
+        let mut buf = [0; 256];
+        let mut writer = BufWriter::new(&mut buf);
+
+        assert!(encode_memos::<1>(&mut writer, &memos).is_ok());
+
+        let encoded = writer.written();
+        assert_eq!(
+            encoded.len(),
+            2 + memos[0].encoded_size() + memos[1].encoded_size()
+        );
+        assert_eq!(
+            &encoded[..14],
+            &[0xF9, 0xEA, 0x7C, 3, b'r', b'e', b'f', 0x7D, 2, 0xAB, 0xCD, 0xE1, 0xEA, 0x7D]
+        );
+        // two bytes length: 193 + 200 - 193
+        assert_eq!(&encoded[14..17], &[193, 7, 0xEF]);
+        assert_eq!(
+            &encoded[214..],
+            &[0xEF, 0xEF, 0x7E, 3, b'h', b'e', b'x', 0xE1, 0xF1]
+        );
+        assert!(!guard_violated());
+    }
+
+    #[test]
+    fn memos_too_small() {
+        let memos = [Memo {
+            ty: b"ref",
+            data: &[0; 16],
+            format: &[],
+        }];
+        let mut buf = [0; 16];
+
+        assert!(encode_memos::<1>(&mut BufWriter::new(&mut buf), &memos).is_err());
+        assert!(encode_memos::<3>(&mut BufWriter::new(&mut buf), &[]).is_ok());
+        assert!(!guard_violated());
+    }
+
+    #[test]
+    fn hex() {
+        let mut out = [0; 4];
+
+        assert!(matches!(hex_decode::<1>(&mut out, b"DEADbeef"), Ok(4)));
+        assert_eq!(out, [0xDE, 0xAD, 0xBE, 0xEF]);
+        assert!(hex_decode::<2>(&mut out, b"ABC").is_err());
+        assert!(hex_decode::<3>(&mut out, b"XY").is_err());
+        assert!(hex_decode::<4>(&mut out, b"0011223344").is_err());
+        assert!(!guard_violated());
+    }
+
+    #[test]
+    fn utf8() {
+        assert!(matches!(utf8_str::<1>(b"memo"), Ok("memo")));
+        assert!(matches!(utf8_str::<2>("ключ €𝄞".as_bytes()), Ok("ключ €𝄞")));
+        // overlong, surrogate, truncated and out of range sequences
+        assert!(utf8_str::<3>(&[0xC0, 0x80]).is_err());
+        assert!(utf8_str::<4>(&[0xED, 0xA0, 0x80]).is_err());
+        assert!(utf8_str::<5>(&[0xE2, 0x82]).is_err());
+        assert!(utf8_str::<6>(&[0xF4, 0x90, 0x80, 0x80]).is_err());
+        assert!(!guard_violated());
+    }
+
+    #[test]
+    fn memos_parsing() {
+        let mut buf = [0; 128];
+        let mut writer = BufWriter::new(&mut buf);
+        assert!(encode_memos::<1>(&mut writer, &MEMOS).is_ok());
+        let memos = Memos::from_sto(writer.written());
+
+        let mut count = 0;
+        for (memo, expected) in memos.iter::<3>(4).zip(MEMOS.iter()) {
+            let memo = match memo {
+                Ok(memo) => memo,
+                Err(_) => panic!("memo parsing failed"),
+            };
+
+            assert_eq!(memo.ty, expected.ty);
+            assert_eq!(memo.data, expected.data);
+            assert_eq!(memo.format, expected.format);
+            count += 1;
+        }
+        assert_eq!(count, 3);
+
+        let invoice = match memos.find::<4>(b"invoice", 4) {
+            Ok(Some(memo)) => memo,
+            _ => panic!("memo not found"),
+        };
+        assert!(matches!(invoice.data_utf8::<6>(), Ok("0042")));
+
+        // the first of the memos with the type
+        assert!(matches!(memos.find::<7>(b"ref", 4), Ok(Some(memo)) if memo.data == [0xAB, 0xCD]));
+        assert!(matches!(memos.find::<9>(b"rex", 4), Ok(None)));
+        assert!(!guard_violated());
+    }
+
+    #[test]
+    fn memo_element() {
+        let element = [0xEA, 0x7C, 1, b't', 0x7D, 2, 0x12, 0x34, 0xE1];
+
+        let memo = match Memo::from_sto(&element) {
+            Ok(memo) => memo,
+            Err(_) => panic!("memo parsing failed"),
+        };
+        assert_eq!(memo.ty, b"t");
+        assert_eq!(memo.data, [0x12, 0x34]);
+        assert!(memo.format.is_empty());
+
+        // not a memo
+        assert!(matches!(
+            Memo::from_sto(&[0x7C, 1, b't']),
+            Err(Error::DoesntExist)
+        ));
+    }
+
+    #[test]
+    fn malformed_memos() {
+        // the length runs past the end
+        let sto = [0xEA, 0x7C, 10, b't', 0xE1];
+        let mut iter = Memos::from_sto(&sto).iter::<1>(2);
+
+        assert!(matches!(iter.next(), Some(Err(Error::ParseError))));
+        assert!(iter.next().is_none());
+        assert!(Memos::from_sto(&[]).iter::<2>(2).next().is_none());
+        assert!(!guard_violated());
+    }
+
+    #[test]
+    fn originating_memos() {
+        let mut buf = [0; 128];
+        let mut writer = BufWriter::new(&mut buf);
+        assert!(encode_memos::<1>(&mut writer, &MEMOS[1..2]).is_ok());
+        let encoded = writer.written().to_vec();
+
+        testing::host(|host| host.otxn = encoded);
+
+        let mut buf = [0; 128];
+        let memos = match otxn_memos(&mut buf) {
+            Ok(memos) => memos,
+            Err(_) => panic!("no memos"),
+        };
+        assert!(matches!(memos.find::<3>(b"invoice", 1), Ok(Some(_))));
+
+        testing::host(|host| host.otxn.clear());
+
+        let mut buf = [0; 128];
+        let memos = match otxn_memos(&mut buf) {
+            Ok(memos) => memos,
+            Err(_) => panic!("no memos"),
+        };
+        assert!(memos.iter::<5>(1).next().is_none());
+        assert!(!guard_violated());
+    }
+}
@@ -0,0 +1,1096 @@
+use crate::amount::TypedAmount;
+use crate::api::*;
+use crate::fields::*;
+use crate::iter::{GuardedIter, GuardedIterator};
+use crate::uninit_buf;
+
+/// Buffer length fitting a serialized ledger object, but [Amendments] and long signer lists
+pub const LEDGER_OBJECT_BUF_LEN: usize = 1024;
+/// Buffer length fitting a serialized [DirectoryNode] with a full page of entries
+pub const DIRECTORY_PAGE_BUF_LEN: usize = 1280;
+
+/// `AccountRoot` flag: a destination tag is required
+pub const LSF_REQUIRE_DEST_TAG: u32 = 0x0002_0000;
+/// `AccountRoot` flag: trust lines must be authorized
+pub const LSF_REQUIRE_AUTH: u32 = 0x0004_0000;
+/// `AccountRoot` flag: XRP should not be sent to the account
+pub const LSF_DISALLOW_XRP: u32 = 0x0008_0000;
+/// `AccountRoot` flag: the master key is disabled
+pub const LSF_DISABLE_MASTER: u32 = 0x0010_0000;
+/// `AccountRoot` flag: the account gave up freezing trust lines
+pub const LSF_NO_FREEZE: u32 = 0x0020_0000;
+/// `AccountRoot` flag: all the issued currencies are frozen
+pub const LSF_GLOBAL_FREEZE: u32 = 0x0040_0000;
+/// `AccountRoot` flag: rippling is enabled by default
+pub const LSF_DEFAULT_RIPPLE: u32 = 0x0080_0000;
+/// `AccountRoot` flag: only preauthorized accounts can send payments
+pub const LSF_DEPOSIT_AUTH: u32 = 0x0100_0000;
+
+/// `RippleState` flag: the low account holds the reserve
+pub const LSF_LOW_RESERVE: u32 = 0x0001_0000;
+/// `RippleState` flag: the high account holds the reserve
+pub const LSF_HIGH_RESERVE: u32 = 0x0002_0000;
+/// `RippleState` flag: the low account authorized the high account
+pub const LSF_LOW_AUTH: u32 = 0x0004_0000;
+/// `RippleState` flag: the high account authorized the low account
+pub const LSF_HIGH_AUTH: u32 = 0x0008_0000;
+/// `RippleState` flag: the low account disabled rippling
+pub const LSF_LOW_NO_RIPPLE: u32 = 0x0010_0000;
+/// `RippleState` flag: the high account disabled rippling
+pub const LSF_HIGH_NO_RIPPLE: u32 = 0x0020_0000;
+/// `RippleState` flag: the low account froze the line
+pub const LSF_LOW_FREEZE: u32 = 0x0040_0000;
+/// `RippleState` flag: the high account froze the line
+pub const LSF_HIGH_FREEZE: u32 = 0x0080_0000;
+
+/// `Offer` flag: passive offer
+pub const LSF_PASSIVE: u32 = 0x0001_0000;
+/// `Offer` flag: sell offer
+pub const LSF_SELL: u32 = 0x0002_0000;
+
+/// `LedgerEntryType` of ledger objects
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u16)]
+pub enum LedgerEntryType {
+    AccountRoot = 0x61,
+    Amendments = 0x66,
+    Check = 0x43,
+    DirectoryNode = 0x64,
+    Escrow = 0x75,
+    FeeSettings = 0x73,
+    Offer = 0x6F,
+    PayChannel = 0x78,
+    RippleState = 0x72,
+    SignerList = 0x53,
+}
+
+/// Ledger object decoded from its serialized form
+///
+/// Slot the object with [slot_set] on a keylet from [util_keylet],
+/// then decode it with [LedgerObject::from_slot],
+/// or with [LedgerObject::from_sto] if it is serialized already.
+///
+/// ``` txt
+/// let mut keylet = [0; KEYLET_LEN];
+/// util_keylet(&mut keylet, KeyletType::Account(&account)).unwrap();
+/// let slot_no = slot_set(&keylet, 0).unwrap() as u32;
+///
+/// let mut buf = [0; LEDGER_OBJECT_BUF_LEN];
+/// let account_root = AccountRoot::from_slot(slot_no, &mut buf).unwrap();
+/// ```
+pub trait LedgerObject<'a>: Sized {
+    /// `LedgerEntryType` of the object
+    const ENTRY_TYPE: LedgerEntryType;
+
+    /// Decode the fields, the entry type is checked already
+    fn decode(sto: &'a [u8]) -> Result<Self>;
+
+    /// Decode a serialized object, as output by `slot`
+    ///
+    /// Returns [Error::InvalidArgument] if it is another type of object.
+    #[inline(always)]
+    fn from_sto(sto: &'a [u8]) -> Result<Self> {
+        match u16_field(sto, FieldId::LedgerEntryType) {
+            Ok(Some(ty)) if ty == Self::ENTRY_TYPE as u16 => Self::decode(sto),
+            Ok(_) => Err(Error::InvalidArgument),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Serialize the object in `slot_no` into `buf` and decode it
+    #[inline(always)]
+    fn from_slot(slot_no: u32, buf: &'a mut [u8]) -> Result<Self> {
+        match slot(buf, slot_no) {
+            Ok(len) => Self::from_sto(&buf[..len as usize]),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Slot the object of the keylet and decode it, `None` if it doesn't exist
+///
+/// Uses a slot and frees it before returning.
+///
+/// ``` txt
+/// let fees: Fees = match load_object(KeyletType::Fees) {
+///     Ok(Some(fees)) => fees,
+///     _ => rollback(b"no fees", 1),
+/// };
+/// ```
+#[inline(always)]
+pub fn load_object<T>(keylet_type: KeyletType) -> Result<Option<T>>
+where
+    T: for<'a> LedgerObject<'a>,
+{
+    let mut keylet: Keylet = [0; KEYLET_LEN];
+
+    match util_keylet(&mut keylet, keylet_type) {
+        Err(e) => return Err(e),
+        Ok(_) => {}
+    }
+
+    let slot_no = match slot_set(&keylet, 0) {
+        Ok(slot_no) => slot_no as u32,
+        Err(Error::DoesntExist) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let mut buf: [u8; LEDGER_OBJECT_BUF_LEN] = uninit_buf!();
+    let object = T::from_slot(slot_no, &mut buf);
+    let _ = slot_clear(slot_no);
+
+    match object {
+        Ok(object) => Ok(Some(object)),
+        Err(e) => Err(e),
+    }
+}
+
+/// `AccountRoot` object
+#[derive(Clone, Copy)]
+pub struct AccountRoot {
+    /// `Account`
+    pub account: AccountId,
+    /// `Balance` in drops
+    pub balance: u64,
+    /// `Sequence`
+    pub sequence: u32,
+    /// `OwnerCount`
+    pub owner_count: u32,
+    /// `Flags`, see `LSF_*`
+    pub flags: u32,
+    /// `HookStateCount`, zero if absent
+    pub hook_state_count: u32,
+    /// `HookReserveCount`, zero if absent
+    pub hook_reserve_count: u32,
+}
+
+impl<'a> LedgerObject<'a> for AccountRoot {
+    const ENTRY_TYPE: LedgerEntryType = LedgerEntryType::AccountRoot;
+
+    #[inline(always)]
+    fn decode(sto: &'a [u8]) -> Result<Self> {
+        let account = match required(account_field(sto, FieldId::Account)) {
+            Ok(account) => account,
+            Err(e) => return Err(e),
+        };
+        let balance = match required(drops_field(sto, FieldId::Balance)) {
+            Ok(balance) => balance,
+            Err(e) => return Err(e),
+        };
+        let sequence = match required(u32_field(sto, FieldId::Sequence)) {
+            Ok(sequence) => sequence,
+            Err(e) => return Err(e),
+        };
+        let owner_count = match u32_field(sto, FieldId::OwnerCount) {
+            Ok(owner_count) => owner_count.unwrap_or(0),
+            Err(e) => return Err(e),
+        };
+        let flags = match u32_field(sto, FieldId::Flags) {
+            Ok(flags) => flags.unwrap_or(0),
+            Err(e) => return Err(e),
+        };
+        let hook_state_count = match u32_field(sto, FieldId::HookStateCount) {
+            Ok(count) => count.unwrap_or(0),
+            Err(e) => return Err(e),
+        };
+        let hook_reserve_count = match u32_field(sto, FieldId::HookReserveCount) {
+            Ok(count) => count.unwrap_or(0),
+            Err(e) => return Err(e),
+        };
+
+        Ok(AccountRoot {
+            account,
+            balance,
+            sequence,
+            owner_count,
+            flags,
+            hook_state_count,
+            hook_reserve_count,
+        })
+    }
+}
+
+/// `RippleState` object, a trust line between the low and the high account
+///
+/// The balance is from the low account side: positive if the high account owes the low one.
+#[derive(Clone, Copy)]
+pub struct RippleState {
+    /// `Balance`
+    pub balance: XFL,
+    /// Currency of the line
+    pub currency: CurrencyCode,
+    /// Issuer of `LowLimit`, the account with the lower id
+    pub low_account: AccountId,
+    /// `LowLimit`
+    pub low_limit: XFL,
+    /// Issuer of `HighLimit`, the account with the higher id
+    pub high_account: AccountId,
+    /// `HighLimit`
+    pub high_limit: XFL,
+    /// `Flags`, see `LSF_*`
+    pub flags: u32,
+}
+
+impl<'a> LedgerObject<'a> for RippleState {
+    const ENTRY_TYPE: LedgerEntryType = LedgerEntryType::RippleState;
+
+    #[inline(always)]
+    fn decode(sto: &'a [u8]) -> Result<Self> {
+        let (balance, currency, _) = match iou_field(sto, FieldId::Balance) {
+            Ok(balance) => balance,
+            Err(e) => return Err(e),
+        };
+        let (low_limit, _, low_account) = match iou_field(sto, FieldId::LowLimit) {
+            Ok(limit) => limit,
+            Err(e) => return Err(e),
+        };
+        let (high_limit, _, high_account) = match iou_field(sto, FieldId::HighLimit) {
+            Ok(limit) => limit,
+            Err(e) => return Err(e),
+        };
+        let flags = match u32_field(sto, FieldId::Flags) {
+            Ok(flags) => flags.unwrap_or(0),
+            Err(e) => return Err(e),
+        };
+
+        Ok(RippleState {
+            balance,
+            currency,
+            low_account,
+            low_limit,
+            high_account,
+            high_limit,
+            flags,
+        })
+    }
+}
+
+/// `Offer` object
+#[derive(Clone, Copy)]
+pub struct Offer {
+    /// `Account`, the owner
+    pub account: AccountId,
+    /// `Sequence` of the OfferCreate
+    pub sequence: u32,
+    /// `TakerPays`
+    pub taker_pays: TypedAmount,
+    /// `TakerGets`
+    pub taker_gets: TypedAmount,
+    /// `BookDirectory`, the quality directory of the offer
+    pub book_directory: Hash,
+    /// `Expiration`
+    pub expiration: Option<u32>,
+    /// `Flags`, see [LSF_PASSIVE] and [LSF_SELL]
+    pub flags: u32,
+}
+
+impl<'a> LedgerObject<'a> for Offer {
+    const ENTRY_TYPE: LedgerEntryType = LedgerEntryType::Offer;
+
+    #[inline(always)]
+    fn decode(sto: &'a [u8]) -> Result<Self> {
+        let account = match required(account_field(sto, FieldId::Account)) {
+            Ok(account) => account,
+            Err(e) => return Err(e),
+        };
+        let sequence = match required(u32_field(sto, FieldId::Sequence)) {
+            Ok(sequence) => sequence,
+            Err(e) => return Err(e),
+        };
+        let taker_pays = match required(amount_field(sto, FieldId::TakerPays)) {
+            Ok(amount) => amount,
+            Err(e) => return Err(e),
+        };
+        let taker_gets = match required(amount_field(sto, FieldId::TakerGets)) {
+            Ok(amount) => amount,
+            Err(e) => return Err(e),
+        };
+        let book_directory = match required(hash_field(sto, FieldId::BookDirectory)) {
+            Ok(hash) => hash,
+            Err(e) => return Err(e),
+        };
+        let expiration = match u32_field(sto, FieldId::Expiration) {
+            Ok(expiration) => expiration,
+            Err(e) => return Err(e),
+        };
+        let flags = match u32_field(sto, FieldId::Flags) {
+            Ok(flags) => flags.unwrap_or(0),
+            Err(e) => return Err(e),
+        };
+
+        Ok(Offer {
+            account,
+            sequence,
+            taker_pays,
+            taker_gets,
+            book_directory,
+            expiration,
+            flags,
+        })
+    }
+}
+
+/// `Escrow` object
+#[derive(Clone, Copy)]
+pub struct Escrow<'a> {
+    /// `Account`, the sender
+    pub account: AccountId,
+    /// `Destination`
+    pub destination: AccountId,
+    /// `Amount`
+    pub amount: TypedAmount,
+    /// `Condition`, the crypto-condition
+    pub condition: Option<&'a [u8]>,
+    /// `FinishAfter`
+    pub finish_after: Option<u32>,
+    /// `CancelAfter`
+    pub cancel_after: Option<u32>,
+    /// `DestinationTag`
+    pub destination_tag: Option<u32>,
+    /// `SourceTag`
+    pub source_tag: Option<u32>,
+}
+
+impl<'a> LedgerObject<'a> for Escrow<'a> {
+    const ENTRY_TYPE: LedgerEntryType = LedgerEntryType::Escrow;
+
+    #[inline(always)]
+    fn decode(sto: &'a [u8]) -> Result<Self> {
+        let account = match required(account_field(sto, FieldId::Account)) {
+            Ok(account) => account,
+            Err(e) => return Err(e),
+        };
+        let destination = match required(account_field(sto, FieldId::Destination)) {
+            Ok(account) => account,
+            Err(e) => return Err(e),
+        };
+        let amount = match required(amount_field(sto, FieldId::Amount)) {
+            Ok(amount) => amount,
+            Err(e) => return Err(e),
+        };
+        let condition = match field(sto, FieldId::Condition) {
+            Ok(condition) => condition,
+            Err(e) => return Err(e),
+        };
+        let finish_after = match u32_field(sto, FieldId::FinishAfter) {
+            Ok(time) => time,
+            Err(e) => return Err(e),
+        };
+        let cancel_after = match u32_field(sto, FieldId::CancelAfter) {
+            Ok(time) => time,
+            Err(e) => return Err(e),
+        };
+        let destination_tag = match u32_field(sto, FieldId::DestinationTag) {
+            Ok(tag) => tag,
+            Err(e) => return Err(e),
+        };
+        let source_tag = match u32_field(sto, FieldId::SourceTag) {
+            Ok(tag) => tag,
+            Err(e) => return Err(e),
+        };
+
+        Ok(Escrow {
+            account,
+            destination,
+            amount,
+            condition,
+            finish_after,
+            cancel_after,
+            destination_tag,
+            source_tag,
+        })
+    }
+}
+
+/// `Check` object
+#[derive(Clone, Copy)]
+pub struct Check {
+    /// `Account`, the sender
+    pub account: AccountId,
+    /// `Destination`
+    pub destination: AccountId,
+    /// `SendMax`
+    pub send_max: TypedAmount,
+    /// `Sequence` of the CheckCreate
+    pub sequence: u32,
+    /// `Expiration`
+    pub expiration: Option<u32>,
+    /// `InvoiceID`
+    pub invoice_id: Option<Hash>,
+    /// `DestinationTag`
+    pub destination_tag: Option<u32>,
+    /// `SourceTag`
+    pub source_tag: Option<u32>,
+}
+
+impl<'a> LedgerObject<'a> for Check {
+    const ENTRY_TYPE: LedgerEntryType = LedgerEntryType::Check;
+
+    #[inline(always)]
+    fn decode(sto: &'a [u8]) -> Result<Self> {
+        let account = match required(account_field(sto, FieldId::Account)) {
+            Ok(account) => account,
+            Err(e) => return Err(e),
+        };
+        let destination = match required(account_field(sto, FieldId::Destination)) {
+            Ok(account) => account,
+            Err(e) => return Err(e),
+        };
+        let send_max = match required(amount_field(sto, FieldId::SendMax)) {
+            Ok(amount) => amount,
+            Err(e) => return Err(e),
+        };
+        let sequence = match required(u32_field(sto, FieldId::Sequence)) {
+            Ok(sequence) => sequence,
+            Err(e) => return Err(e),
+        };
+        let expiration = match u32_field(sto, FieldId::Expiration) {
+            Ok(expiration) => expiration,
+            Err(e) => return Err(e),
+        };
+        let invoice_id = match hash_field(sto, FieldId::InvoiceID) {
+            Ok(hash) => hash,
+            Err(e) => return Err(e),
+        };
+        let destination_tag = match u32_field(sto, FieldId::DestinationTag) {
+            Ok(tag) => tag,
+            Err(e) => return Err(e),
+        };
+        let source_tag = match u32_field(sto, FieldId::SourceTag) {
+            Ok(tag) => tag,
+            Err(e) => return Err(e),
+        };
+
+        Ok(Check {
+            account,
+            destination,
+            send_max,
+            sequence,
+            expiration,
+            invoice_id,
+            destination_tag,
+            source_tag,
+        })
+    }
+}
+
+/// `PayChannel` object
+#[derive(Clone, Copy)]
+pub struct PayChannel<'a> {
+    /// `Account`, the source
+    pub account: AccountId,
+    /// `Destination`
+    pub destination: AccountId,
+    /// `Amount`, the total funded
+    pub amount: TypedAmount,
+    /// `Balance`, the total already paid out
+    pub balance: TypedAmount,
+    /// `PublicKey` signing the claims
+    pub public_key: &'a [u8],
+    /// `SettleDelay` in seconds
+    pub settle_delay: u32,
+    /// `Expiration`
+    pub expiration: Option<u32>,
+    /// `CancelAfter`
+    pub cancel_after: Option<u32>,
+    /// `DestinationTag`
+    pub destination_tag: Option<u32>,
+    /// `SourceTag`
+    pub source_tag: Option<u32>,
+}
+
+impl<'a> LedgerObject<'a> for PayChannel<'a> {
+    const ENTRY_TYPE: LedgerEntryType = LedgerEntryType::PayChannel;
+
+    #[inline(always)]
+    fn decode(sto: &'a [u8]) -> Result<Self> {
+        let account = match required(account_field(sto, FieldId::Account)) {
+            Ok(account) => account,
+            Err(e) => return Err(e),
+        };
+        let destination = match required(account_field(sto, FieldId::Destination)) {
+            Ok(account) => account,
+            Err(e) => return Err(e),
+        };
+        let amount = match required(amount_field(sto, FieldId::Amount)) {
+            Ok(amount) => amount,
+            Err(e) => return Err(e),
+        };
+        let balance = match required(amount_field(sto, FieldId::Balance)) {
+            Ok(amount) => amount,
+            Err(e) => return Err(e),
+        };
+        let public_key = match required(field(sto, FieldId::PublicKey)) {
+            Ok(public_key) => public_key,
+            Err(e) => return Err(e),
+        };
+        let settle_delay = match required(u32_field(sto, FieldId::SettleDelay)) {
+            Ok(delay) => delay,
+            Err(e) => return Err(e),
+        };
+        let expiration = match u32_field(sto, FieldId::Expiration) {
+            Ok(expiration) => expiration,
+            Err(e) => return Err(e),
+        };
+        let cancel_after = match u32_field(sto, FieldId::CancelAfter) {
+            Ok(time) => time,
+            Err(e) => return Err(e),
+        };
+        let destination_tag = match u32_field(sto, FieldId::DestinationTag) {
+            Ok(tag) => tag,
+            Err(e) => return Err(e),
+        };
+        let source_tag = match u32_field(sto, FieldId::SourceTag) {
+            Ok(tag) => tag,
+            Err(e) => return Err(e),
+        };
+
+        Ok(PayChannel {
+            account,
+            destination,
+            amount,
+            balance,
+            public_key,
+            settle_delay,
+            expiration,
+            cancel_after,
+            destination_tag,
+            source_tag,
+        })
+    }
+}
+
+/// `DirectoryNode` object, a page of an owner or an offer directory
+///
+/// A page holds 32 entries at most, slot it into a [DIRECTORY_PAGE_BUF_LEN] buffer.
+#[derive(Clone, Copy)]
+pub struct DirectoryNode<'a> {
+    /// `RootIndex`, the key of the first page
+    pub root_index: Hash,
+    /// `Indexes`, the keys of the entries, 32 bytes each
+    pub indexes: &'a [u8],
+    /// `IndexNext`, the next page, zero on the last page
+    pub index_next: u64,
+    /// `IndexPrevious`, the previous page, zero if absent
+    pub index_previous: u64,
+    /// `Owner` of an owner directory
+    pub owner: Option<AccountId>,
+    /// `ExchangeRate` of an offer directory, the quality of its offers
+    pub exchange_rate: Option<u64>,
+}
+
+impl<'a> LedgerObject<'a> for DirectoryNode<'a> {
+    const ENTRY_TYPE: LedgerEntryType = LedgerEntryType::DirectoryNode;
+
+    #[inline(always)]
+    fn decode(sto: &'a [u8]) -> Result<Self> {
+        let root_index = match required(hash_field(sto, FieldId::RootIndex)) {
+            Ok(root_index) => root_index,
+            Err(e) => return Err(e),
+        };
+        let indexes = match field(sto, FieldId::Indexes) {
+            Ok(indexes) => indexes.unwrap_or(&[]),
+            Err(e) => return Err(e),
+        };
+        let index_next = match u64_field(sto, FieldId::IndexNext) {
+            Ok(index) => index.unwrap_or(0),
+            Err(e) => return Err(e),
+        };
+        let index_previous = match u64_field(sto, FieldId::IndexPrevious) {
+            Ok(index) => index.unwrap_or(0),
+            Err(e) => return Err(e),
+        };
+        let owner = match account_field(sto, FieldId::Owner) {
+            Ok(owner) => owner,
+            Err(e) => return Err(e),
+        };
+        let exchange_rate = match u64_field(sto, FieldId::ExchangeRate) {
+            Ok(rate) => rate,
+            Err(e) => return Err(e),
+        };
+
+        Ok(DirectoryNode {
+            root_index,
+            indexes,
+            index_next,
+            index_previous,
+            owner,
+            exchange_rate,
+        })
+    }
+}
+
+impl<'a> DirectoryNode<'a> {
+    /// Number of entries on the page
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.indexes.len() / HASH_LEN
+    }
+
+    /// The page has no entries, only the root page can be empty
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// `SignerList` object
+#[derive(Clone, Copy)]
+pub struct SignerList<'a> {
+    /// `SignerQuorum`
+    pub quorum: u32,
+    /// `SignerListID`
+    pub signer_list_id: u32,
+    entries: &'a [u8],
+}
+
+/// `SignerEntry` of a [SignerList]
+#[derive(Clone, Copy)]
+pub struct SignerEntry {
+    /// `Account`
+    pub account: AccountId,
+    /// `SignerWeight`
+    pub weight: u16,
+}
+
+impl<'a> LedgerObject<'a> for SignerList<'a> {
+    const ENTRY_TYPE: LedgerEntryType = LedgerEntryType::SignerList;
+
+    #[inline(always)]
+    fn decode(sto: &'a [u8]) -> Result<Self> {
+        let quorum = match required(u32_field(sto, FieldId::SignerQuorum)) {
+            Ok(quorum) => quorum,
+            Err(e) => return Err(e),
+        };
+        let signer_list_id = match u32_field(sto, FieldId::SignerListID) {
+            Ok(id) => id.unwrap_or(0),
+            Err(e) => return Err(e),
+        };
+        let entries = match required(field(sto, FieldId::SignerEntries)) {
+            Ok(entries) => entries,
+            Err(e) => return Err(e),
+        };
+
+        Ok(SignerList {
+            quorum,
+            signer_list_id,
+            entries,
+        })
+    }
+}
+
+impl<'a> SignerList<'a> {
+    /// Guarded iterator over the signers
+    ///
+    /// `maxiter` is the maximum number of signers the loop will take,
+    /// through the entire hook execution. A list has 32 signers at most.
+    ///
+    /// Pay attention to the GUARD_ID parameter.
+    /// This should be unique on every call, through the entire hook code.
+    /// Otherwise you will encounter guard violation during the execution of your hook.
+    #[inline(always)]
+    pub fn signers<const GUARD_ID: u32>(
+        &self,
+        maxiter: u32,
+    ) -> GuardedIter<SignerIter<'a>, GUARD_ID> {
+        SignerIter {
+            entries: self.entries,
+            index: 0,
+            done: self.entries.is_empty(),
+        }
+        .guarded_iter::<GUARD_ID>(maxiter)
+    }
+}
+
+/// Iterator over the signers of a [SignerList]
+///
+/// Obtained guarded from [SignerList::signers].
+pub struct SignerIter<'a> {
+    entries: &'a [u8],
+    index: u32,
+    done: bool,
+}
+
+impl<'a> Iterator for SignerIter<'a> {
+    type Item = Result<SignerEntry>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let element = match sto_subarray(self.entries, self.index) {
+            Ok(element) => element,
+            Err(Error::DoesntExist) => {
+                self.done = true;
+                return None;
+            }
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e));
+            }
+        };
+
+        self.index += 1;
+
+        let entry = match sto_subfield(element, FieldId::SignerEntry) {
+            Ok(entry) => signer_entry(entry),
+            Err(e) => Err(e),
+        };
+        self.done = entry.is_err();
+
+        Some(entry)
+    }
+}
+
+#[inline(always)]
+fn signer_entry(entry: &[u8]) -> Result<SignerEntry> {
+    let account = match required(account_field(entry, FieldId::Account)) {
+        Ok(account) => account,
+        Err(e) => return Err(e),
+    };
+
+    match required(u16_field(entry, FieldId::SignerWeight)) {
+        Ok(weight) => Ok(SignerEntry { account, weight }),
+        Err(e) => Err(e),
+    }
+}
+
+/// `FeeSettings` object
+///
+/// Reads the drops fields of the XRPFees amendment, or the older integer fields.
+#[derive(Clone, Copy)]
+pub struct Fees {
+    /// `BaseFeeDrops` or `BaseFee`, the fee of a reference transaction in drops
+    pub base_fee: u64,
+    /// `ReserveBaseDrops` or `ReserveBase`, the account reserve in drops
+    pub reserve_base: u64,
+    /// `ReserveIncrementDrops` or `ReserveIncrement`, the reserve of an owned object in drops
+    pub reserve_increment: u64,
+}
+
+impl<'a> LedgerObject<'a> for Fees {
+    const ENTRY_TYPE: LedgerEntryType = LedgerEntryType::FeeSettings;
+
+    #[inline(always)]
+    fn decode(sto: &'a [u8]) -> Result<Self> {
+        match drops_field(sto, FieldId::BaseFeeDrops) {
+            Ok(Some(base_fee)) => {
+                let reserve_base = match required(drops_field(sto, FieldId::ReserveBaseDrops)) {
+                    Ok(drops) => drops,
+                    Err(e) => return Err(e),
+                };
+                let reserve_increment =
+                    match required(drops_field(sto, FieldId::ReserveIncrementDrops)) {
+                        Ok(drops) => drops,
+                        Err(e) => return Err(e),
+                    };
+
+                return Ok(Fees {
+                    base_fee,
+                    reserve_base,
+                    reserve_increment,
+                });
+            }
+            Ok(None) => {}
+            Err(e) => return Err(e),
+        }
+
+        let base_fee = match required(u64_field(sto, FieldId::BaseFee)) {
+            Ok(base_fee) => base_fee,
+            Err(e) => return Err(e),
+        };
+        let reserve_base = match required(u32_field(sto, FieldId::ReserveBase)) {
+            Ok(drops) => drops,
+            Err(e) => return Err(e),
+        };
+        let reserve_increment = match required(u32_field(sto, FieldId::ReserveIncrement)) {
+            Ok(drops) => drops,
+            Err(e) => return Err(e),
+        };
+
+        Ok(Fees {
+            base_fee,
+            reserve_base: reserve_base as u64,
+            reserve_increment: reserve_increment as u64,
+        })
+    }
+}
+
+/// `Amendments` object, the enabled amendments
+///
+/// It has a 32 byte hash per amendment, slot it into a large enough buffer.
+#[derive(Clone, Copy)]
+pub struct Amendments<'a> {
+    hashes: &'a [u8],
+}
+
+impl<'a> LedgerObject<'a> for Amendments<'a> {
+    const ENTRY_TYPE: LedgerEntryType = LedgerEntryType::Amendments;
+
+    #[inline(always)]
+    fn decode(sto: &'a [u8]) -> Result<Self> {
+        match field(sto, FieldId::Amendments) {
+            Ok(hashes) => Ok(Amendments::from_hashes(hashes.unwrap_or(&[]))),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<'a> Amendments<'a> {
+    /// Wraps the hashes of the `Amendments` field
+    #[inline(always)]
+    const fn from_hashes(hashes: &'a [u8]) -> Self {
+        Amendments { hashes }
+    }
+
+    /// Number of enabled amendments
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.hashes.len() / HASH_LEN
+    }
+
+    /// No amendment is enabled
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks whether the amendment is enabled
+    ///
+    /// Looks through `maxiter` amendments at most.
+    ///
+    /// Pay attention to the GUARD_ID parameter.
+    /// This should be unique on every call, through the entire hook code.
+    /// Otherwise you will encounter guard violation during the execution of your hook.
+    /// `GUARD_ID` guards the amendment loop and `GUARD_ID + 1` the comparison of the hashes
+    /// (like `GUARDM` in C), keep both unique.
+    #[inline(always)]
+    pub fn is_enabled<const GUARD_ID: u32>(&self, amendment: &Hash, maxiter: u32) -> bool {
+        let cmp_guard_id = GUARD_ID.wrapping_add(1);
+        let cmp_maxiter = maxiter.saturating_mul(HASH_LEN as u32);
+
+        for hash in self
+            .hashes
+            .chunks_exact(HASH_LEN)
+            .guarded_iter::<GUARD_ID>(maxiter)
+        {
+            // guarded loop
+            let mut i = 0;
+            while {
+                _g(cmp_guard_id, cmp_maxiter.saturating_add(1));
+                i < HASH_LEN && hash[i] == amendment[i]
+            } {
+                i += 1;
+            }
+
+            if i == HASH_LEN {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Sto};
+
+    const ACCOUNT: AccountId = [0xA1; ACC_ID_LEN];
+    const ISSUER: AccountId = [0xF2; ACC_ID_LEN];
+    const USD: CurrencyCode = [
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, b'U', b'S', b'D', 0, 0, 0, 0, 0,
+    ];
+    // 5 and 100
+    const FIVE: i64 = 0x5491_C379_37E0_8000;
+    const HUNDRED: i64 = 0x5503_8D7E_A4C6_8000;
+
+    fn insert(keylet_type: KeyletType, object: Sto) {
+        let keylet = testing::keylet(keylet_type);
+
+        testing::host(|host| host.insert_object(&keylet, object.build()));
+    }
+
+    #[test]
+    fn account_root() {
+        insert(
+            KeyletType::Account(&ACCOUNT),
+            Sto::new()
+                .u16(FieldId::LedgerEntryType as _, 0x61)
+                .u32(FieldId::Flags as _, LSF_REQUIRE_DEST_TAG)
+                .u32(FieldId::Sequence as _, 42)
+                .u32(FieldId::OwnerCount as _, 3)
+                .u32(FieldId::HookStateCount as _, 2)
+                .drops(FieldId::Balance as _, 75_000_000)
+                .vl_field(FieldId::Account as _, &ACCOUNT),
+        );
+
+        let root: AccountRoot = match load_object(KeyletType::Account(&ACCOUNT)) {
+            Ok(Some(root)) => root,
+            _ => panic!("no account root"),
+        };
+
+        assert_eq!(root.account, ACCOUNT);
+        assert_eq!(root.balance, 75_000_000);
+        assert_eq!(root.sequence, 42);
+        assert_eq!(root.owner_count, 3);
+        assert_eq!(root.flags, LSF_REQUIRE_DEST_TAG);
+        assert_eq!(root.hook_state_count, 2);
+        assert_eq!(root.hook_reserve_count, 0);
+        assert_eq!(testing::host(|host| host.slots_in_use()), 0);
+
+        assert!(matches!(
+            load_object::<AccountRoot>(KeyletType::Account(&ISSUER)),
+            Ok(None)
+        ));
+    }
+
+    #[test]
+    fn ripple_state() {
+        let line = Sto::new()
+            .u16(FieldId::LedgerEntryType as _, 0x72)
+            .u32(FieldId::Flags as _, LSF_LOW_RESERVE)
+            .iou(FieldId::Balance as _, FIVE, &USD, &[0; ACC_ID_LEN])
+            .iou(FieldId::LowLimit as _, HUNDRED, &USD, &ACCOUNT)
+            .iou(FieldId::HighLimit as _, 0, &USD, &ISSUER)
+            .build();
+
+        let state = match RippleState::from_sto(&line) {
+            Ok(state) => state,
+            Err(_) => panic!("trust line decoding failed"),
+        };
+
+        assert_eq!(state.balance.0, FIVE);
+        assert_eq!(state.currency, USD);
+        assert_eq!(state.low_account, ACCOUNT);
+        assert_eq!(state.low_limit.0, HUNDRED);
+        assert_eq!(state.high_account, ISSUER);
+        assert_eq!(state.high_limit.0, 0);
+        assert_eq!(state.flags, LSF_LOW_RESERVE);
+
+        // not an account root
+        assert!(matches!(
+            AccountRoot::from_sto(&line),
+            Err(Error::InvalidArgument)
+        ));
+    }
+
+    #[test]
+    fn fees_drops() {
+        insert(
+            KeyletType::Fees,
+            Sto::new()
+                .u16(FieldId::LedgerEntryType as _, 0x73)
+                .drops(FieldId::BaseFeeDrops as _, 10)
+                .drops(FieldId::ReserveBaseDrops as _, 1_000_000)
+                .drops(FieldId::ReserveIncrementDrops as _, 200_000),
+        );
+
+        let fees: Fees = match load_object(KeyletType::Fees) {
+            Ok(Some(fees)) => fees,
+            _ => panic!("no fees"),
+        };
+
+        assert_eq!(fees.base_fee, 10);
+        assert_eq!(fees.reserve_base, 1_000_000);
+        assert_eq!(fees.reserve_increment, 200_000);
+    }
+
+    #[test]
+    fn fees_legacy() {
+        insert(
+            KeyletType::Fees,
+            Sto::new()
+                .u16(FieldId::LedgerEntryType as _, 0x73)
+                .u32(FieldId::ReserveBase as _, 10_000_000)
+                .u32(FieldId::ReserveIncrement as _, 2_000_000)
+                .u64(FieldId::BaseFee as _, 10),
+        );
+
+        let fees: Fees = match load_object(KeyletType::Fees) {
+            Ok(Some(fees)) => fees,
+            _ => panic!("no fees"),
+        };
+
+        assert_eq!(fees.base_fee, 10);
+        assert_eq!(fees.reserve_base, 10_000_000);
+        assert_eq!(fees.reserve_increment, 2_000_000);
+
+        // the drops fields come in together
+        let partial = Sto::new()
+            .u16(FieldId::LedgerEntryType as _, 0x73)
+            .drops(FieldId::BaseFeeDrops as _, 10)
+            .build();
+        assert!(matches!(Fees::from_sto(&partial), Err(Error::DoesntExist)));
+    }
+
+    #[test]
+    fn object_sizes() {
+        // the longest signer list without wallet locators: 32 entries of 27 bytes
+        let mut list = Sto::new()
+            .u16(FieldId::LedgerEntryType as _, 0x53)
+            .u32(FieldId::Flags as _, 0)
+            .u32(FieldId::SignerQuorum as _, 32)
+            .u32(FieldId::SignerListID as _, 0)
+            .u64(FieldId::OwnerNode as _, 0)
+            .fixed(FieldId::PreviousTxnID as _, &[0; HASH_LEN])
+            .u32(FieldId::PreviousTxnLgrSeq as _, 1)
+            .fixed(FieldId::SignerEntries as _, &[]);
+        for i in 0..32 {
+            list = list
+                .fixed(FieldId::SignerEntry as _, &[])
+                .vl_field(FieldId::Account as _, &[i; ACC_ID_LEN])
+                .u16(FieldId::SignerWeight as _, 1)
+                // ObjectEndMarker
+                .fixed(14 << 16 | 1, &[]);
+        }
+        // ArrayEndMarker
+        let list = list.fixed(15 << 16 | 1, &[]).build();
+        assert!(list.len() <= LEDGER_OBJECT_BUF_LEN);
+
+        let keylet = testing::keylet(KeyletType::Signers(&ACCOUNT));
+        testing::host(|host| host.insert_object(&keylet, list.clone()));
+        let slot_no = match slot_set(&keylet, 0) {
+            Ok(slot_no) => slot_no as u32,
+            Err(_) => panic!("no signer list"),
+        };
+
+        let mut buf = [0; LEDGER_OBJECT_BUF_LEN];
+        let signers = match SignerList::from_slot(slot_no, &mut buf) {
+            Ok(signers) => signers
+                .signers::<1>(32)
+                .filter(|entry| entry.is_ok())
+                .count(),
+            Err(_) => panic!("signer list decoding failed"),
+        };
+        assert_eq!(signers, 32);
+
+        // the host doesn't serialize into a shorter buffer
+        let mut short = [0; LEDGER_OBJECT_BUF_LEN];
+        assert!(matches!(
+            SignerList::from_slot(slot_no, &mut short[..list.len() - 1]),
+            Err(Error::TooSmall)
+        ));
+        assert!(!testing::guard_violated());
+    }
+
+    #[test]
+    fn amendments() {
+        let mut hashes = [0; 3 * HASH_LEN];
+        hashes[HASH_LEN..2 * HASH_LEN].copy_from_slice(&[0xAB; HASH_LEN]);
+        hashes[2 * HASH_LEN] = 1;
+        let amendments = Amendments::from_hashes(&hashes);
+
+        assert_eq!(amendments.len(), 3);
+        assert!(amendments.is_enabled::<1>(&[0xAB; HASH_LEN], 3));
+        assert!(!amendments.is_enabled::<3>(&[0xAC; HASH_LEN], 3));
+        // the comparison bound saturates instead of overflowing
+        assert!(amendments.is_enabled::<5>(&[0xAB; HASH_LEN], 0x1000_0000));
+        assert!(!crate::testing::guard_violated());
+        assert!(Amendments::from_hashes(&[]).is_empty());
+    }
+}
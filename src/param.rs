@@ -0,0 +1,203 @@
+use crate::api::*;
+use crate::codec::BufReader;
+use crate::state::StateCodec;
+use crate::uninit_buf;
+
+/// Hook parameter decoded with [StateCodec]
+///
+/// `Ok(None)` if the hook has no such parameter,
+/// [Error::InvalidArgument] if the value lenght is not [StateCodec::SIZE].
+/// Numbers are big-endian, like everything [StateCodec] decodes.
+///
+/// ``` txt
+/// let limit = match hook_param_u64(b"LIMIT") {
+///     Ok(Some(limit)) => limit,
+///     Ok(None) => DEFAULT_LIMIT,
+///     Err(e) => rollback(b"bad LIMIT parameter", e.code() as _),
+/// };
+/// ```
+#[inline(always)]
+pub fn hook_param_decode<V: StateCodec>(name: &[u8]) -> Result<Option<V>> {
+    param_decode(name, hook_param)
+}
+
+/// `u32` hook parameter, see [hook_param_decode]
+#[inline(always)]
+pub fn hook_param_u32(name: &[u8]) -> Result<Option<u32>> {
+    hook_param_decode(name)
+}
+
+/// `u64` hook parameter, see [hook_param_decode]
+#[inline(always)]
+pub fn hook_param_u64(name: &[u8]) -> Result<Option<u64>> {
+    hook_param_decode(name)
+}
+
+/// XFL hook parameter, see [hook_param_decode]
+#[inline(always)]
+pub fn hook_param_xfl(name: &[u8]) -> Result<Option<XFL>> {
+    hook_param_decode(name)
+}
+
+/// Account id hook parameter, see [hook_param_decode]
+#[inline(always)]
+pub fn hook_param_account(name: &[u8]) -> Result<Option<AccountId>> {
+    hook_param_decode(name)
+}
+
+/// Originating transaction parameter decoded with [StateCodec]
+///
+/// See [hook_param_decode].
+#[inline(always)]
+pub fn otxn_param_decode<V: StateCodec>(name: &[u8]) -> Result<Option<V>> {
+    param_decode(name, otxn_param)
+}
+
+/// `u32` originating transaction parameter, see [otxn_param_decode]
+#[inline(always)]
+pub fn otxn_param_u32(name: &[u8]) -> Result<Option<u32>> {
+    otxn_param_decode(name)
+}
+
+/// `u64` originating transaction parameter, see [otxn_param_decode]
+#[inline(always)]
+pub fn otxn_param_u64(name: &[u8]) -> Result<Option<u64>> {
+    otxn_param_decode(name)
+}
+
+/// XFL originating transaction parameter, see [otxn_param_decode]
+#[inline(always)]
+pub fn otxn_param_xfl(name: &[u8]) -> Result<Option<XFL>> {
+    otxn_param_decode(name)
+}
+
+/// Account id originating transaction parameter, see [otxn_param_decode]
+#[inline(always)]
+pub fn otxn_param_account(name: &[u8]) -> Result<Option<AccountId>> {
+    otxn_param_decode(name)
+}
+
+#[inline(always)]
+fn param_decode<V, F>(name: &[u8], param: F) -> Result<Option<V>>
+where
+    V: StateCodec,
+    F: Fn(&mut [u8], &[u8]) -> Result<u64>,
+{
+    let mut value: [u8; HOOK_PARAM_VALUE_MAX_LEN] = uninit_buf!();
+
+    let len = match param(&mut value, name) {
+        Ok(len) => len as usize,
+        Err(Error::DoesntExist) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    match decode_value(&value[..len]) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) => Err(e),
+    }
+}
+
+/// Decode a whole parameter value
+#[inline(always)]
+fn decode_value<V: StateCodec>(value: &[u8]) -> Result<V> {
+    if value.len() != V::SIZE {
+        return Err(Error::InvalidArgument);
+    }
+
+    V::decode(&mut BufReader::new(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    const HOOK_HASH: Hash = [0x4B; HASH_LEN];
+
+    #[test]
+    fn param_values() {
+        assert!(matches!(
+            decode_value::<u64>(&[0, 0, 0, 0, 0, 0, 1, 0]),
+            Ok(256)
+        ));
+        assert!(matches!(decode_value::<AccountId>(&[7; 20]), Ok(account) if account == [7; 20]));
+        assert!(decode_value::<u64>(&[1, 0]).is_err());
+        assert!(decode_value::<u32>(&[0; 8]).is_err());
+    }
+
+    #[test]
+    fn hook_params() {
+        testing::host(|host| {
+            host.hook_params
+                .insert(b"LIMIT".to_vec(), 500u64.to_be_bytes().to_vec());
+            host.hook_params
+                .insert(b"OWNER".to_vec(), [0x0A; ACC_ID_LEN].to_vec());
+            host.hook_params.insert(b"SHORT".to_vec(), [1, 2].to_vec());
+        });
+
+        assert!(matches!(hook_param_u64(b"LIMIT"), Ok(Some(500))));
+        assert!(
+            matches!(hook_param_account(b"OWNER"), Ok(Some(owner)) if owner == [0x0A; ACC_ID_LEN])
+        );
+        assert!(matches!(hook_param_u32(b"MISSING"), Ok(None)));
+        assert!(matches!(
+            hook_param_u32(b"SHORT"),
+            Err(Error::InvalidArgument)
+        ));
+        assert!(matches!(hook_param_u32(&[b'N'; 33]), Err(Error::TooBig)));
+
+        let mut value = [0; 1];
+        assert!(matches!(
+            hook_param(&mut value, b"LIMIT"),
+            Err(Error::TooSmall)
+        ));
+    }
+
+    #[test]
+    fn otxn_params() {
+        testing::host(|host| {
+            host.otxn_params
+                .insert(b"RATE".to_vec(), 0x0102_0304u32.to_be_bytes().to_vec());
+        });
+
+        assert!(matches!(otxn_param_u32(b"RATE"), Ok(Some(0x0102_0304))));
+        assert!(matches!(
+            otxn_param_u64(b"RATE"),
+            Err(Error::InvalidArgument)
+        ));
+        assert!(matches!(otxn_param_xfl(b"LIMIT"), Ok(None)));
+    }
+
+    #[test]
+    fn param_set_and_skip() {
+        let value = 7u32.to_be_bytes();
+
+        assert!(matches!(hook_param_set(&value, b"RATE", &HOOK_HASH), Ok(4)));
+        assert!(matches!(hook_param_set(&[], b"RATE", &HOOK_HASH), Ok(0)));
+        assert!(matches!(
+            hook_param_set(&value, b"", &HOOK_HASH),
+            Err(Error::TooSmall)
+        ));
+        assert!(matches!(
+            hook_param_set(&value, b"RATE", &HOOK_HASH[1..]),
+            Err(Error::InvalidArgument)
+        ));
+
+        assert!(hook_skip(&HOOK_HASH, true).is_ok());
+        assert!(hook_skip(&HOOK_HASH, false).is_ok());
+
+        testing::host(|host| {
+            assert_eq!(
+                host.param_sets,
+                [
+                    (HOOK_HASH.to_vec(), b"RATE".to_vec(), value.to_vec()),
+                    (HOOK_HASH.to_vec(), b"RATE".to_vec(), [].to_vec()),
+                ]
+            );
+            assert_eq!(
+                host.skips,
+                [(HOOK_HASH.to_vec(), 0), (HOOK_HASH.to_vec(), 1)]
+            );
+        });
+    }
+}
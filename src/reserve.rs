@@ -0,0 +1,216 @@
+use crate::api::*;
+use crate::objects::{load_object, AccountRoot, Fees};
+
+/// Reserve of an account owning `owner_count` objects, in drops
+///
+/// Hook state entries and installed hooks are counted in `OwnerCount` by the host.
+#[inline(always)]
+pub const fn account_reserve(fees: &Fees, owner_count: u32) -> u64 {
+    fees.reserve_base
+        .saturating_add(fees.reserve_increment.saturating_mul(owner_count as u64))
+}
+
+/// Drops of the account above its reserve, zero if the balance is below it
+///
+/// `new_objects` are objects the hook is about to create, an offer or a state entry,
+/// their reserve is taken off too.
+#[inline(always)]
+pub const fn spendable_drops(fees: &Fees, account_root: &AccountRoot, new_objects: u32) -> u64 {
+    let reserve = account_reserve(fees, account_root.owner_count.saturating_add(new_objects));
+
+    account_root.balance.saturating_sub(reserve)
+}
+
+/// Current reserve settings, read from the `FeeSettings` object
+///
+/// Uses a slot and frees it before returning.
+#[inline(always)]
+pub fn fee_settings() -> Result<Fees> {
+    match load_object(KeyletType::Fees) {
+        Ok(Some(fees)) => Ok(fees),
+        Ok(None) => Err(Error::DoesntExist),
+        Err(e) => Err(e),
+    }
+}
+
+/// Spendable drops of the account, see [spendable_drops]
+///
+/// [Error::DoesntExist] if the account doesn't exist.
+/// Uses a slot at a time and frees it before returning.
+///
+/// ``` txt
+/// let available = match spendable_balance(&hook_account_id, 0) {
+///     Ok(drops) => drops,
+///     Err(e) => rollback(b"no balance", e.code() as _),
+/// };
+///
+/// if payout > available {
+///     rollback(b"payout above the reserve", 1);
+/// }
+/// ```
+#[inline(always)]
+pub fn spendable_balance(account: &AccountId, new_objects: u32) -> Result<u64> {
+    let fees = match fee_settings() {
+        Ok(fees) => fees,
+        Err(e) => return Err(e),
+    };
+
+    let account_root: AccountRoot = match load_object(KeyletType::Account(account)) {
+        Ok(Some(account_root)) => account_root,
+        Ok(None) => return Err(Error::DoesntExist),
+        Err(e) => return Err(e),
+    };
+
+    Ok(spendable_drops(&fees, &account_root, new_objects))
+}
+
+/// Spendable drops of the account the hook is installed on, see [spendable_balance]
+#[inline(always)]
+pub fn hook_spendable_balance(new_objects: u32) -> Result<u64> {
+    let mut account = [0; ACC_ID_LEN];
+
+    match hook_account(&mut account) {
+        Ok(_) => spendable_balance(&account, new_objects),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::LEDGER_OBJECT_BUF_LEN;
+    use crate::testing::{self, Sto};
+
+    const FEES: Fees = Fees {
+        base_fee: 10,
+        reserve_base: 10_000_000,
+        reserve_increment: 2_000_000,
+    };
+
+    fn account_root(balance: u64, owner_count: u32) -> AccountRoot {
+        AccountRoot {
+            account: [0; ACC_ID_LEN],
+            balance,
+            sequence: 1,
+            owner_count,
+            flags: 0,
+            hook_state_count: 0,
+            hook_reserve_count: 0,
+        }
+    }
+
+    #[test]
+    fn spendable() {
+        assert_eq!(account_reserve(&FEES, 3), 16_000_000);
+        assert_eq!(
+            spendable_drops(&FEES, &account_root(20_000_000, 3), 0),
+            4_000_000
+        );
+        assert_eq!(
+            spendable_drops(&FEES, &account_root(20_000_000, 3), 1),
+            2_000_000
+        );
+        assert_eq!(spendable_drops(&FEES, &account_root(15_000_000, 3), 0), 0);
+        assert_eq!(account_reserve(&FEES, u32::MAX), 8_589_934_600_000_000);
+    }
+
+    #[test]
+    fn hook_counts() {
+        // already part of the 3 owned objects
+        let root = AccountRoot {
+            hook_state_count: 2,
+            hook_reserve_count: 1,
+            ..account_root(30_000_000, 3)
+        };
+
+        assert_eq!(spendable_drops(&FEES, &root, 0), 14_000_000);
+        assert_eq!(spendable_drops(&FEES, &root, 7), 0);
+    }
+
+    #[test]
+    fn spendable_from_ledger() {
+        const ACCOUNT: AccountId = [0x41; ACC_ID_LEN];
+
+        let fees = testing::keylet(KeyletType::Fees);
+        let account = testing::keylet(KeyletType::Account(&ACCOUNT));
+        testing::host(|host| {
+            host.insert_object(
+                &fees,
+                Sto::new()
+                    .u16(FieldId::LedgerEntryType as _, 0x73)
+                    .drops(FieldId::BaseFeeDrops as _, 10)
+                    .drops(FieldId::ReserveBaseDrops as _, 10_000_000)
+                    .drops(FieldId::ReserveIncrementDrops as _, 2_000_000)
+                    .build(),
+            );
+            host.insert_object(
+                &account,
+                Sto::new()
+                    .u16(FieldId::LedgerEntryType as _, 0x61)
+                    .u32(FieldId::Sequence as _, 1)
+                    // a trust line, the two state entries and the hook
+                    .u32(FieldId::OwnerCount as _, 4)
+                    .u32(FieldId::HookStateCount as _, 2)
+                    .u32(FieldId::HookReserveCount as _, 1)
+                    .drops(FieldId::Balance as _, 30_000_000)
+                    .vl_field(FieldId::Account as _, &ACCOUNT)
+                    .build(),
+            );
+        });
+
+        assert!(matches!(spendable_balance(&ACCOUNT, 0), Ok(12_000_000)));
+        assert!(matches!(spendable_balance(&ACCOUNT, 1), Ok(10_000_000)));
+        assert!(matches!(
+            spendable_balance(&[0x42; ACC_ID_LEN], 0),
+            Err(Error::DoesntExist)
+        ));
+        assert_eq!(testing::host(|host| host.slots_in_use()), 0);
+    }
+
+    #[test]
+    fn account_root_size() {
+        const ACCOUNT: AccountId = [0x43; ACC_ID_LEN];
+
+        // every optional field, with the longest `Domain` the host accepts
+        let root = Sto::new()
+            .u16(FieldId::LedgerEntryType as _, 0x61)
+            .u32(FieldId::Flags as _, 0)
+            .u32(FieldId::Sequence as _, 1)
+            .u32(FieldId::OwnerCount as _, 0)
+            .u32(FieldId::TransferRate as _, 1_000_000_000)
+            .u32(FieldId::PreviousTxnLgrSeq as _, 1)
+            .u32(FieldId::HookStateCount as _, 0)
+            .u32(FieldId::HookReserveCount as _, 0)
+            .u64(FieldId::OwnerNode as _, 0)
+            .fixed(FieldId::EmailHash as _, &[0; 16])
+            .fixed(FieldId::PreviousTxnID as _, &[0; HASH_LEN])
+            .fixed(FieldId::AccountTxnID as _, &[0; HASH_LEN])
+            .fixed(FieldId::WalletLocator as _, &[0; HASH_LEN])
+            .fixed(FieldId::TickSize as _, &[5])
+            .drops(FieldId::Balance as _, 30_000_000)
+            .vl_field(FieldId::MessageKey as _, &[0; 33])
+            .vl_field(FieldId::Domain as _, &[b'a'; 256])
+            .vl_field(FieldId::Account as _, &ACCOUNT)
+            .vl_field(FieldId::RegularKey as _, &[0x44; ACC_ID_LEN])
+            .build();
+        assert!(root.len() <= LEDGER_OBJECT_BUF_LEN);
+
+        let fees = testing::keylet(KeyletType::Fees);
+        let account = testing::keylet(KeyletType::Account(&ACCOUNT));
+        testing::host(|host| {
+            host.insert_object(
+                &fees,
+                Sto::new()
+                    .u16(FieldId::LedgerEntryType as _, 0x73)
+                    .drops(FieldId::BaseFeeDrops as _, 10)
+                    .drops(FieldId::ReserveBaseDrops as _, 10_000_000)
+                    .drops(FieldId::ReserveIncrementDrops as _, 2_000_000)
+                    .build(),
+            );
+            host.insert_object(&account, root);
+        });
+
+        assert!(matches!(spendable_balance(&ACCOUNT, 0), Ok(20_000_000)));
+        assert_eq!(testing::host(|host| host.slots_in_use()), 0);
+    }
+}
@@ -0,0 +1,851 @@
+use crate::address::{encode_classic_address, CLASSIC_ADDRESS_MAX_LEN};
+use crate::api::*;
+use crate::codec::BufWriter;
+
+/// HookOn field lenght
+pub const HOOK_ON_LEN: usize = 32;
+
+/// `HookOn` value: the transaction types a hook fires on
+///
+/// A 256-bit big-endian mask where bit `n` is transaction type `n`.
+/// The bits are inverted, a set bit means "don't fire",
+/// except `SetHook` (bit 22) where a set bit means "fire".
+/// The zero value fires on everything but `SetHook`.
+///
+/// Build values in `const` items, so deploy tooling and the hook itself share them:
+///
+/// ``` txt
+/// const HOOK_ON: HookOn = HookOn::only(&[TxnType::Payment, TxnType::Invoke]);
+///
+/// match otxn_type() {
+///     Ok(tt) if HOOK_ON.includes(tt) => ...,
+///     _ => rollback(b"unexpected transaction type", 1),
+/// }
+/// ```
+///
+/// [HookOn::only] and [HookOn::all_except] loop over the types unguarded,
+/// use them at compile time only. Transaction types above 255 don't fit the mask,
+/// adding or removing them leaves the value as it is and [HookOn::includes] is `false` for them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HookOn([u8; HOOK_ON_LEN]);
+
+/// Bit of `SetHook` which is not inverted
+const HOOK_SET_BIT: u16 = 22;
+
+impl HookOn {
+    /// Fires on every transaction type, `SetHook` included
+    pub const ALL: HookOn = HookOn::all_except(&[]);
+
+    /// Fires on no transaction type
+    pub const NONE: HookOn = HookOn::only(&[]);
+
+    /// Fires only on the transaction types
+    #[inline(always)]
+    pub const fn only(types: &[TxnType]) -> Self {
+        let mut hook_on = HookOn([0xFF; HOOK_ON_LEN]).without(TxnType::HookSet);
+
+        let mut i = 0;
+        while i < types.len() {
+            hook_on = hook_on.with(types[i]);
+            i += 1;
+        }
+
+        hook_on
+    }
+
+    /// Fires on every transaction type, `SetHook` included, except the types
+    #[inline(always)]
+    pub const fn all_except(types: &[TxnType]) -> Self {
+        let mut hook_on = HookOn([0; HOOK_ON_LEN]).with(TxnType::HookSet);
+
+        let mut i = 0;
+        while i < types.len() {
+            hook_on = hook_on.without(types[i]);
+            i += 1;
+        }
+
+        hook_on
+    }
+
+    /// Also fires on the transaction type
+    ///
+    /// No-op for a type above 255, see [HookOn].
+    #[inline(always)]
+    pub const fn with(self, tt: TxnType) -> Self {
+        self.set(tt, true)
+    }
+
+    /// Doesn't fire on the transaction type
+    ///
+    /// No-op for a type above 255, see [HookOn].
+    #[inline(always)]
+    pub const fn without(self, tt: TxnType) -> Self {
+        self.set(tt, false)
+    }
+
+    /// Whether the hook fires on the transaction type
+    #[inline(always)]
+    pub const fn includes(&self, tt: TxnType) -> bool {
+        let code = tt.code();
+
+        if code > 255 {
+            return false;
+        }
+
+        let (byte, mask) = Self::position(code);
+        let bit = self.0[byte] & mask != 0;
+
+        bit == (code == HOOK_SET_BIT)
+    }
+
+    /// From the raw field value
+    #[inline(always)]
+    pub const fn from_bytes(bytes: [u8; HOOK_ON_LEN]) -> Self {
+        HookOn(bytes)
+    }
+
+    /// The raw field value
+    #[inline(always)]
+    pub const fn to_bytes(self) -> [u8; HOOK_ON_LEN] {
+        self.0
+    }
+
+    #[inline(always)]
+    const fn set(mut self, tt: TxnType, fire: bool) -> Self {
+        let code = tt.code();
+
+        if code > 255 {
+            return self;
+        }
+
+        let (byte, mask) = Self::position(code);
+
+        // `SetHook` bit is set to fire, the others are set to not fire
+        if fire == (code == HOOK_SET_BIT) {
+            self.0[byte] |= mask;
+        } else {
+            self.0[byte] &= !mask;
+        }
+
+        self
+    }
+
+    /// Byte index and bit mask of the transaction type code
+    #[inline(always)]
+    const fn position(code: u16) -> (usize, u8) {
+        (HOOK_ON_LEN - 1 - (code / 8) as usize, 1 << (code % 8))
+    }
+}
+
+/// Maximum number of hooks on an account
+pub const HOOKS_MAX: usize = 10;
+
+/// `Flags` of a hook: replace the hook at the position
+pub const HSF_OVERRIDE: u32 = 1;
+/// `Flags` of a hook: delete the state of the namespace
+pub const HSF_NS_DELETE: u32 = 2;
+/// `Flags` of a hook: allow collect calls
+pub const HSF_COLLECT: u32 = 4;
+
+/// `HookParameter` entry: a name and a value, both at most 32 and 256 bytes
+#[derive(Clone, Copy)]
+pub struct HookParameter<'a> {
+    /// `HookParameterName`
+    pub name: &'a [u8],
+    /// `HookParameterValue`, empty deletes the parameter
+    pub value: &'a [u8],
+}
+
+/// `HookGrant` entry: allows the hook to modify the state of the installing account
+#[derive(Clone, Copy)]
+pub struct HookGrant {
+    /// `HookHash` of the granted hook
+    pub hook_hash: Hash,
+    /// `Authorize`, the only account whose hook is granted, any if `None`
+    pub authorize: Option<AccountId>,
+}
+
+impl HookGrant {
+    /// Grants the hook with `hook_hash`, installed on any account
+    ///
+    /// ``` txt
+    /// // the partner account lets the controller hook keep state in its namespace
+    /// let grants = [HookGrant::new(CONTROLLER_HOOK_HASH).authorize(CONTROLLER_ACCOUNT)];
+    /// let hooks = [Hook::create(CODE, HookOn::ALL, NAMESPACE).grants(&grants)];
+    /// ```
+    #[inline(always)]
+    pub const fn new(hook_hash: Hash) -> Self {
+        HookGrant {
+            hook_hash,
+            authorize: None,
+        }
+    }
+
+    /// Only grants the hook installed on `account`
+    #[inline(always)]
+    pub const fn authorize(self, account: AccountId) -> Self {
+        HookGrant {
+            authorize: Some(account),
+            ..self
+        }
+    }
+}
+
+/// `Hook` entry of a SetHook transaction
+///
+/// Absent fields are not serialized, [Hook::SKIP] leaves the position as it is.
+#[derive(Clone, Copy)]
+pub struct Hook<'a> {
+    /// `CreateCode`, the wasm binary, empty with [HSF_OVERRIDE] deletes the hook
+    pub create_code: Option<&'a [u8]>,
+    /// `HookHash` of an already installed definition
+    pub hook_hash: Option<Hash>,
+    /// `HookOn`
+    pub hook_on: Option<HookOn>,
+    /// `HookNamespace`
+    pub namespace: Option<Hash>,
+    /// `HookApiVersion`
+    pub api_version: Option<u16>,
+    /// `Flags`, see [HSF_OVERRIDE], [HSF_NS_DELETE], [HSF_COLLECT]
+    pub flags: Option<u32>,
+    /// `HookParameters`
+    pub parameters: &'a [HookParameter<'a>],
+    /// `HookGrants`
+    pub grants: &'a [HookGrant],
+}
+
+impl<'a> Hook<'a> {
+    /// Empty entry, leaves the hook at the position as it is
+    pub const SKIP: Hook<'static> = Hook {
+        create_code: None,
+        hook_hash: None,
+        hook_on: None,
+        namespace: None,
+        api_version: None,
+        flags: None,
+        parameters: &[],
+        grants: &[],
+    };
+
+    /// Installs a wasm binary, with `HookApiVersion` 0
+    #[inline(always)]
+    pub const fn create(code: &'a [u8], hook_on: HookOn, namespace: Hash) -> Self {
+        Hook {
+            create_code: Some(code),
+            hook_on: Some(hook_on),
+            namespace: Some(namespace),
+            api_version: Some(0),
+            ..Hook::SKIP
+        }
+    }
+
+    /// Installs an already existing hook definition by its hash
+    #[inline(always)]
+    pub const fn install(hook_hash: Hash) -> Self {
+        Hook {
+            hook_hash: Some(hook_hash),
+            ..Hook::SKIP
+        }
+    }
+
+    /// Deletes the hook at the position
+    #[inline(always)]
+    pub const fn delete() -> Self {
+        Hook {
+            create_code: Some(&[]),
+            flags: Some(HSF_OVERRIDE),
+            ..Hook::SKIP
+        }
+    }
+
+    /// With `Flags`
+    #[inline(always)]
+    pub const fn flags(self, flags: u32) -> Self {
+        Hook {
+            flags: Some(flags),
+            ..self
+        }
+    }
+
+    /// With `HookParameters`
+    #[inline(always)]
+    pub const fn parameters(self, parameters: &'a [HookParameter<'a>]) -> Self {
+        Hook { parameters, ..self }
+    }
+
+    /// With `HookGrants`
+    #[inline(always)]
+    pub const fn grants(self, grants: &'a [HookGrant]) -> Self {
+        Hook { grants, ..self }
+    }
+}
+
+/// Unsigned SetHook transaction
+///
+/// Built and serialized on the host, by deployment tooling for example,
+/// without any hook API calls. The serialization loops are unguarded,
+/// so don't use it from a hook.
+///
+/// ``` txt
+/// const HOOK_ON: HookOn = HookOn::only(&[TxnType::Payment]);
+///
+/// let hooks = [Hook::create(WASM, HOOK_ON, NAMESPACE).flags(HSF_OVERRIDE)];
+/// let txn = SetHook::new(account, sequence, fee, &hooks).network_id(21337);
+///
+/// let mut blob = [0; 65536];
+/// let mut writer = BufWriter::new(&mut blob);
+/// txn.encode(&mut writer).unwrap();
+/// ```
+#[derive(Clone, Copy)]
+pub struct SetHook<'a> {
+    account: AccountId,
+    sequence: u32,
+    fee: u64,
+    flags: u32,
+    network_id: Option<u32>,
+    last_ledger_sequence: Option<u32>,
+    signing_pub_key: &'a [u8],
+    hooks: &'a [Hook<'a>],
+}
+
+impl<'a> SetHook<'a> {
+    /// SetHook transaction of the account
+    #[inline(always)]
+    pub const fn new(account: AccountId, sequence: u32, fee: u64, hooks: &'a [Hook<'a>]) -> Self {
+        SetHook {
+            account,
+            sequence,
+            fee,
+            flags: 0,
+            network_id: None,
+            last_ledger_sequence: None,
+            signing_pub_key: &[],
+            hooks,
+        }
+    }
+
+    /// With `Flags`
+    #[inline(always)]
+    pub const fn flags(self, flags: u32) -> Self {
+        SetHook { flags, ..self }
+    }
+
+    /// With `NetworkID`, required by networks with the id above 1024
+    #[inline(always)]
+    pub const fn network_id(self, network_id: u32) -> Self {
+        SetHook {
+            network_id: Some(network_id),
+            ..self
+        }
+    }
+
+    /// With `LastLedgerSequence`
+    #[inline(always)]
+    pub const fn last_ledger_sequence(self, last_ledger_sequence: u32) -> Self {
+        SetHook {
+            last_ledger_sequence: Some(last_ledger_sequence),
+            ..self
+        }
+    }
+
+    /// With `SigningPubKey`, empty by default as multi-signing expects
+    #[inline(always)]
+    pub const fn signing_pub_key(self, signing_pub_key: &'a [u8]) -> Self {
+        SetHook {
+            signing_pub_key,
+            ..self
+        }
+    }
+
+    /// Serialize into the binary format, ready to be signed
+    ///
+    /// Returns [Error::TooBig] for more than [HOOKS_MAX] hooks
+    /// and [Error::TooSmall] if the writer runs out of space.
+    pub fn encode(&self, writer: &mut BufWriter) -> Result<()> {
+        if self.hooks.len() > HOOKS_MAX {
+            return Err(Error::TooBig);
+        }
+
+        // fields in the canonical order: by type code, then by field code
+        let mut enc = Encoder(writer, Ok(()));
+        enc.uint16(1, 2, TxnType::HookSet.code());
+        if let Some(network_id) = self.network_id {
+            enc.uint32(2, 1, network_id);
+        }
+        enc.uint32(2, 2, self.flags);
+        enc.uint32(2, 4, self.sequence);
+        if let Some(last_ledger_sequence) = self.last_ledger_sequence {
+            enc.uint32(2, 27, last_ledger_sequence);
+        }
+        enc.drops(6, 8, self.fee);
+        enc.blob(7, 3, self.signing_pub_key);
+        enc.account(8, 1, &self.account);
+
+        enc.header(15, 11);
+        for hook in self.hooks.iter() {
+            enc.header(14, 14);
+            if let Some(api_version) = hook.api_version {
+                enc.uint16(1, 20, api_version);
+            }
+            if let Some(flags) = hook.flags {
+                enc.uint32(2, 2, flags);
+            }
+            if let Some(hook_on) = hook.hook_on {
+                enc.hash(5, 20, &hook_on.to_bytes());
+            }
+            if let Some(hook_hash) = hook.hook_hash {
+                enc.hash(5, 31, &hook_hash);
+            }
+            if let Some(namespace) = hook.namespace {
+                enc.hash(5, 32, &namespace);
+            }
+            if let Some(create_code) = hook.create_code {
+                enc.blob(7, 11, create_code);
+            }
+            if !hook.parameters.is_empty() {
+                enc.header(15, 19);
+                for parameter in hook.parameters.iter() {
+                    enc.header(14, 23);
+                    enc.blob(7, 24, parameter.name);
+                    enc.blob(7, 25, parameter.value);
+                    enc.object_end();
+                }
+                enc.array_end();
+            }
+            if !hook.grants.is_empty() {
+                enc.header(15, 20);
+                for grant in hook.grants.iter() {
+                    enc.header(14, 24);
+                    enc.hash(5, 31, &grant.hook_hash);
+                    if let Some(authorize) = grant.authorize {
+                        enc.account(8, 5, &authorize);
+                    }
+                    enc.object_end();
+                }
+                enc.array_end();
+            }
+            enc.object_end();
+        }
+        enc.array_end();
+
+        enc.1
+    }
+
+    /// Serialize into JSON, as submitted to a signer
+    ///
+    /// Returns [Error::TooBig] for more than [HOOKS_MAX] hooks
+    /// and [Error::TooSmall] if the writer runs out of space.
+    pub fn encode_json(&self, writer: &mut BufWriter) -> Result<()> {
+        if self.hooks.len() > HOOKS_MAX {
+            return Err(Error::TooBig);
+        }
+
+        let mut json = Json(writer, Ok(()));
+        json.raw(b"{\"TransactionType\":\"SetHook\"");
+        json.account(b"Account", &self.account);
+        json.key(b"Fee");
+        json.raw(b"\"");
+        json.number(self.fee);
+        json.raw(b"\"");
+        json.key(b"Sequence");
+        json.number(self.sequence as u64);
+        json.key(b"Flags");
+        json.number(self.flags as u64);
+        if let Some(network_id) = self.network_id {
+            json.key(b"NetworkID");
+            json.number(network_id as u64);
+        }
+        if let Some(last_ledger_sequence) = self.last_ledger_sequence {
+            json.key(b"LastLedgerSequence");
+            json.number(last_ledger_sequence as u64);
+        }
+        json.hex(b"SigningPubKey", self.signing_pub_key);
+
+        json.key(b"Hooks");
+        json.raw(b"[");
+        for (i, hook) in self.hooks.iter().enumerate() {
+            if i > 0 {
+                json.raw(b",");
+            }
+            json.raw(b"{\"Hook\":{");
+            let mut first = true;
+            if let Some(create_code) = hook.create_code {
+                json.first_hex(&mut first, b"CreateCode", create_code);
+            }
+            if let Some(hook_hash) = hook.hook_hash {
+                json.first_hex(&mut first, b"HookHash", &hook_hash);
+            }
+            if let Some(hook_on) = hook.hook_on {
+                json.first_hex(&mut first, b"HookOn", &hook_on.to_bytes());
+            }
+            if let Some(namespace) = hook.namespace {
+                json.first_hex(&mut first, b"HookNamespace", &namespace);
+            }
+            if let Some(api_version) = hook.api_version {
+                json.first_key(&mut first, b"HookApiVersion");
+                json.number(api_version as u64);
+            }
+            if let Some(flags) = hook.flags {
+                json.first_key(&mut first, b"Flags");
+                json.number(flags as u64);
+            }
+            if !hook.parameters.is_empty() {
+                json.first_key(&mut first, b"HookParameters");
+                json.raw(b"[");
+                for (i, parameter) in hook.parameters.iter().enumerate() {
+                    if i > 0 {
+                        json.raw(b",");
+                    }
+                    json.raw(b"{\"HookParameter\":{\"HookParameterName\":");
+                    json.hex_value(parameter.name);
+                    json.hex(b"HookParameterValue", parameter.value);
+                    json.raw(b"}}");
+                }
+                json.raw(b"]");
+            }
+            if !hook.grants.is_empty() {
+                json.first_key(&mut first, b"HookGrants");
+                json.raw(b"[");
+                for (i, grant) in hook.grants.iter().enumerate() {
+                    if i > 0 {
+                        json.raw(b",");
+                    }
+                    json.raw(b"{\"HookGrant\":{\"HookHash\":");
+                    json.hex_value(&grant.hook_hash);
+                    if let Some(authorize) = grant.authorize {
+                        json.account(b"Authorize", &authorize);
+                    }
+                    json.raw(b"}}");
+                }
+                json.raw(b"]");
+            }
+            json.raw(b"}}");
+        }
+        json.raw(b"]}");
+
+        json.1
+    }
+}
+
+/// Binary codec writer, remembers the first error
+struct Encoder<'a, 'b>(&'a mut BufWriter<'b>, Result<()>);
+
+impl<'a, 'b> Encoder<'a, 'b> {
+    fn write(&mut self, bytes: &[u8]) {
+        if self.1.is_ok() {
+            self.1 = self.0.write_slice(bytes);
+        }
+    }
+
+    fn header(&mut self, type_code: u8, field_code: u8) {
+        match (type_code < 16, field_code < 16) {
+            (true, true) => self.write(&[type_code << 4 | field_code]),
+            (true, false) => self.write(&[type_code << 4, field_code]),
+            (false, true) => self.write(&[field_code, type_code]),
+            (false, false) => self.write(&[0, type_code, field_code]),
+        }
+    }
+
+    fn uint16(&mut self, type_code: u8, field_code: u8, i: u16) {
+        self.header(type_code, field_code);
+        self.write(&i.to_be_bytes());
+    }
+
+    fn uint32(&mut self, type_code: u8, field_code: u8, i: u32) {
+        self.header(type_code, field_code);
+        self.write(&i.to_be_bytes());
+    }
+
+    fn drops(&mut self, type_code: u8, field_code: u8, drops: u64) {
+        self.header(type_code, field_code);
+        // positive XRP amount
+        self.write(&(drops | 0x4000_0000_0000_0000).to_be_bytes());
+    }
+
+    fn hash(&mut self, type_code: u8, field_code: u8, hash: &[u8; 32]) {
+        self.header(type_code, field_code);
+        self.write(hash);
+    }
+
+    fn account(&mut self, type_code: u8, field_code: u8, account: &AccountId) {
+        self.header(type_code, field_code);
+        self.write(&[ACC_ID_LEN as u8]);
+        self.write(account);
+    }
+
+    fn blob(&mut self, type_code: u8, field_code: u8, blob: &[u8]) {
+        self.header(type_code, field_code);
+        let len = blob.len();
+        match len {
+            0..=192 => self.write(&[len as u8]),
+            193..=12480 => {
+                let len = len - 193;
+                self.write(&[193 + (len >> 8) as u8, len as u8])
+            }
+            _ => {
+                let len = len - 12481;
+                self.write(&[241 + (len >> 16) as u8, (len >> 8) as u8, len as u8])
+            }
+        }
+        self.write(blob);
+    }
+
+    fn object_end(&mut self) {
+        self.write(&[0xE1]);
+    }
+
+    fn array_end(&mut self) {
+        self.write(&[0xF1]);
+    }
+}
+
+/// JSON writer, remembers the first error
+struct Json<'a, 'b>(&'a mut BufWriter<'b>, Result<()>);
+
+impl<'a, 'b> Json<'a, 'b> {
+    fn raw(&mut self, bytes: &[u8]) {
+        if self.1.is_ok() {
+            self.1 = self.0.write_slice(bytes);
+        }
+    }
+
+    fn key(&mut self, key: &[u8]) {
+        self.raw(b",\"");
+        self.raw(key);
+        self.raw(b"\":");
+    }
+
+    fn first_key(&mut self, first: &mut bool, key: &[u8]) {
+        if !*first {
+            self.raw(b",");
+        }
+        *first = false;
+        self.raw(b"\"");
+        self.raw(key);
+        self.raw(b"\":");
+    }
+
+    fn number(&mut self, mut i: u64) {
+        let mut digits = [0; 20];
+        let mut pos = digits.len();
+        loop {
+            pos -= 1;
+            digits[pos] = b'0' + (i % 10) as u8;
+            i /= 10;
+            if i == 0 {
+                break;
+            }
+        }
+        self.raw(&digits[pos..]);
+    }
+
+    fn hex_value(&mut self, bytes: &[u8]) {
+        const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+        self.raw(b"\"");
+        for byte in bytes.iter() {
+            self.raw(&[DIGITS[(byte >> 4) as usize], DIGITS[(byte & 0x0F) as usize]]);
+        }
+        self.raw(b"\"");
+    }
+
+    fn hex(&mut self, key: &[u8], bytes: &[u8]) {
+        self.key(key);
+        self.hex_value(bytes);
+    }
+
+    fn first_hex(&mut self, first: &mut bool, key: &[u8], bytes: &[u8]) {
+        self.first_key(first, key);
+        self.hex_value(bytes);
+    }
+
+    fn account(&mut self, key: &[u8], account: &AccountId) {
+        let mut address = [0; CLASSIC_ADDRESS_MAX_LEN];
+
+        match encode_classic_address(account, &mut address) {
+            Ok(len) => {
+                self.key(key);
+                self.raw(b"\"");
+                self.raw(&address[..len]);
+                self.raw(b"\"");
+            }
+            Err(e) => {
+                if self.1.is_ok() {
+                    self.1 = Err(e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::vec::Vec;
+
+    const GENESIS: AccountId = [
+        0xB5, 0xF7, 0x62, 0x79, 0x8A, 0x53, 0xD5, 0x43, 0xA0, 0x14, 0xCA, 0xF8, 0xB2, 0x97, 0xCF,
+        0xF8, 0xF2, 0xF9, 0x37, 0xE8,
+    ];
+    const CODE: &[u8] = &[0x00, 0x61, 0x73, 0x6D];
+    const PARAMETERS: &[HookParameter] = &[HookParameter {
+        name: b"LIMIT",
+        value: &[1],
+    }];
+
+    const PAYMENT_ONLY: HookOn = HookOn::only(&[TxnType::Payment]);
+
+    #[test]
+    fn hook_on_only() {
+        let mut expected = [0xFF; HOOK_ON_LEN];
+        expected[29] = 0xBF;
+        expected[31] = 0xFE;
+
+        assert_eq!(PAYMENT_ONLY.to_bytes(), expected);
+        assert!(PAYMENT_ONLY.includes(TxnType::Payment));
+        assert!(!PAYMENT_ONLY.includes(TxnType::HookSet));
+        assert!(!PAYMENT_ONLY.includes(TxnType::EscrowCreate));
+        assert!(!HookOn::NONE.includes(TxnType::HookSet));
+    }
+
+    #[test]
+    fn hook_on_all_except() {
+        let hook_on = HookOn::all_except(&[TxnType::HookSet, TxnType::from_code(104)]);
+
+        assert_eq!(hook_on.to_bytes(), {
+            let mut expected = [0; HOOK_ON_LEN];
+            expected[18] = 0x01;
+            expected
+        });
+        assert!(hook_on.includes(TxnType::Payment));
+        assert!(!hook_on.includes(TxnType::HookSet));
+        assert!(!hook_on.includes(TxnType::from_code(104)));
+        assert!(HookOn::ALL.includes(TxnType::HookSet));
+        assert!(!HookOn::ALL.includes(TxnType::Unknown(300)));
+        // the default value of the field
+        assert_eq!(
+            HookOn::from_bytes([0; HOOK_ON_LEN]),
+            HookOn::ALL.without(TxnType::HookSet)
+        );
+    }
+
+    #[test]
+    fn hook_on_ignores_wide_types() {
+        let hook_on = HookOn::only(&[TxnType::Payment, TxnType::Unknown(300)]);
+
+        assert_eq!(hook_on, HookOn::only(&[TxnType::Payment]));
+        assert!(!hook_on.includes(TxnType::Unknown(300)));
+        assert_eq!(HookOn::ALL.without(TxnType::Unknown(0x1FF)), HookOn::ALL);
+        assert_eq!(HookOn::NONE.with(TxnType::Unknown(256)), HookOn::NONE);
+    }
+
+    fn set_hook<'a>(hooks: &'a [Hook<'a>]) -> SetHook<'a> {
+        SetHook::new(GENESIS, 5, 100, hooks).network_id(21337)
+    }
+
+    #[test]
+    fn set_hook_binary() {
+        let hooks = [Hook::create(CODE, PAYMENT_ONLY, [0xAA; 32])
+            .flags(HSF_OVERRIDE)
+            .parameters(PARAMETERS)];
+        let mut buf = [0; 512];
+        let mut writer = BufWriter::new(&mut buf);
+
+        assert!(set_hook(&hooks).encode(&mut writer).is_ok());
+
+        let mut expected = Vec::new();
+        expected.extend_from_slice(&[0x12, 0x00, 0x16]);
+        expected.extend_from_slice(&[0x21, 0x00, 0x00, 0x53, 0x59]);
+        expected.extend_from_slice(&[0x22, 0, 0, 0, 0]);
+        expected.extend_from_slice(&[0x24, 0, 0, 0, 5]);
+        expected.extend_from_slice(&[0x68, 0x40, 0, 0, 0, 0, 0, 0, 100]);
+        expected.extend_from_slice(&[0x73, 0x00]);
+        expected.extend_from_slice(&[0x81, 0x14]);
+        expected.extend_from_slice(&GENESIS);
+        expected.extend_from_slice(&[0xFB, 0xEE]);
+        expected.extend_from_slice(&[0x10, 0x14, 0, 0]);
+        expected.extend_from_slice(&[0x22, 0, 0, 0, 1]);
+        expected.extend_from_slice(&[0x50, 0x14]);
+        expected.extend_from_slice(&PAYMENT_ONLY.to_bytes());
+        expected.extend_from_slice(&[0x50, 0x20]);
+        expected.extend_from_slice(&[0xAA; 32]);
+        expected.extend_from_slice(&[0x7B, 0x04, 0x00, 0x61, 0x73, 0x6D]);
+        expected.extend_from_slice(&[0xF0, 0x13, 0xE0, 0x17]);
+        expected.extend_from_slice(&[0x70, 0x18, 5, b'L', b'I', b'M', b'I', b'T']);
+        expected.extend_from_slice(&[0x70, 0x19, 1, 1]);
+        expected.extend_from_slice(&[0xE1, 0xF1, 0xE1, 0xF1]);
+
+        assert_eq!(writer.written(), &expected[..]);
+
+        let mut small = [0; 64];
+        assert!(set_hook(&hooks)
+            .encode(&mut BufWriter::new(&mut small))
+            .is_err());
+        assert!(set_hook(&[Hook::SKIP; HOOKS_MAX + 1])
+            .encode(&mut BufWriter::new(&mut buf))
+            .is_err());
+    }
+
+    #[test]
+    fn set_hook_grants() {
+        let grants = [
+            HookGrant::new([0x0B; 32]).authorize(GENESIS),
+            HookGrant::new([0x0C; 32]),
+        ];
+        let hooks = [Hook::install([0x0A; 32]).grants(&grants)];
+        let mut buf = [0; 512];
+        let mut writer = BufWriter::new(&mut buf);
+
+        assert!(set_hook(&hooks).encode(&mut writer).is_ok());
+
+        let mut expected = Vec::new();
+        expected.extend_from_slice(&[0xFB, 0xEE]);
+        expected.extend_from_slice(&[0x50, 0x1F]);
+        expected.extend_from_slice(&[0x0A; 32]);
+        expected.extend_from_slice(&[0xF0, 0x14, 0xE0, 0x18, 0x50, 0x1F]);
+        expected.extend_from_slice(&[0x0B; 32]);
+        expected.extend_from_slice(&[0x85, 0x14]);
+        expected.extend_from_slice(&GENESIS);
+        expected.extend_from_slice(&[0xE1, 0xE0, 0x18, 0x50, 0x1F]);
+        expected.extend_from_slice(&[0x0C; 32]);
+        expected.extend_from_slice(&[0xE1, 0xF1, 0xE1, 0xF1]);
+
+        assert!(writer.written().ends_with(&expected));
+    }
+
+    #[test]
+    fn set_hook_json() {
+        let grants = [HookGrant::new([0x0B; 32]).authorize(GENESIS)];
+        let hooks = [
+            Hook::SKIP,
+            Hook::create(CODE, PAYMENT_ONLY, [0xAA; 32])
+                .flags(HSF_OVERRIDE)
+                .parameters(PARAMETERS)
+                .grants(&grants),
+        ];
+        let mut buf = [0; 1024];
+        let mut writer = BufWriter::new(&mut buf);
+
+        assert!(set_hook(&hooks).encode_json(&mut writer).is_ok());
+
+        let expected = std::format!(
+            concat!(
+                r#"{{"TransactionType":"SetHook","Account":"rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh","#,
+                r#""Fee":"100","Sequence":5,"Flags":0,"NetworkID":21337,"SigningPubKey":"","#,
+                r#""Hooks":[{{"Hook":{{}}}},{{"Hook":{{"CreateCode":"0061736D","HookOn":"{}","#,
+                r#""HookNamespace":"{}","HookApiVersion":0,"Flags":1,"#,
+                r#""HookParameters":[{{"HookParameter":{{"HookParameterName":"4C494D4954","#,
+                r#""HookParameterValue":"01"}}}}],"#,
+                r#""HookGrants":[{{"HookGrant":{{"HookHash":"{}","#,
+                r#""Authorize":"rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh"}}}}]}}}}]}}"#
+            ),
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFBFFFFE",
+            "AA".repeat(32),
+            "0B".repeat(32),
+        );
+
+        assert_eq!(std::str::from_utf8(writer.written()).unwrap(), expected);
+    }
+}
@@ -0,0 +1,1264 @@
+use core::marker::PhantomData;
+
+use crate::api::*;
+use crate::codec::{BufReader, BufWriter};
+use crate::uninit_buf;
+
+/// Fixed-size big-endian encoding of a hook state value or key
+///
+/// Implemented for integers, `bool`, [XFL] and byte arrays
+/// (thus for [AccountId], [Amount], [Hash] and other buffers).
+pub trait StateCodec: Sized {
+    /// Encoded size in bytes
+    const SIZE: usize;
+
+    /// Write exactly [SIZE](StateCodec::SIZE) bytes
+    fn encode(&self, writer: &mut BufWriter) -> Result<()>;
+
+    /// Read exactly [SIZE](StateCodec::SIZE) bytes
+    fn decode(reader: &mut BufReader) -> Result<Self>;
+}
+
+macro_rules! impl_state_codec_int {
+    ($($t:ty),*) => {
+        $(
+            impl StateCodec for $t {
+                const SIZE: usize = core::mem::size_of::<$t>();
+
+                #[inline(always)]
+                fn encode(&self, writer: &mut BufWriter) -> Result<()> {
+                    writer.write_bytes(&self.to_be_bytes())
+                }
+
+                #[inline(always)]
+                fn decode(reader: &mut BufReader) -> Result<Self> {
+                    match reader.read_bytes() {
+                        Ok(bytes) => Ok(<$t>::from_be_bytes(bytes)),
+                        Err(e) => Err(e),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_state_codec_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl StateCodec for bool {
+    const SIZE: usize = 1;
+
+    #[inline(always)]
+    fn encode(&self, writer: &mut BufWriter) -> Result<()> {
+        writer.write_u8(*self as u8)
+    }
+
+    #[inline(always)]
+    fn decode(reader: &mut BufReader) -> Result<Self> {
+        match reader.read_u8() {
+            Ok(0) => Ok(false),
+            Ok(1) => Ok(true),
+            Ok(_) => Err(Error::InvalidArgument),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl StateCodec for XFL {
+    const SIZE: usize = 8;
+
+    #[inline(always)]
+    fn encode(&self, writer: &mut BufWriter) -> Result<()> {
+        writer.write_xfl(*self)
+    }
+
+    #[inline(always)]
+    fn decode(reader: &mut BufReader) -> Result<Self> {
+        reader.read_xfl()
+    }
+}
+
+impl<const N: usize> StateCodec for [u8; N] {
+    const SIZE: usize = N;
+
+    #[inline(always)]
+    fn encode(&self, writer: &mut BufWriter) -> Result<()> {
+        writer.write_bytes(self)
+    }
+
+    #[inline(always)]
+    fn decode(reader: &mut BufReader) -> Result<Self> {
+        reader.read_bytes()
+    }
+}
+
+macro_rules! impl_state_codec_tuple {
+    ($($name:ident),*) => {
+        impl<$($name: StateCodec),*> StateCodec for ($($name,)*) {
+            const SIZE: usize = 0 $(+ $name::SIZE)*;
+
+            #[inline(always)]
+            #[allow(non_snake_case)]
+            fn encode(&self, writer: &mut BufWriter) -> Result<()> {
+                let ($($name,)*) = self;
+                $(
+                    match $name.encode(writer) {
+                        Err(e) => return Err(e),
+                        Ok(_) => {}
+                    }
+                )*
+                Ok(())
+            }
+
+            #[inline(always)]
+            #[allow(non_snake_case)]
+            fn decode(reader: &mut BufReader) -> Result<Self> {
+                $(
+                    let $name = match $name::decode(reader) {
+                        Ok(value) => value,
+                        Err(e) => return Err(e),
+                    };
+                )*
+                Ok(($($name,)*))
+            }
+        }
+    };
+}
+
+impl_state_codec_tuple!(A, B);
+impl_state_codec_tuple!(A, B, C);
+impl_state_codec_tuple!(A, B, C, D);
+
+/// A typed hook state entry
+///
+/// The 32 byte state key is derived from a namespace and a typed key:
+///
+/// ``` txt
+/// | zero padding | key | namespace | namespace length |
+/// ```
+///
+/// The layout is left-padded the way the host pads short keys,
+/// and entries of different namespaces never collide.
+///
+/// # Example
+///
+/// ``` txt
+/// let balance: StateCell<AccountId, u64> = StateCell::new(b"BAL", &account_id).unwrap();
+///
+/// let drops = match balance.load() {
+///     Ok(Some(drops)) => drops,
+///     Ok(None) => 0,
+///     Err(e) => rollback(b"state error", e.code() as _),
+/// };
+///
+/// balance.store(&(drops + 1)).unwrap();
+/// ```
+pub struct StateCell<K, V> {
+    key: StateKey,
+    _marker: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K: StateCodec, V: StateCodec> StateCell<K, V> {
+    /// Creates a state entry for the namespace and the key
+    ///
+    /// Returns [Error::TooBig] if the key and the namespace don't fit in 31 bytes.
+    #[inline(always)]
+    pub fn new<const N: usize>(namespace: &[u8; N], key: &K) -> Result<Self> {
+        let used = K::SIZE + N + 1;
+
+        if used > STATE_KEY_LEN || N > u8::MAX as usize {
+            return Err(Error::TooBig);
+        }
+
+        let mut state_key: StateKey = [0; STATE_KEY_LEN];
+        let mut writer = BufWriter::new(&mut state_key[STATE_KEY_LEN - used..]);
+
+        match key.encode(&mut writer) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+
+        match writer.write_bytes(namespace) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+
+        match writer.write_u8(N as u8) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+
+        Ok(Self::from_key(state_key))
+    }
+
+    /// Creates a state entry for an already derived 32 byte state key
+    #[inline(always)]
+    pub const fn from_key(key: StateKey) -> Self {
+        StateCell {
+            key,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The derived 32 byte state key
+    #[inline(always)]
+    pub fn key(&self) -> &StateKey {
+        &self.key
+    }
+
+    /// Load the value, `None` if the entry doesn't exist
+    #[inline(always)]
+    pub fn load(&self) -> Result<Option<V>> {
+        if V::SIZE > STATE_DATA_MAX_LEN {
+            return Err(Error::TooBig);
+        }
+
+        let mut data: [u8; STATE_DATA_MAX_LEN] = uninit_buf!();
+
+        let len = match state(&mut data[..V::SIZE], &self.key) {
+            Ok(len) => len as usize,
+            Err(Error::DoesntExist) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        match V::decode(&mut BufReader::new(&data[..len])) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Store the value
+    #[inline(always)]
+    pub fn store(&self, value: &V) -> Result<()> {
+        if V::SIZE > STATE_DATA_MAX_LEN {
+            return Err(Error::TooBig);
+        }
+
+        let mut data: [u8; STATE_DATA_MAX_LEN] = uninit_buf!();
+
+        match value.encode(&mut BufWriter::new(&mut data[..V::SIZE])) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+
+        match state_set(&data[..V::SIZE], &self.key) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Delete the entry
+    #[inline(always)]
+    pub fn remove(&self) -> Result<()> {
+        match state_set(&[], &self.key) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Checks whether the entry exists
+    #[inline(always)]
+    pub fn exists(&self) -> Result<bool> {
+        let mut data: [u8; STATE_DATA_MAX_LEN] = uninit_buf!();
+
+        match state(&mut data, &self.key) {
+            Ok(_) => Ok(true),
+            Err(Error::DoesntExist) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Load the value from the state of another account's hook
+    ///
+    /// With `api-v2` the value is read from the `namespace` of the account,
+    /// the one [StateCell::store_foreign] writes to.
+    /// Tells a missing key apart from an account without any hook state.
+    #[inline(always)]
+    pub fn load_foreign(
+        &self,
+        account: &AccountId,
+        #[cfg(feature = "api-v2")] namespace: &Hash,
+    ) -> Result<ForeignState<V>> {
+        #[cfg(feature = "api-v2")]
+        let namespace: &[u8] = namespace;
+        #[cfg(not(feature = "api-v2"))]
+        let namespace: &[u8] = &[];
+
+        match self.read_foreign(account, namespace) {
+            Ok(ForeignState::KeyMissing) => foreign_key_missing(account),
+            res => res,
+        }
+    }
+
+    /// Store the value in the state of another account, in its `namespace`
+    ///
+    /// The account must have installed a hook with a [HookGrant](crate::sethook::HookGrant)
+    /// for this hook, otherwise the host returns [Error::NotAuthorized].
+    #[cfg(feature = "api-v2")]
+    #[inline(always)]
+    pub fn store_foreign(&self, account: &AccountId, namespace: &Hash, value: &V) -> Result<()> {
+        if V::SIZE > STATE_DATA_MAX_LEN {
+            return Err(Error::TooBig);
+        }
+
+        let mut data: [u8; STATE_DATA_MAX_LEN] = uninit_buf!();
+
+        match value.encode(&mut BufWriter::new(&mut data[..V::SIZE])) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+
+        match state_foreign_set(&data[..V::SIZE], &self.key, namespace, account) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Delete the entry from the state of another account, see [StateCell::store_foreign]
+    #[cfg(feature = "api-v2")]
+    #[inline(always)]
+    pub fn remove_foreign(&self, account: &AccountId, namespace: &Hash) -> Result<()> {
+        match state_foreign_set(&[], &self.key, namespace, account) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Reads the foreign value, a missing key is not checked further
+    ///
+    /// `namespace` is ignored without `api-v2`.
+    #[inline(always)]
+    fn read_foreign(&self, account: &AccountId, namespace: &[u8]) -> Result<ForeignState<V>> {
+        if V::SIZE > STATE_DATA_MAX_LEN {
+            return Err(Error::TooBig);
+        }
+
+        let mut data: [u8; STATE_DATA_MAX_LEN] = uninit_buf!();
+
+        #[cfg(feature = "api-v2")]
+        let res = state_foreign(&mut data[..V::SIZE], &self.key, namespace, account);
+        #[cfg(not(feature = "api-v2"))]
+        let res = {
+            let _ = namespace;
+            state_foreign(&mut data[..V::SIZE], &self.key, account)
+        };
+
+        let len = match res {
+            Ok(len) => len as usize,
+            Err(Error::DoesntExist) => return Ok(ForeignState::KeyMissing),
+            Err(e) => return Err(e),
+        };
+
+        match V::decode(&mut BufReader::new(&data[..len])) {
+            Ok(value) => Ok(ForeignState::Value(value)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// A value read from the state of another account's hook
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ForeignState<V> {
+    /// The key exists
+    Value(V),
+    /// The account has hook state, but not this key
+    KeyMissing,
+    /// The account has no hook state at all (or doesn't exist)
+    NoHookState,
+}
+
+impl<V> ForeignState<V> {
+    /// The value, if any
+    #[inline(always)]
+    pub fn value(self) -> Option<V> {
+        match self {
+            ForeignState::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Number of hook state entries the account owns
+///
+/// Reads `HookStateCount` of the account root, `0` if the account doesn't exist.
+/// Uses two slots and frees them before returning.
+#[inline(always)]
+pub fn hook_state_count(account: &AccountId) -> Result<u32> {
+    let mut keylet: Keylet = [0; KEYLET_LEN];
+
+    match util_keylet(&mut keylet, KeyletType::Account(account)) {
+        Err(e) => return Err(e),
+        Ok(_) => {}
+    }
+
+    let account_slot = match slot_set(&keylet, 0) {
+        Ok(slot_no) => slot_no as u32,
+        Err(Error::DoesntExist) => return Ok(0),
+        Err(e) => return Err(e),
+    };
+
+    let count = match slot_subfield(account_slot, FieldId::HookStateCount, 0) {
+        Ok(count_slot) => {
+            let mut count = [0; 4];
+            let res = slot(&mut count, count_slot as u32);
+            let _ = slot_clear(count_slot as u32);
+
+            match res {
+                Ok(_) => Ok(u32::from_be_bytes(count)),
+                Err(e) => Err(e),
+            }
+        }
+        Err(Error::DoesntExist) => Ok(0),
+        Err(e) => Err(e),
+    };
+
+    let _ = slot_clear(account_slot);
+
+    count
+}
+
+#[inline(always)]
+fn foreign_key_missing<V>(account: &AccountId) -> Result<ForeignState<V>> {
+    match hook_state_count(account) {
+        Ok(0) => Ok(ForeignState::NoHookState),
+        Ok(_) => Ok(ForeignState::KeyMissing),
+        Err(e) => Err(e),
+    }
+}
+
+/// Namespace of a [StateMap]
+///
+/// # Example
+///
+/// ``` txt
+/// struct Balances;
+///
+/// impl StateNamespace for Balances {
+///     const NAME: &'static [u8] = b"balances";
+/// }
+/// ```
+pub trait StateNamespace {
+    /// Name hashed into every key of the map, keep it short
+    const NAME: &'static [u8];
+}
+
+/// Maximum length of a namespace name, its length byte and an encoded key hashed together
+pub const STATE_MAP_KEY_DATA_MAX_LEN: usize = 64;
+
+/// A typed map in the hook state
+///
+/// Every entry is stored under `SHA-512Half(len(namespace) || namespace || key)`,
+/// so keys can be larger than 32 bytes and maps of different namespaces don't collide.
+/// Use tuples for compound keys. Hashing costs a host call,
+/// [StateCell] is a cheaper option for small keys.
+///
+/// # Example
+///
+/// ``` txt
+/// let daily: StateMap<DailyVolume, (AccountId, u32), u64> = StateMap::new();
+/// let day = (ledger_seq() / 20_000) as u32;
+///
+/// daily.counter(&(account_id, day)).unwrap().increment(drops).unwrap();
+/// ```
+pub struct StateMap<N, K, V> {
+    _marker: PhantomData<(N, K, V)>,
+}
+
+impl<N: StateNamespace, K: StateCodec, V: StateCodec> Default for StateMap<N, K, V> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: StateNamespace, K: StateCodec, V: StateCodec> StateMap<N, K, V> {
+    /// Creates the map
+    #[inline(always)]
+    pub const fn new() -> Self {
+        StateMap {
+            _marker: PhantomData,
+        }
+    }
+
+    /// The state entry of the key
+    ///
+    /// Returns [Error::TooBig] if the namespace name, its length byte and the key
+    /// exceed [STATE_MAP_KEY_DATA_MAX_LEN].
+    #[inline(always)]
+    pub fn entry(&self, key: &K) -> Result<StateCell<K, V>> {
+        let len = 1 + N::NAME.len() + K::SIZE;
+
+        if len > STATE_MAP_KEY_DATA_MAX_LEN {
+            return Err(Error::TooBig);
+        }
+
+        let mut data: [u8; STATE_MAP_KEY_DATA_MAX_LEN] = uninit_buf!();
+        let mut writer = BufWriter::new(&mut data[..len]);
+
+        // the length keeps a name and the start of a key apart from a longer name
+        match writer.write_u8(N::NAME.len() as u8) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+
+        match writer.write_slice(N::NAME) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+
+        match key.encode(&mut writer) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+
+        let mut state_key: StateKey = [0; STATE_KEY_LEN];
+
+        match util_sha512h(&mut state_key, &data[..len]) {
+            Ok(_) => Ok(StateCell::from_key(state_key)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Get the value, `None` if the key doesn't exist
+    #[inline(always)]
+    pub fn get(&self, key: &K) -> Result<Option<V>> {
+        match self.entry(key) {
+            Ok(cell) => cell.load(),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Set the value
+    #[inline(always)]
+    pub fn set(&self, key: &K, value: &V) -> Result<()> {
+        match self.entry(key) {
+            Ok(cell) => cell.store(value),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Remove the key
+    #[inline(always)]
+    pub fn remove(&self, key: &K) -> Result<()> {
+        match self.entry(key) {
+            Ok(cell) => cell.remove(),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Checks whether the key exists
+    #[inline(always)]
+    pub fn contains(&self, key: &K) -> Result<bool> {
+        match self.entry(key) {
+            Ok(cell) => cell.exists(),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Get the value from the same map kept by another account's hook
+    ///
+    /// With `api-v2` the map is read from the `namespace` of the account.
+    #[inline(always)]
+    pub fn get_foreign(
+        &self,
+        account: &AccountId,
+        #[cfg(feature = "api-v2")] namespace: &Hash,
+        key: &K,
+    ) -> Result<ForeignState<V>> {
+        #[cfg(feature = "api-v2")]
+        let namespace: &[u8] = namespace;
+        #[cfg(not(feature = "api-v2"))]
+        let namespace: &[u8] = &[];
+
+        match self.entry(key) {
+            Ok(cell) => match cell.read_foreign(account, namespace) {
+                Ok(ForeignState::KeyMissing) => foreign_key_missing(account),
+                res => res,
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Set the value in the same map kept in the `namespace` of another account
+    ///
+    /// The account must grant this hook, see [StateCell::store_foreign].
+    #[cfg(feature = "api-v2")]
+    #[inline(always)]
+    pub fn set_foreign(
+        &self,
+        account: &AccountId,
+        namespace: &Hash,
+        key: &K,
+        value: &V,
+    ) -> Result<()> {
+        match self.entry(key) {
+            Ok(cell) => cell.store_foreign(account, namespace, value),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Remove the key from the same map kept in the `namespace` of another account
+    #[cfg(feature = "api-v2")]
+    #[inline(always)]
+    pub fn remove_foreign(&self, account: &AccountId, namespace: &Hash, key: &K) -> Result<()> {
+        match self.entry(key) {
+            Ok(cell) => cell.remove_foreign(account, namespace),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<N: StateNamespace, K: StateCodec, V: StateCodec + Copy> StateMap<N, K, V> {
+    /// Get many values from the same map kept by another account's hook
+    ///
+    /// Writes a result per key into `out`, which must be as long as `keys`.
+    /// The account is checked for hook state at most once,
+    /// which suits oracle hooks reading a bunch of price feeds.
+    /// With `api-v2` the values are read from the `namespace` of the account.
+    ///
+    /// Guard budget: `keys.len()` iterations.
+    ///
+    /// Pay attention to the GUARD_ID parameter.
+    /// This should be unique on every call, through the entire hook code.
+    /// Otherwise you will encounter guard violation during the execution of your hook.
+    #[inline(always)]
+    pub fn get_foreign_batch<const GUARD_ID: u32>(
+        &self,
+        account: &AccountId,
+        #[cfg(feature = "api-v2")] namespace: &Hash,
+        keys: &[K],
+        out: &mut [ForeignState<V>],
+    ) -> Result<()> {
+        #[cfg(feature = "api-v2")]
+        let namespace: &[u8] = namespace;
+        #[cfg(not(feature = "api-v2"))]
+        let namespace: &[u8] = &[];
+
+        let keys_len = keys.len();
+
+        if keys_len != out.len() {
+            return Err(Error::InvalidArgument);
+        }
+
+        let mut has_state = None;
+
+        // guarded loop
+        let mut i = 0;
+        while {
+            _g(GUARD_ID, keys_len as u32 + 1);
+            i < keys_len
+        } {
+            let res = match self.entry(&keys[i]) {
+                Ok(cell) => cell.read_foreign(account, namespace),
+                Err(e) => Err(e),
+            };
+
+            out[i] = match res {
+                Ok(ForeignState::KeyMissing) => {
+                    if has_state.is_none() {
+                        has_state = match hook_state_count(account) {
+                            Ok(count) => Some(count > 0),
+                            Err(e) => return Err(e),
+                        };
+                    }
+
+                    match has_state {
+                        Some(false) => ForeignState::NoHookState,
+                        _ => ForeignState::KeyMissing,
+                    }
+                }
+                Ok(value) => value,
+                Err(e) => return Err(e),
+            };
+
+            i += 1;
+        }
+
+        Ok(())
+    }
+}
+
+impl<N: StateNamespace, K: StateCodec, V: CounterValue> StateMap<N, K, V> {
+    /// The counter of the key
+    #[inline(always)]
+    pub fn counter(&self, key: &K) -> Result<StateCounter<K, V>> {
+        match self.entry(key) {
+            Ok(cell) => Ok(StateCounter::from_cell(cell)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// A value of a [StateCounter]
+pub trait CounterValue: StateCodec + Copy {
+    /// Value of a missing counter
+    const ZERO: Self;
+
+    /// Addition returning an error instead of wrapping
+    fn checked_add(self, other: Self) -> Result<Self>;
+
+    /// Subtraction returning an error instead of wrapping
+    fn checked_sub(self, other: Self) -> Result<Self>;
+}
+
+macro_rules! impl_counter_value_int {
+    ($($t:ty),*) => {
+        $(
+            impl CounterValue for $t {
+                const ZERO: Self = 0;
+
+                #[inline(always)]
+                fn checked_add(self, other: Self) -> Result<Self> {
+                    match <$t>::checked_add(self, other) {
+                        Some(sum) => Ok(sum),
+                        None => Err(Error::Overflow),
+                    }
+                }
+
+                #[inline(always)]
+                fn checked_sub(self, other: Self) -> Result<Self> {
+                    match <$t>::checked_sub(self, other) {
+                        Some(diff) => Ok(diff),
+                        None => Err(Error::CantReturnNegative),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_counter_value_int!(u32, u64, i64);
+
+impl CounterValue for XFL {
+    const ZERO: Self = XFL(0);
+
+    #[inline(always)]
+    fn checked_add(self, other: Self) -> Result<Self> {
+        float_sum(self, other)
+    }
+
+    #[inline(always)]
+    fn checked_sub(self, other: Self) -> Result<Self> {
+        match float_negate(other) {
+            Ok(negated) => float_sum(self, negated),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// A counter in the hook state
+///
+/// A missing counter reads as zero. Unsigned counters never wrap:
+/// [Error::Overflow] is returned on overflow and [Error::CantReturnNegative] below zero,
+/// XFL counters return the host errors.
+/// The state is left untouched on an error.
+pub struct StateCounter<K, V = u64> {
+    cell: StateCell<K, V>,
+}
+
+impl<K: StateCodec, V: CounterValue> StateCounter<K, V> {
+    /// Creates a counter for the namespace and the key, see [StateCell::new]
+    #[inline(always)]
+    pub fn new<const N: usize>(namespace: &[u8; N], key: &K) -> Result<Self> {
+        match StateCell::new(namespace, key) {
+            Ok(cell) => Ok(Self::from_cell(cell)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Creates a counter stored in the state entry
+    #[inline(always)]
+    pub const fn from_cell(cell: StateCell<K, V>) -> Self {
+        StateCounter { cell }
+    }
+
+    /// Current value
+    #[inline(always)]
+    pub fn get(&self) -> Result<V> {
+        match self.cell.load() {
+            Ok(Some(value)) => Ok(value),
+            Ok(None) => Ok(V::ZERO),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Add to the counter and return the new value
+    #[inline(always)]
+    pub fn increment(&self, by: V) -> Result<V> {
+        let value = match self.get() {
+            Ok(value) => value.checked_add(by),
+            Err(e) => Err(e),
+        };
+
+        self.store(value)
+    }
+
+    /// Subtract from the counter and return the new value
+    #[inline(always)]
+    pub fn decrement(&self, by: V) -> Result<V> {
+        let value = match self.get() {
+            Ok(value) => value.checked_sub(by),
+            Err(e) => Err(e),
+        };
+
+        self.store(value)
+    }
+
+    /// Delete the counter, it reads as zero afterwards
+    #[inline(always)]
+    pub fn reset(&self) -> Result<()> {
+        self.cell.remove()
+    }
+
+    #[inline(always)]
+    fn store(&self, value: Result<V>) -> Result<V> {
+        match value {
+            Ok(value) => match self.cell.store(&value) {
+                Ok(_) => Ok(value),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Sto};
+
+    fn roundtrip<T: StateCodec>(value: &T) -> Result<T> {
+        let mut buf = [0; 64];
+        match value.encode(&mut BufWriter::new(&mut buf[..T::SIZE])) {
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+        T::decode(&mut BufReader::new(&buf[..T::SIZE]))
+    }
+
+    fn key_of<K: StateCodec, V: StateCodec>(cell: Result<StateCell<K, V>>) -> Option<StateKey> {
+        match cell {
+            Ok(cell) => Some(*cell.key()),
+            Err(_) => None,
+        }
+    }
+
+    #[test]
+    fn codecs_roundtrip() {
+        assert!(matches!(roundtrip(&0xABCDu16), Ok(0xABCD)));
+        assert!(matches!(roundtrip(&-7i32), Ok(-7)));
+        assert!(matches!(roundtrip(&u64::MAX), Ok(u64::MAX)));
+        assert!(matches!(roundtrip(&true), Ok(true)));
+        assert!(matches!(roundtrip(&[7u8; ACC_ID_LEN]), Ok([7, ..])));
+        assert_eq!(<Amount as StateCodec>::SIZE, AMOUNT_LEN);
+    }
+
+    #[test]
+    fn tuples_roundtrip() {
+        assert_eq!(<(AccountId, u32)>::SIZE, 24);
+        assert!(matches!(
+            roundtrip(&([3u8; ACC_ID_LEN], 0x0A0Bu16, true)),
+            Ok(([3, ..], 0x0A0B, true))
+        ));
+    }
+
+    #[test]
+    fn counter_values_never_wrap() {
+        assert!(matches!(CounterValue::checked_add(1u64, 2), Ok(3)));
+        assert!(matches!(
+            CounterValue::checked_add(u64::MAX, 1),
+            Err(Error::Overflow)
+        ));
+        assert!(matches!(
+            CounterValue::checked_sub(1u32, 2),
+            Err(Error::CantReturnNegative)
+        ));
+        assert!(matches!(CounterValue::checked_sub(1i64, 2), Ok(-1)));
+    }
+
+    struct Prices;
+
+    impl StateNamespace for Prices {
+        const NAME: &'static [u8] = b"prices";
+    }
+
+    #[test]
+    fn foreign_batch_lengths_must_match() {
+        let prices: StateMap<Prices, CurrencyCode, u64> = StateMap::new();
+        let mut out = [ForeignState::KeyMissing; 1];
+
+        let keys = [[0; CURRENCY_CODE_SIZE]; 2];
+
+        #[cfg(feature = "api-v2")]
+        let res = prices.get_foreign_batch::<1>(&[0; ACC_ID_LEN], &[0; HASH_LEN], &keys, &mut out);
+        #[cfg(not(feature = "api-v2"))]
+        let res = prices.get_foreign_batch::<1>(&[0; ACC_ID_LEN], &keys, &mut out);
+
+        assert!(res.is_err());
+        assert_eq!(ForeignState::Value(5u64).value(), Some(5));
+        assert_eq!(ForeignState::<u64>::NoHookState.value(), None);
+    }
+
+    struct Ab;
+
+    impl StateNamespace for Ab {
+        const NAME: &'static [u8] = b"ab";
+    }
+
+    struct A;
+
+    impl StateNamespace for A {
+        const NAME: &'static [u8] = b"a";
+    }
+
+    #[test]
+    fn map_namespaces_dont_collide() {
+        let ab: StateMap<Ab, [u8; 3], u8> = StateMap::new();
+        let a: StateMap<A, [u8; 4], u8> = StateMap::new();
+
+        let (ab_key, a_key) = match (ab.entry(b"cde"), a.entry(b"bcde")) {
+            (Ok(ab), Ok(a)) => (ab.key, a.key),
+            _ => panic!("no entries"),
+        };
+        assert_ne!(ab_key, a_key);
+
+        // the key is hashed after the name and its length
+        let expected = testing::digest(&[&[2, b'a', b'b', b'c', b'd', b'e']]);
+        assert_eq!(ab_key, expected);
+    }
+
+    #[cfg(feature = "api-v2")]
+    const NAMESPACE: Hash = [0x4E; HASH_LEN];
+    // the test host keeps the hook's own state under a zero namespace
+    #[cfg(not(feature = "api-v2"))]
+    const NAMESPACE: Hash = [0; HASH_LEN];
+
+    const WITH_STATE: AccountId = [0xA1; ACC_ID_LEN];
+    const WITHOUT_STATE: AccountId = [0xB2; ACC_ID_LEN];
+    const UNFUNDED: AccountId = [0xC3; ACC_ID_LEN];
+
+    fn foreign_ledger() {
+        let with_state = testing::keylet(KeyletType::Account(&WITH_STATE));
+        let without_state = testing::keylet(KeyletType::Account(&WITHOUT_STATE));
+
+        testing::host(|host| {
+            host.insert_object(
+                &with_state,
+                Sto::new()
+                    .u16(FieldId::LedgerEntryType as _, 0x0061)
+                    .u32(FieldId::HookStateCount as _, 2)
+                    .build(),
+            );
+            host.insert_object(
+                &without_state,
+                Sto::new()
+                    .u16(FieldId::LedgerEntryType as _, 0x0061)
+                    .build(),
+            );
+        });
+    }
+
+    fn load_foreign(cell: &StateCell<u32, u64>, account: &AccountId) -> Result<ForeignState<u64>> {
+        #[cfg(feature = "api-v2")]
+        let res = cell.load_foreign(account, &NAMESPACE);
+        #[cfg(not(feature = "api-v2"))]
+        let res = cell.load_foreign(account);
+
+        res
+    }
+
+    fn cell(key: u32) -> StateCell<u32, u64> {
+        match StateCell::new(b"cell", &key) {
+            Ok(cell) => cell,
+            Err(_) => panic!("key too long"),
+        }
+    }
+
+    #[test]
+    fn hook_state_counts() {
+        foreign_ledger();
+
+        assert!(matches!(hook_state_count(&WITH_STATE), Ok(2)));
+        assert!(matches!(hook_state_count(&WITHOUT_STATE), Ok(0)));
+        assert!(matches!(hook_state_count(&UNFUNDED), Ok(0)));
+        assert_eq!(testing::host(|host| host.slots_in_use()), 0);
+    }
+
+    #[test]
+    fn foreign_missing_keys() {
+        foreign_ledger();
+
+        let (present, missing) = (cell(1), cell(2));
+        testing::host(|host| {
+            host.set_state(&WITH_STATE, &NAMESPACE, present.key(), &42u64.to_be_bytes())
+        });
+
+        assert!(matches!(
+            load_foreign(&present, &WITH_STATE),
+            Ok(ForeignState::Value(42))
+        ));
+        assert!(matches!(
+            load_foreign(&missing, &WITH_STATE),
+            Ok(ForeignState::KeyMissing)
+        ));
+        assert!(matches!(
+            load_foreign(&missing, &WITHOUT_STATE),
+            Ok(ForeignState::NoHookState)
+        ));
+        assert!(matches!(
+            load_foreign(&missing, &UNFUNDED),
+            Ok(ForeignState::NoHookState)
+        ));
+        assert_eq!(testing::host(|host| host.slots_in_use()), 0);
+    }
+
+    #[cfg(feature = "api-v2")]
+    #[test]
+    fn foreign_reads_use_the_namespace() {
+        foreign_ledger();
+
+        let cell = cell(1);
+        testing::host(|host| {
+            host.set_state(
+                &WITH_STATE,
+                &[0x55; HASH_LEN],
+                cell.key(),
+                &42u64.to_be_bytes(),
+            )
+        });
+
+        assert!(matches!(
+            cell.load_foreign(&WITH_STATE, &[0x55; HASH_LEN]),
+            Ok(ForeignState::Value(42))
+        ));
+        assert!(matches!(
+            cell.load_foreign(&WITH_STATE, &NAMESPACE),
+            Ok(ForeignState::KeyMissing)
+        ));
+    }
+
+    #[cfg(feature = "api-v2")]
+    #[test]
+    fn foreign_writes_need_a_grant() {
+        let cell = cell(1);
+
+        assert!(matches!(
+            cell.store_foreign(&WITH_STATE, &NAMESPACE, &42),
+            Err(Error::NotAuthorized)
+        ));
+        assert!(matches!(
+            cell.remove_foreign(&WITH_STATE, &NAMESPACE),
+            Err(Error::NotAuthorized)
+        ));
+        assert!(testing::host(|host| host.state.is_empty()));
+
+        testing::host(|host| host.grants.insert(WITH_STATE.to_vec()));
+
+        assert!(cell.store_foreign(&WITH_STATE, &NAMESPACE, &42).is_ok());
+        assert!(matches!(
+            cell.load_foreign(&WITH_STATE, &NAMESPACE),
+            Ok(ForeignState::Value(42))
+        ));
+        // the other account didn't grant
+        assert!(matches!(
+            cell.store_foreign(&WITHOUT_STATE, &NAMESPACE, &42),
+            Err(Error::NotAuthorized)
+        ));
+
+        assert!(cell.remove_foreign(&WITH_STATE, &NAMESPACE).is_ok());
+        assert!(testing::host(|host| host.state.is_empty()));
+    }
+
+    #[cfg(feature = "api-v2")]
+    #[test]
+    fn foreign_map_writes() {
+        foreign_ledger();
+
+        let prices: StateMap<Prices, CurrencyCode, u64> = StateMap::new();
+        let usd = [b'U'; CURRENCY_CODE_SIZE];
+
+        assert!(matches!(
+            prices.set_foreign(&WITH_STATE, &NAMESPACE, &usd, &7),
+            Err(Error::NotAuthorized)
+        ));
+
+        testing::host(|host| host.grants.insert(WITH_STATE.to_vec()));
+
+        assert!(prices
+            .set_foreign(&WITH_STATE, &NAMESPACE, &usd, &7)
+            .is_ok());
+        assert!(matches!(
+            prices.get_foreign(&WITH_STATE, &NAMESPACE, &usd),
+            Ok(ForeignState::Value(7))
+        ));
+
+        assert!(prices.remove_foreign(&WITH_STATE, &NAMESPACE, &usd).is_ok());
+        assert!(matches!(
+            prices.get_foreign(&WITH_STATE, &NAMESPACE, &usd),
+            Ok(ForeignState::KeyMissing)
+        ));
+    }
+
+    #[test]
+    fn foreign_map_reads() {
+        foreign_ledger();
+
+        let prices: StateMap<Prices, CurrencyCode, u64> = StateMap::new();
+        let keys = [[b'U'; CURRENCY_CODE_SIZE], [b'E'; CURRENCY_CODE_SIZE]];
+
+        let present = match prices.entry(&keys[0]) {
+            Ok(cell) => *cell.key(),
+            Err(_) => panic!("key too long"),
+        };
+        testing::host(|host| {
+            host.set_state(&WITH_STATE, &NAMESPACE, &present, &7u64.to_be_bytes())
+        });
+
+        let mut out = [ForeignState::KeyMissing; 2];
+
+        #[cfg(feature = "api-v2")]
+        let res = prices.get_foreign_batch::<1>(&WITH_STATE, &NAMESPACE, &keys, &mut out);
+        #[cfg(not(feature = "api-v2"))]
+        let res = prices.get_foreign_batch::<1>(&WITH_STATE, &keys, &mut out);
+
+        assert!(res.is_ok());
+        assert_eq!(out, [ForeignState::Value(7), ForeignState::KeyMissing]);
+
+        #[cfg(feature = "api-v2")]
+        let res = prices.get_foreign_batch::<2>(&WITHOUT_STATE, &NAMESPACE, &keys, &mut out);
+        #[cfg(not(feature = "api-v2"))]
+        let res = prices.get_foreign_batch::<2>(&WITHOUT_STATE, &keys, &mut out);
+
+        assert!(res.is_ok());
+        assert_eq!(out, [ForeignState::NoHookState; 2]);
+        assert_eq!(testing::guard_calls(), 6);
+        assert!(!testing::guard_violated());
+
+        #[cfg(feature = "api-v2")]
+        let res = prices.get_foreign(&WITH_STATE, &NAMESPACE, &keys[1]);
+        #[cfg(not(feature = "api-v2"))]
+        let res = prices.get_foreign(&WITH_STATE, &keys[1]);
+
+        assert!(matches!(res, Ok(ForeignState::KeyMissing)));
+    }
+
+    #[test]
+    fn invalid_bool() {
+        assert!(bool::decode(&mut BufReader::new(&[2])).is_err());
+    }
+
+    #[test]
+    fn key_layout() {
+        let cell = StateCell::<u32, u64>::new(b"NS", &0x0102_0304);
+
+        assert!(matches!(
+            key_of(cell),
+            Some([
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3,
+                4, b'N', b'S', 2
+            ])
+        ));
+    }
+
+    #[test]
+    fn namespaces_dont_collide() {
+        let a = key_of(StateCell::<u16, u8>::new(b"A", &0x4201));
+        let b = key_of(StateCell::<u8, u8>::new(b"BA", &0x42));
+        let c = key_of(StateCell::<[u8; 3], u8>::new(b"", &[0x42, b'A', 1]));
+
+        assert!(a.is_some() && b.is_some() && c.is_some());
+        assert_ne!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(b, c);
+    }
+
+    #[derive(xrpl_hooks_derive::HookState, PartialEq, Debug)]
+    struct Position {
+        owner: AccountId,
+        drops: u64,
+        active: bool,
+    }
+
+    #[derive(xrpl_hooks_derive::HookState, PartialEq, Debug)]
+    struct Counter(u32, i16);
+
+    #[derive(xrpl_hooks_derive::HookState, PartialEq, Debug)]
+    enum Order {
+        Empty,
+        Limit { price: u64, quantity: u32 },
+        Market(u16),
+    }
+
+    #[test]
+    fn derived_struct() {
+        let position = Position {
+            owner: [1; ACC_ID_LEN],
+            drops: 0x0102_0304_0506_0708,
+            active: true,
+        };
+
+        assert_eq!(Position::SIZE, 29);
+
+        let bytes = match position.to_bytes() {
+            Ok(bytes) => bytes,
+            Err(_) => panic!("encode failed"),
+        };
+        assert_eq!(&bytes[..20], &[1; ACC_ID_LEN]);
+        assert_eq!(&bytes[20..], &[1, 2, 3, 4, 5, 6, 7, 8, 1]);
+
+        assert!(matches!(Position::from_bytes(&bytes), Ok(p) if p == position));
+        assert!(Position::from_bytes(&bytes[..28]).is_err());
+    }
+
+    #[test]
+    fn derived_tuple_struct() {
+        assert_eq!(Counter::SIZE, 6);
+        assert!(matches!(
+            Counter(7, -1).to_bytes(),
+            Ok([0, 0, 0, 7, 0xFF, 0xFF])
+        ));
+        assert!(matches!(roundtrip(&Counter(7, -1)), Ok(Counter(7, -1))));
+    }
+
+    #[test]
+    fn derived_enum() {
+        assert_eq!(Order::SIZE, 13);
+        assert!(matches!(
+            Order::Market(0x0102).to_bytes(),
+            Ok([2, 1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0])
+        ));
+
+        let limit = Order::Limit {
+            price: 5,
+            quantity: 6,
+        };
+        assert!(matches!(roundtrip(&limit), Ok(order) if order == limit));
+        assert!(matches!(roundtrip(&Order::Empty), Ok(Order::Empty)));
+        assert!(Order::from_bytes(&[3; 13]).is_err());
+    }
+
+    #[test]
+    fn derived_enum_padding() {
+        let mut buf = [0xFF; 13];
+
+        match Order::Market(0x0102).encode(&mut BufWriter::new(&mut buf)) {
+            Ok(_) => {}
+            Err(_) => panic!("encode failed"),
+        }
+        assert_eq!(buf, [2, 1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+        let mut buf = [0xFF; 13];
+
+        match Order::Empty.encode(&mut BufWriter::new(&mut buf)) {
+            Ok(_) => {}
+            Err(_) => panic!("encode failed"),
+        }
+        assert_eq!(buf, [0; 13]);
+    }
+
+    #[test]
+    fn key_too_big() {
+        assert!(StateCell::<Hash, u8>::new(b"", &[0; HASH_LEN]).is_err());
+        assert!(StateCell::<AccountId, u8>::new(b"NAMESPACE12", &[0; ACC_ID_LEN]).is_ok());
+        assert!(StateCell::<AccountId, u8>::new(b"NAMESPACE123", &[0; ACC_ID_LEN]).is_err());
+    }
+}
@@ -0,0 +1,1308 @@
+//! Test host
+//!
+//! Native stand-ins for the host functions,
+//! so the wrappers and helpers can be unit-tested with `cargo test`.
+//!
+//! Every test runs on its own thread and gets a fresh [Host], set it up with [host].
+//!
+//! Under `cfg(test)` the bindings take pointers as [_c::Ptr], pointer-width,
+//! so the stubs get the native addresses of the buffers handed to the host.
+
+extern crate std;
+
+use std::{
+    cell::RefCell,
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash as _, Hasher},
+    vec::Vec,
+};
+
+use crate::{
+    _c::{self, Ptr},
+    api::{self, Error, Keylet, KeyletType, KEYLET_LEN},
+};
+
+std::thread_local! {
+    static GUARDS: RefCell<HashMap<u32, u32>> = RefCell::new(HashMap::new());
+    static GUARD_VIOLATED: RefCell<bool> = const { RefCell::new(false) };
+    static HOST: RefCell<Host> = RefCell::new(Host::default());
+}
+
+/// Host `_g`: counts the calls per guard id and records a guard violation
+///
+/// Can't panic here, unwinding out of an `extern "C"` function aborts.
+#[no_mangle]
+extern "C" fn _g(id: u32, maxiter: u32) -> i32 {
+    let calls = GUARDS.with(|guards| {
+        let mut guards = guards.borrow_mut();
+        let calls = guards.entry(id).or_insert(0);
+        *calls += 1;
+        *calls
+    });
+
+    if calls > maxiter {
+        GUARD_VIOLATED.with(|violated| *violated.borrow_mut() = true);
+    }
+
+    1
+}
+
+/// Total number of `_g` calls made by the current test
+pub(crate) fn guard_calls() -> u32 {
+    GUARDS.with(|guards| guards.borrow().values().sum())
+}
+
+/// Number of `_g` calls of the guard id made by the current test
+pub(crate) fn guard_calls_of(id: u32) -> u32 {
+    GUARDS.with(|guards| guards.borrow().get(&id).copied().unwrap_or(0))
+}
+
+/// Whether any guard of the current test was violated
+pub(crate) fn guard_violated() -> bool {
+    GUARD_VIOLATED.with(|violated| *violated.borrow())
+}
+
+/// State of the test host
+#[derive(Default)]
+pub(crate) struct Host {
+    /// Account the hook is installed on
+    pub(crate) hook_account: [u8; 20],
+    /// Ledger objects by keylet
+    pub(crate) ledger: HashMap<Vec<u8>, Vec<u8>>,
+    /// Serialized content of the slots, slot `n` at `n - 1`
+    pub(crate) slots: Vec<Option<Vec<u8>>>,
+    /// Hook state by account, namespace and key
+    pub(crate) state: HashMap<StateKey, Vec<u8>>,
+    /// Accounts which granted this hook foreign state writes
+    #[cfg(feature = "api-v2")]
+    pub(crate) grants: std::collections::HashSet<Vec<u8>>,
+    /// Serialized originating transaction
+    pub(crate) otxn: Vec<u8>,
+    /// Parameters of the hook by name
+    #[cfg(feature = "api-v2")]
+    pub(crate) hook_params: HashMap<Vec<u8>, Vec<u8>>,
+    /// `HookParameters` of the originating transaction by name
+    #[cfg(feature = "api-v2")]
+    pub(crate) otxn_params: HashMap<Vec<u8>, Vec<u8>>,
+    /// `hook_param_set` calls: hook hash, name and value
+    #[cfg(feature = "api-v2")]
+    pub(crate) param_sets: Vec<(Vec<u8>, Vec<u8>, Vec<u8>)>,
+    /// `hook_skip` calls: hook hash and flags
+    #[cfg(feature = "api-v2")]
+    pub(crate) skips: Vec<(Vec<u8>, u32)>,
+    /// Position of the hook in the chain
+    #[cfg(feature = "api-v2")]
+    pub(crate) hook_pos: i64,
+    /// Whether `hook_again` was called
+    #[cfg(feature = "api-v2")]
+    pub(crate) again: bool,
+    /// Close time of the last closed ledger
+    #[cfg(feature = "api-v2")]
+    pub(crate) ledger_last_time: i64,
+    /// Number of nonces handed out by `ledger_nonce` and `etxn_nonce`
+    #[cfg(feature = "api-v2")]
+    pub(crate) nonces: u32,
+    /// Metadata of the originating transaction, for `meta_slot`
+    #[cfg(feature = "api-v2")]
+    pub(crate) meta: Option<Vec<u8>>,
+    /// Transaction and metadata of an `Import` XPOP, for `xpop_slot`
+    #[cfg(feature = "xahau")]
+    pub(crate) xpop: Option<(Vec<u8>, Vec<u8>)>,
+    /// Whether the hook has a `cbak`, so `etxn_details` includes an `EmitCallback`
+    #[cfg(feature = "api-v2")]
+    pub(crate) has_callback: bool,
+    /// Emissions reserved with `etxn_reserve`
+    pub(crate) reserved: Option<u32>,
+    /// Error `emit` fails with
+    pub(crate) emit_error: Option<Error>,
+    /// Emitted transactions
+    pub(crate) emitted: Vec<Vec<u8>>,
+    /// Sequence of the ledger being built
+    pub(crate) ledger_seq: i64,
+    /// Transactions, or their lengths, `etxn_fee_base` was called with
+    pub(crate) fee_txns: Vec<Vec<u8>>,
+}
+
+/// Runs `f` on the host of the current test
+pub(crate) fn host<R>(f: impl FnOnce(&mut Host) -> R) -> R {
+    HOST.with(|host| f(&mut host.borrow_mut()))
+}
+
+const DOESNT_EXIST: i64 = _c::DOESNT_EXIST as i64;
+const INVALID_ARGUMENT: i64 = _c::INVALID_ARGUMENT as i64;
+const NO_FREE_SLOTS: i64 = _c::NO_FREE_SLOTS as i64;
+const PARSE_ERROR: i64 = _c::PARSE_ERROR as i64;
+const TOO_SMALL: i64 = _c::TOO_SMALL as i64;
+#[cfg(feature = "api-v2")]
+const NOT_AUTHORIZED: i64 = -34;
+const NOT_AN_AMOUNT: i64 = -32;
+
+/// Maximum number of slots
+const SLOTS: usize = 255;
+
+/// Memory the hook handed to the host
+fn read<'a>(ptr: Ptr, len: u32) -> &'a [u8] {
+    if len == 0 {
+        return &[];
+    }
+
+    unsafe { std::slice::from_raw_parts(ptr as *const u8, len as usize) }
+}
+
+/// Writes `data` to the hook's buffer, [TOO_SMALL] if it doesn't fit
+fn write(ptr: Ptr, len: u32, data: &[u8]) -> i64 {
+    if data.len() > len as usize {
+        return TOO_SMALL;
+    }
+
+    if !data.is_empty() {
+        let out = unsafe { std::slice::from_raw_parts_mut(ptr as *mut u8, data.len()) };
+        out.copy_from_slice(data);
+    }
+
+    data.len() as i64
+}
+
+/// Deterministic 32 byte digest standing for the hashes of the host
+pub(crate) fn digest(parts: &[&[u8]]) -> [u8; 32] {
+    let mut out = [0; 32];
+
+    for (i, chunk) in out.chunks_mut(8).enumerate() {
+        let mut hasher = DefaultHasher::new();
+        i.hash(&mut hasher);
+        parts.hash(&mut hasher);
+        chunk.copy_from_slice(&hasher.finish().to_be_bytes());
+    }
+
+    out
+}
+
+/// Location of a field in a serialized object
+struct Field {
+    id: u32,
+    /// Start of the header
+    start: usize,
+    /// Start of the payload, after the VL prefix
+    payload: usize,
+    /// Length of the payload, without the end marker of an object or an array
+    len: usize,
+    /// End of the field, after the end marker
+    end: usize,
+}
+
+const OBJECT_END: u8 = 0xE1;
+const ARRAY_END: u8 = 0xF1;
+
+/// Parses the field at `pos`, `None` on malformed data
+fn parse_field(sto: &[u8], pos: usize) -> Option<Field> {
+    let first = *sto.get(pos)?;
+    let (ty, field, header) = match (first >> 4, first & 0x0F) {
+        (0, 0) => (*sto.get(pos + 1)? as u32, *sto.get(pos + 2)? as u32, 3),
+        (0, field) => (*sto.get(pos + 1)? as u32, field as u32, 2),
+        (ty, 0) => (ty as u32, *sto.get(pos + 1)? as u32, 2),
+        (ty, field) => (ty as u32, field as u32, 1),
+    };
+
+    let start = pos;
+    let pos = pos + header;
+
+    let fixed = |len: usize| Some((pos, len, pos + len));
+    let (payload, len, end) = match ty {
+        // UInt16, UInt32, UInt64, Hash128, Hash256
+        1 => fixed(2)?,
+        2 => fixed(4)?,
+        3 => fixed(8)?,
+        4 => fixed(16)?,
+        5 => fixed(32)?,
+        // Amount
+        6 if *sto.get(pos)? & 0x80 == 0 => fixed(8)?,
+        6 => fixed(48)?,
+        // Blob, AccountID, Vector256
+        7 | 8 | 19 => {
+            let (prefix, len) = vl_length(sto, pos)?;
+            (pos + prefix, len, pos + prefix + len)
+        }
+        // Object and Array, up to their end marker
+        14 | 15 => {
+            let marker = if ty == 14 { OBJECT_END } else { ARRAY_END };
+            let mut inner = pos;
+
+            while *sto.get(inner)? != marker {
+                inner = parse_field(sto, inner)?.end;
+            }
+
+            (pos, inner - pos, inner + 1)
+        }
+        // UInt8, Hash160
+        16 => fixed(1)?,
+        17 => fixed(20)?,
+        _ => return None,
+    };
+
+    if end > sto.len() {
+        return None;
+    }
+
+    Some(Field {
+        id: (ty << 16) | field,
+        start,
+        payload,
+        len,
+        end,
+    })
+}
+
+fn vl_length(sto: &[u8], pos: usize) -> Option<(usize, usize)> {
+    let b0 = *sto.get(pos)? as usize;
+
+    match b0 {
+        0..=192 => Some((1, b0)),
+        193..=240 => Some((2, 193 + (b0 - 193) * 256 + *sto.get(pos + 1)? as usize)),
+        241..=254 => Some((
+            3,
+            12481
+                + (b0 - 241) * 65536
+                + *sto.get(pos + 1)? as usize * 256
+                + *sto.get(pos + 2)? as usize,
+        )),
+        _ => None,
+    }
+}
+
+/// The payload of the field, `Err` with the host error code
+fn find_field(sto: &[u8], field_id: u32) -> Result<Field, i64> {
+    let mut pos = 0;
+
+    while pos < sto.len() && sto[pos] != OBJECT_END && sto[pos] != ARRAY_END {
+        let field = match parse_field(sto, pos) {
+            Some(field) => field,
+            None => return Err(PARSE_ERROR),
+        };
+
+        if field.id == field_id {
+            return Ok(field);
+        }
+
+        pos = field.end;
+    }
+
+    Err(DOESNT_EXIST)
+}
+
+/// The element of an array payload, header and end marker included
+fn find_element(sto: &[u8], index: u32) -> Result<Field, i64> {
+    let mut pos = 0;
+    let mut i = 0;
+
+    while pos < sto.len() && sto[pos] != ARRAY_END {
+        let field = match parse_field(sto, pos) {
+            Some(field) => field,
+            None => return Err(PARSE_ERROR),
+        };
+
+        if i == index {
+            return Ok(field);
+        }
+
+        pos = field.end;
+        i += 1;
+    }
+
+    Err(DOESNT_EXIST)
+}
+
+fn location(start: usize, len: usize) -> i64 {
+    ((start as i64) << 32) | len as i64
+}
+
+#[no_mangle]
+extern "C" fn sto_subfield(read_ptr: Ptr, read_len: u32, field_id: u32) -> i64 {
+    match find_field(read(read_ptr, read_len), field_id) {
+        Ok(field) => location(field.payload, field.len),
+        Err(e) => e,
+    }
+}
+
+#[no_mangle]
+extern "C" fn sto_subarray(read_ptr: Ptr, read_len: u32, array_id: u32) -> i64 {
+    let sto = read(read_ptr, read_len);
+
+    // the host unwraps an array passed with its header, like a serialized `Memos` field
+    let offset = match parse_field(sto, 0) {
+        Some(field) if field.id >> 16 == 15 && field.end == sto.len() => field.payload,
+        _ => 0,
+    };
+
+    match find_element(&sto[offset..], array_id) {
+        Ok(element) => location(offset + element.start, element.end - element.start),
+        Err(e) => e,
+    }
+}
+
+/// Serialized object builder for the tests
+#[derive(Default)]
+pub(crate) struct Sto(pub(crate) Vec<u8>);
+
+impl Sto {
+    pub(crate) fn new() -> Self {
+        Sto::default()
+    }
+
+    fn header(mut self, field_id: u32) -> Self {
+        let (ty, field) = ((field_id >> 16) as u8, field_id as u8);
+
+        match (ty < 16, field < 16) {
+            (true, true) => self.0.push(ty << 4 | field),
+            (true, false) => self.0.extend_from_slice(&[ty << 4, field]),
+            (false, true) => self.0.extend_from_slice(&[field, ty]),
+            (false, false) => self.0.extend_from_slice(&[0, ty, field]),
+        }
+
+        self
+    }
+
+    fn vl(mut self, len: usize) -> Self {
+        match len {
+            0..=192 => self.0.push(len as u8),
+            193..=12480 => {
+                let len = len - 193;
+                self.0
+                    .extend_from_slice(&[193 + (len >> 8) as u8, len as u8]);
+            }
+            _ => {
+                let len = len - 12481;
+                self.0
+                    .extend_from_slice(&[241 + (len >> 16) as u8, (len >> 8) as u8, len as u8]);
+            }
+        }
+
+        self
+    }
+
+    /// A fixed-size field, the payload as is
+    pub(crate) fn fixed(mut self, field_id: u32, payload: &[u8]) -> Self {
+        self = self.header(field_id);
+        self.0.extend_from_slice(payload);
+        self
+    }
+
+    pub(crate) fn u16(self, field_id: u32, value: u16) -> Self {
+        self.fixed(field_id, &value.to_be_bytes())
+    }
+
+    pub(crate) fn u32(self, field_id: u32, value: u32) -> Self {
+        self.fixed(field_id, &value.to_be_bytes())
+    }
+
+    pub(crate) fn u64(self, field_id: u32, value: u64) -> Self {
+        self.fixed(field_id, &value.to_be_bytes())
+    }
+
+    /// An XRP amount
+    pub(crate) fn drops(self, field_id: u32, drops: u64) -> Self {
+        self.u64(field_id, drops | 0x4000_0000_0000_0000)
+    }
+
+    /// An issued currency amount from the raw XFL value
+    pub(crate) fn iou(self, field_id: u32, xfl: i64, currency: &[u8], issuer: &[u8]) -> Self {
+        let mut payload = Vec::new();
+        payload.extend_from_slice(&(xfl as u64 | 0x8000_0000_0000_0000).to_be_bytes());
+        payload.extend_from_slice(currency);
+        payload.extend_from_slice(issuer);
+
+        self.fixed(field_id, &payload)
+    }
+
+    /// A VL encoded field: Blob, AccountID or Vector256
+    pub(crate) fn vl_field(mut self, field_id: u32, payload: &[u8]) -> Self {
+        self = self.header(field_id).vl(payload.len());
+        self.0.extend_from_slice(payload);
+        self
+    }
+
+    pub(crate) fn build(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+/// Ledger entry type of the keylets, the first two bytes
+fn keylet_entry_type(keylet_type: u32) -> u16 {
+    match keylet_type {
+        _c::KEYLET_HOOK => 0x0048,
+        _c::KEYLET_HOOK_STATE => 0x0076,
+        _c::KEYLET_ACCOUNT => 0x0061,
+        _c::KEYLET_AMENDMENTS => 0x0066,
+        _c::KEYLET_FEES => 0x0073,
+        _c::KEYLET_LINE => 0x0072,
+        _c::KEYLET_OFFER => 0x006F,
+        _c::KEYLET_QUALITY | _c::KEYLET_OWNER_DIR | _c::KEYLET_PAGE => 0x0064,
+        _c::KEYLET_SIGNERS => 0x0053,
+        _c::KEYLET_CHECK => 0x0043,
+        _c::KEYLET_ESCROW => 0x0075,
+        _c::KEYLET_PAYCHAN => 0x0078,
+        _ => 0,
+    }
+}
+
+/// Number of leading `(ptr, len)` argument pairs of the keylet type
+fn keylet_buffers(keylet_type: u32) -> usize {
+    match keylet_type {
+        _c::KEYLET_LINE => 3,
+        #[cfg(feature = "api-v2")]
+        _c::KEYLET_HOOK_STATE => 3,
+        #[cfg(not(feature = "api-v2"))]
+        _c::KEYLET_HOOK_STATE => 2,
+        _c::KEYLET_DEPOSIT_PREAUTH | _c::KEYLET_PAYCHAN => 2,
+        _c::KEYLET_AMENDMENTS
+        | _c::KEYLET_SKIP
+        | _c::KEYLET_FEES
+        | _c::KEYLET_NEGATIVE_UNL
+        | _c::KEYLET_EMITTED_DIR => 0,
+        #[cfg(feature = "api-v2")]
+        _c::KEYLET_HOOK_STATE_DIR => 2,
+        _ => 1,
+    }
+}
+
+/// Host `util_keylet`
+///
+/// `Unchecked`, `Page` 0 and `Quality` keylets are built as on the host,
+/// the others hash their arguments with [digest].
+#[no_mangle]
+extern "C" fn util_keylet(
+    write_ptr: Ptr,
+    write_len: u32,
+    keylet_type: u32,
+    a: Ptr,
+    b: Ptr,
+    c: Ptr,
+    d: Ptr,
+    e: Ptr,
+    f: Ptr,
+) -> i64 {
+    let args = [a, b, c, d, e, f];
+    let mut keylet = Vec::with_capacity(34);
+    keylet.extend_from_slice(&keylet_entry_type(keylet_type).to_be_bytes());
+
+    match keylet_type {
+        _c::KEYLET_UNCHECKED => {
+            let key = read(a, b as u32);
+            if key.len() != 32 {
+                return INVALID_ARGUMENT;
+            }
+            keylet.extend_from_slice(key);
+        }
+        _c::KEYLET_PAGE if c == 0 && d == 0 => {
+            let root = read(a, b as u32);
+            if root.len() != 32 {
+                return INVALID_ARGUMENT;
+            }
+            keylet.extend_from_slice(root);
+        }
+        _c::KEYLET_QUALITY => {
+            let base = read(a, b as u32);
+            if base.len() != 34 {
+                return INVALID_ARGUMENT;
+            }
+            keylet.extend_from_slice(&base[2..26]);
+            keylet.extend_from_slice(&(c as u32).to_be_bytes());
+            keylet.extend_from_slice(&(d as u32).to_be_bytes());
+        }
+        _ => {
+            let buffers = keylet_buffers(keylet_type);
+            let mut parts: Vec<&[u8]> = Vec::new();
+            let type_bytes = keylet_type.to_be_bytes();
+            parts.push(&type_bytes);
+
+            for pair in args[..buffers * 2].chunks(2) {
+                parts.push(read(pair[0], pair[1] as u32));
+            }
+
+            let mut ints = Vec::new();
+            for arg in &args[buffers * 2..] {
+                ints.extend_from_slice(&(*arg as u32).to_be_bytes());
+            }
+            parts.push(&ints);
+
+            keylet.extend_from_slice(&digest(&parts));
+        }
+    }
+
+    write(write_ptr, write_len, &keylet)
+}
+
+#[no_mangle]
+extern "C" fn util_sha512h(write_ptr: Ptr, write_len: u32, read_ptr: Ptr, read_len: u32) -> i64 {
+    write(write_ptr, write_len, &digest(&[read(read_ptr, read_len)]))
+}
+
+/// Host `otxn_field`: the payload of the field, as [sto_subfield] locates it
+#[no_mangle]
+extern "C" fn otxn_field(write_ptr: Ptr, write_len: u32, field_id: u32) -> i64 {
+    let field = host(|host| match find_field(&host.otxn, field_id) {
+        Ok(field) => Ok(host.otxn[field.payload..field.payload + field.len].to_vec()),
+        Err(e) => Err(e),
+    });
+
+    match field {
+        Ok(field) => write(write_ptr, write_len, &field),
+        Err(e) => e,
+    }
+}
+
+#[cfg(not(feature = "api-v2"))]
+#[no_mangle]
+extern "C" fn otxn_id(write_ptr: Ptr, write_len: u32) -> i64 {
+    let id = host(|host| digest(&[&host.otxn]));
+
+    write(write_ptr, write_len, &id)
+}
+
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn otxn_id(write_ptr: Ptr, write_len: u32, flags: u32) -> i64 {
+    if flags > 1 {
+        return INVALID_ARGUMENT;
+    }
+
+    let id = host(|host| digest(&[&host.otxn]));
+
+    write(write_ptr, write_len, &id)
+}
+
+/// Host `otxn_type`: the `TransactionType` field, [DOESNT_EXIST] without one
+#[no_mangle]
+extern "C" fn otxn_type() -> i64 {
+    host(|host| match find_field(&host.otxn, _c::sfTransactionType) {
+        Ok(field) => {
+            u16::from_be_bytes([host.otxn[field.payload], host.otxn[field.payload + 1]]) as i64
+        }
+        Err(e) => e,
+    })
+}
+
+const TOO_BIG: i64 = _c::TOO_BIG as i64;
+
+/// Parameter names are 1 to 32 bytes long
+#[cfg(feature = "api-v2")]
+fn check_param_name(name: &[u8]) -> i64 {
+    match name.len() {
+        0 => TOO_SMALL,
+        1..=32 => 0,
+        _ => TOO_BIG,
+    }
+}
+
+#[cfg(feature = "api-v2")]
+fn param(
+    params: impl FnOnce(&Host) -> &HashMap<Vec<u8>, Vec<u8>>,
+    write_ptr: Ptr,
+    write_len: u32,
+    read_ptr: Ptr,
+    read_len: u32,
+) -> i64 {
+    let name = read(read_ptr, read_len);
+    let res = check_param_name(name);
+    if res < 0 {
+        return res;
+    }
+
+    match host(|host| params(host).get(name).cloned()) {
+        Some(value) => write(write_ptr, write_len, &value),
+        None => DOESNT_EXIST,
+    }
+}
+
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn hook_param(write_ptr: Ptr, write_len: u32, read_ptr: Ptr, read_len: u32) -> i64 {
+    param(
+        |host| &host.hook_params,
+        write_ptr,
+        write_len,
+        read_ptr,
+        read_len,
+    )
+}
+
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn otxn_param(write_ptr: Ptr, write_len: u32, read_ptr: Ptr, read_len: u32) -> i64 {
+    param(
+        |host| &host.otxn_params,
+        write_ptr,
+        write_len,
+        read_ptr,
+        read_len,
+    )
+}
+
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn hook_param_set(
+    read_ptr: Ptr,
+    read_len: u32,
+    kread_ptr: Ptr,
+    kread_len: u32,
+    hread_ptr: Ptr,
+    hread_len: u32,
+) -> i64 {
+    let value = read(read_ptr, read_len);
+    let name = read(kread_ptr, kread_len);
+    let hook_hash = read(hread_ptr, hread_len);
+
+    let res = check_param_name(name);
+    if res < 0 {
+        return res;
+    }
+    if value.len() > 256 {
+        return TOO_BIG;
+    }
+    if hook_hash.len() != 32 {
+        return INVALID_ARGUMENT;
+    }
+
+    host(|host| {
+        host.param_sets
+            .push((hook_hash.to_vec(), name.to_vec(), value.to_vec()))
+    });
+
+    value.len() as i64
+}
+
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn hook_skip(read_ptr: Ptr, read_len: u32, flags: u32) -> i64 {
+    let hook_hash = read(read_ptr, read_len);
+
+    if hook_hash.len() != 32 || flags > 1 {
+        return INVALID_ARGUMENT;
+    }
+
+    host(|host| host.skips.push((hook_hash.to_vec(), flags)));
+
+    1
+}
+
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn hook_again() -> i64 {
+    host(|host| {
+        if host.again {
+            return _c::ALREADY_SET as i64;
+        }
+
+        host.again = true;
+        1
+    })
+}
+
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn hook_pos() -> i64 {
+    host(|host| host.hook_pos)
+}
+
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn ledger_last_time() -> i64 {
+    host(|host| host.ledger_last_time)
+}
+
+/// A new nonce, different on every call
+#[cfg(feature = "api-v2")]
+fn nonce(kind: &[u8], write_ptr: Ptr, write_len: u32) -> i64 {
+    let nonce = host(|host| {
+        host.nonces += 1;
+        digest(&[kind, &host.nonces.to_be_bytes()])
+    });
+
+    write(write_ptr, write_len, &nonce)
+}
+
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn ledger_nonce(write_ptr: Ptr, write_len: u32) -> i64 {
+    nonce(b"ledger", write_ptr, write_len)
+}
+
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn etxn_nonce(write_ptr: Ptr, write_len: u32) -> i64 {
+    nonce(b"etxn", write_ptr, write_len)
+}
+
+/// Host `ledger_keylet`: the smallest keylet in the ledger between the bounds, both included
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn ledger_keylet(
+    write_ptr: Ptr,
+    write_len: u32,
+    lread_ptr: Ptr,
+    lread_len: u32,
+    hread_ptr: Ptr,
+    hread_len: u32,
+) -> i64 {
+    let low = read(lread_ptr, lread_len);
+    let high = read(hread_ptr, hread_len);
+
+    if low.len() != 34 || high.len() != 34 || low[..2] != high[..2] {
+        return INVALID_ARGUMENT;
+    }
+
+    let found = host(|host| {
+        host.ledger
+            .keys()
+            .filter(|keylet| keylet.as_slice() >= low && keylet.as_slice() <= high)
+            .min()
+            .cloned()
+    });
+
+    match found {
+        Some(keylet) => write(write_ptr, write_len, &keylet),
+        None => DOESNT_EXIST,
+    }
+}
+
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn meta_slot(slot_no: u32) -> i64 {
+    host(|host| match host.meta.clone() {
+        Some(meta) => host.fill_slot(slot_no, meta),
+        None => _c::PREREQUISITE_NOT_MET as i64,
+    })
+}
+
+/// Host `xpop_slot`: the slot numbers packed as `tx << 16 | meta`
+#[cfg(feature = "xahau")]
+#[no_mangle]
+extern "C" fn xpop_slot(slot_tx: u32, slot_meta: u32) -> i64 {
+    host(|host| {
+        let (tx, meta) = match host.xpop.clone() {
+            Some(xpop) => xpop,
+            None => return _c::PREREQUISITE_NOT_MET as i64,
+        };
+
+        let tx_slot = host.fill_slot(slot_tx, tx);
+        if tx_slot < 0 {
+            return tx_slot;
+        }
+
+        let meta_slot = host.fill_slot(slot_meta, meta);
+        if meta_slot < 0 {
+            return meta_slot;
+        }
+
+        (tx_slot << 16) | meta_slot
+    })
+}
+
+#[no_mangle]
+extern "C" fn etxn_reserve(count: u32) -> i64 {
+    host(|host| {
+        if host.reserved.is_some() {
+            return _c::ALREADY_SET as i64;
+        }
+        if count > 255 {
+            return TOO_BIG;
+        }
+
+        host.reserved = Some(count);
+        count as i64
+    })
+}
+
+/// Host `emit`: records the transaction and writes its digest as the hash
+#[no_mangle]
+extern "C" fn emit(write_ptr: Ptr, write_len: u32, read_ptr: Ptr, read_len: u32) -> i64 {
+    let txn = read(read_ptr, read_len);
+
+    let res = host(|host| {
+        if let Some(e) = host.emit_error {
+            return e.code() as i64;
+        }
+
+        match host.reserved {
+            None => return _c::PREREQUISITE_NOT_MET as i64,
+            Some(reserved) if host.emitted.len() >= reserved as usize => {
+                return _c::TOO_MANY_EMITTED_TXN as i64
+            }
+            _ => {}
+        }
+
+        host.emitted.push(txn.to_vec());
+        0
+    });
+
+    if res < 0 {
+        return res;
+    }
+
+    write(write_ptr, write_len, &digest(&[txn]))
+}
+
+#[no_mangle]
+extern "C" fn ledger_seq() -> i64 {
+    host(|host| host.ledger_seq)
+}
+
+/// Host `etxn_details`: an `EmitDetails` object with a callback, 105 bytes
+#[cfg(not(feature = "api-v2"))]
+#[no_mangle]
+extern "C" fn etxn_details(write_ptr: Ptr, write_len: u32) -> i64 {
+    let mut details = Vec::with_capacity(105);
+    details.push(0xEC);
+    // EmitGeneration, EmitBurden
+    details.extend_from_slice(&[0x20, 0x2B, 0, 0, 0, 1, 0x3C, 0, 0, 0, 0, 0, 0, 0, 1]);
+    // EmitParentTxnID, EmitNonce
+    details.push(0x5A);
+    details.extend_from_slice(&digest(&[b"parent"]));
+    details.push(0x5B);
+    details.extend_from_slice(&digest(&[b"nonce"]));
+    // EmitCallback
+    details.extend_from_slice(&[0x85, 0x14]);
+    details.extend_from_slice(&host(|host| host.hook_account));
+    details.push(OBJECT_END);
+
+    write(write_ptr, write_len, &details)
+}
+
+/// Host `etxn_details`: an `EmitDetails` object, 138 bytes with a callback and 116 without
+///
+/// Like the host, fails unless the buffer fits the whole object.
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn etxn_details(write_ptr: Ptr, write_len: u32) -> i64 {
+    let (callback, account) = host(|host| (host.has_callback, host.hook_account));
+
+    let mut details = Vec::with_capacity(138);
+    details.push(0xED);
+    // EmitGeneration, EmitBurden
+    details.extend_from_slice(&[0x20, 0x2E, 0, 0, 0, 1, 0x3D, 0, 0, 0, 0, 0, 0, 0, 1]);
+    // EmitParentTxnID, EmitNonce, EmitHookHash
+    details.push(0x5B);
+    details.extend_from_slice(&digest(&[b"parent"]));
+    details.push(0x5C);
+    details.extend_from_slice(&digest(&[b"nonce"]));
+    details.push(0x5D);
+    details.extend_from_slice(&digest(&[b"hook"]));
+    if callback {
+        // EmitCallback
+        details.extend_from_slice(&[0x8A, 0x14]);
+        details.extend_from_slice(&account);
+    }
+    details.push(OBJECT_END);
+
+    write(write_ptr, write_len, &details)
+}
+
+/// Base fee of the stub: 10 drops plus a drop per byte
+#[cfg(not(feature = "api-v2"))]
+#[no_mangle]
+extern "C" fn etxn_fee_base(tx_byte_count: u32) -> i64 {
+    host(|host| host.fee_txns.push(tx_byte_count.to_be_bytes().to_vec()));
+
+    10 + tx_byte_count as i64
+}
+
+/// Base fee of the stub: 10 drops plus a drop per byte
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn etxn_fee_base(read_ptr: Ptr, read_len: u32) -> i64 {
+    let txn = read(read_ptr, read_len);
+    host(|host| host.fee_txns.push(txn.to_vec()));
+
+    10 + read_len as i64
+}
+
+#[no_mangle]
+extern "C" fn hook_account(write_ptr: Ptr, write_len: u32) -> i64 {
+    let account = host(|host| host.hook_account);
+
+    write(write_ptr, write_len, &account)
+}
+
+/// Keylet computed by the `util_keylet` stub
+pub(crate) fn keylet(keylet_type: KeyletType) -> Keylet {
+    let mut keylet = [0; KEYLET_LEN];
+
+    match api::util_keylet(&mut keylet, keylet_type) {
+        api::Result::Ok(_) => keylet,
+        api::Result::Err(_) => panic!("util_keylet failed"),
+    }
+}
+
+impl Host {
+    /// Puts an object into the ledger under the keylet
+    pub(crate) fn insert_object(&mut self, keylet: &[u8], object: Vec<u8>) {
+        self.ledger.insert(keylet.to_vec(), object);
+    }
+
+    /// Content of a slot
+    pub(crate) fn slot(&self, slot_no: u32) -> Option<&Vec<u8>> {
+        match self.slots.get((slot_no as usize).wrapping_sub(1)) {
+            Some(Some(content)) => Some(content),
+            _ => None,
+        }
+    }
+
+    /// Number of occupied slots
+    pub(crate) fn slots_in_use(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    /// Puts the content into `slot_no`, or into a free slot for 0
+    pub(crate) fn fill_slot(&mut self, slot_no: u32, content: Vec<u8>) -> i64 {
+        let slot_no = match slot_no as usize {
+            0 => match self.slots.iter().position(|slot| slot.is_none()) {
+                Some(free) => free + 1,
+                None if self.slots.len() < SLOTS => self.slots.len() + 1,
+                None => return NO_FREE_SLOTS,
+            },
+            slot_no if slot_no <= SLOTS => slot_no,
+            _ => return INVALID_ARGUMENT,
+        };
+
+        if self.slots.len() < slot_no {
+            self.slots.resize(slot_no, None);
+        }
+        self.slots[slot_no - 1] = Some(content);
+
+        slot_no as i64
+    }
+}
+
+#[no_mangle]
+extern "C" fn slot_set(read_ptr: Ptr, read_len: u32, slot_no: i32) -> i64 {
+    let keylet = read(read_ptr, read_len);
+
+    host(|host| match host.ledger.get(keylet) {
+        Some(object) => {
+            let object = object.clone();
+            host.fill_slot(slot_no as u32, object)
+        }
+        None => DOESNT_EXIST,
+    })
+}
+
+#[no_mangle]
+extern "C" fn slot(write_ptr: Ptr, write_len: u32, slot_no: u32) -> i64 {
+    match host(|host| host.slot(slot_no).cloned()) {
+        Some(content) => write(write_ptr, write_len, &content),
+        None => DOESNT_EXIST,
+    }
+}
+
+#[no_mangle]
+extern "C" fn slot_clear(slot_no: u32) -> i64 {
+    host(
+        |host| match host.slots.get_mut((slot_no as usize).wrapping_sub(1)) {
+            Some(slot @ Some(_)) => {
+                *slot = None;
+                1
+            }
+            _ => DOESNT_EXIST,
+        },
+    )
+}
+
+#[no_mangle]
+extern "C" fn slot_size(slot_no: u32) -> i64 {
+    match host(|host| host.slot(slot_no).map(Vec::len)) {
+        Some(len) => len as i64,
+        None => DOESNT_EXIST,
+    }
+}
+
+#[no_mangle]
+extern "C" fn slot_subfield(parent_slot: u32, field_id: u32, new_slot: u32) -> i64 {
+    host(|host| {
+        let field = match host.slot(parent_slot) {
+            Some(parent) => match find_field(parent, field_id) {
+                Ok(field) => parent[field.payload..field.payload + field.len].to_vec(),
+                Err(e) => return e,
+            },
+            None => return DOESNT_EXIST,
+        };
+
+        host.fill_slot(new_slot, field)
+    })
+}
+
+#[no_mangle]
+extern "C" fn slot_subarray(parent_slot: u32, array_id: u32, new_slot: u32) -> i64 {
+    host(|host| {
+        let element = match host.slot(parent_slot) {
+            Some(parent) => match find_element(parent, array_id) {
+                Ok(element) => parent[element.start..element.end].to_vec(),
+                Err(e) => return e,
+            },
+            None => return DOESNT_EXIST,
+        };
+
+        host.fill_slot(new_slot, element)
+    })
+}
+
+#[no_mangle]
+extern "C" fn slot_count(slot_no: u32) -> i64 {
+    host(|host| match host.slot(slot_no) {
+        Some(array) => {
+            let mut count = 0;
+            while find_element(array, count).is_ok() {
+                count += 1;
+            }
+            count as i64
+        }
+        None => DOESNT_EXIST,
+    })
+}
+
+/// Host `slot_float`: the amount in the slot as XFL
+#[no_mangle]
+extern "C" fn slot_float(slot_no: u32) -> i64 {
+    match host(|host| host.slot(slot_no).cloned()) {
+        Some(amount) if amount.len() == 8 || amount.len() == 48 => {
+            let mut raw = [0; 8];
+            raw.copy_from_slice(&amount[..8]);
+            let raw = u64::from_be_bytes(raw);
+
+            if raw & 0x8000_0000_0000_0000 == 0 {
+                // drops, as a float
+                xfl_from_f64((raw & 0x3FFF_FFFF_FFFF_FFFF) as f64)
+            } else {
+                (raw & 0x7FFF_FFFF_FFFF_FFFF) as i64
+            }
+        }
+        Some(_) => NOT_AN_AMOUNT,
+        None => DOESNT_EXIST,
+    }
+}
+
+#[cfg(feature = "api-v2")]
+const COMPLEX_NOT_SUPPORTED: i64 = -39;
+
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn float_root(float1: i64, n: u32) -> i64 {
+    let value = xfl_to_f64(float1);
+
+    if n == 0 {
+        return INVALID_ARGUMENT;
+    }
+    if value < 0.0 {
+        return COMPLEX_NOT_SUPPORTED;
+    }
+
+    xfl_from_f64(value.powf(1.0 / n as f64))
+}
+
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn float_log(float1: i64) -> i64 {
+    let value = xfl_to_f64(float1);
+
+    if value < 0.0 {
+        return COMPLEX_NOT_SUPPORTED;
+    }
+    if value == 0.0 {
+        return INVALID_ARGUMENT;
+    }
+
+    xfl_from_f64(value.log10())
+}
+
+/// XFL of a float, close enough for the tests
+pub(crate) fn xfl_from_f64(value: f64) -> i64 {
+    if value == 0.0 {
+        return 0;
+    }
+
+    let mut exponent = value.abs().log10().floor() as i32 - 15;
+    let mut mantissa = (value.abs() / 10f64.powi(exponent)).round() as i64;
+
+    if mantissa >= 10_000_000_000_000_000 {
+        mantissa /= 10;
+        exponent += 1;
+    }
+
+    let sign = if value > 0.0 { 1i64 << 62 } else { 0 };
+
+    sign | (((exponent + 97) as i64) << 54) | mantissa
+}
+
+/// Float of an XFL
+#[cfg(feature = "api-v2")]
+pub(crate) fn xfl_to_f64(xfl: i64) -> f64 {
+    if xfl == 0 {
+        return 0.0;
+    }
+
+    let mantissa = (xfl & 0x003F_FFFF_FFFF_FFFF) as f64;
+    let exponent = ((xfl >> 54) & 0xFF) as i32 - 97;
+    let value = mantissa * 10f64.powi(exponent);
+
+    if xfl & (1 << 62) != 0 {
+        value
+    } else {
+        -value
+    }
+}
+
+/// Account, namespace and key of a state entry
+type StateKey = (Vec<u8>, Vec<u8>, Vec<u8>);
+
+fn state_key(account: &[u8], namespace: &[u8], key: &[u8]) -> StateKey {
+    (account.to_vec(), namespace.to_vec(), key.to_vec())
+}
+
+impl Host {
+    /// Value of the state entry
+    pub(crate) fn state_of(
+        &self,
+        account: &[u8],
+        namespace: &[u8],
+        key: &[u8],
+    ) -> Option<&Vec<u8>> {
+        self.state.get(&state_key(account, namespace, key))
+    }
+
+    /// Sets the state entry, an empty value deletes it
+    pub(crate) fn set_state(&mut self, account: &[u8], namespace: &[u8], key: &[u8], value: &[u8]) {
+        let key = state_key(account, namespace, key);
+
+        if value.is_empty() {
+            self.state.remove(&key);
+        } else {
+            self.state.insert(key, value.to_vec());
+        }
+    }
+}
+
+/// The namespace of the hook, all zeroes in the test host
+const HOOK_NAMESPACE: [u8; 32] = [0; 32];
+
+#[no_mangle]
+extern "C" fn state(write_ptr: Ptr, write_len: u32, kread_ptr: Ptr, kread_len: u32) -> i64 {
+    let key = read(kread_ptr, kread_len);
+    let value = host(|host| {
+        let account = host.hook_account;
+        host.state_of(&account, &HOOK_NAMESPACE, key).cloned()
+    });
+
+    match value {
+        Some(value) => write(write_ptr, write_len, &value),
+        None => DOESNT_EXIST,
+    }
+}
+
+#[no_mangle]
+extern "C" fn state_set(read_ptr: Ptr, read_len: u32, kread_ptr: Ptr, kread_len: u32) -> i64 {
+    let value = read(read_ptr, read_len);
+    let key = read(kread_ptr, kread_len);
+
+    host(|host| {
+        let account = host.hook_account;
+        host.set_state(&account, &HOOK_NAMESPACE, key, value);
+    });
+
+    value.len() as i64
+}
+
+fn foreign_state(
+    write_ptr: Ptr,
+    write_len: u32,
+    key: &[u8],
+    namespace: &[u8],
+    account: &[u8],
+) -> i64 {
+    if account.len() != 20 {
+        return _c::INVALID_ACCOUNT as i64;
+    }
+
+    match host(|host| host.state_of(account, namespace, key).cloned()) {
+        Some(value) => write(write_ptr, write_len, &value),
+        None => DOESNT_EXIST,
+    }
+}
+
+#[cfg(not(feature = "api-v2"))]
+#[no_mangle]
+extern "C" fn state_foreign(
+    write_ptr: Ptr,
+    write_len: u32,
+    kread_ptr: Ptr,
+    kread_len: u32,
+    aread_ptr: Ptr,
+    aread_len: u32,
+) -> i64 {
+    foreign_state(
+        write_ptr,
+        write_len,
+        read(kread_ptr, kread_len),
+        &HOOK_NAMESPACE,
+        read(aread_ptr, aread_len),
+    )
+}
+
+/// Host `state_foreign_set`: the account must be the hook's or have granted this hook
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn state_foreign_set(
+    read_ptr: Ptr,
+    read_len: u32,
+    kread_ptr: Ptr,
+    kread_len: u32,
+    nread_ptr: Ptr,
+    nread_len: u32,
+    aread_ptr: Ptr,
+    aread_len: u32,
+) -> i64 {
+    let value = read(read_ptr, read_len);
+    let key = read(kread_ptr, kread_len);
+    let namespace = read(nread_ptr, nread_len);
+    let account = read(aread_ptr, aread_len);
+
+    if account.len() != 20 {
+        return _c::INVALID_ACCOUNT as i64;
+    }
+    if namespace.len() != 32 {
+        return INVALID_ARGUMENT;
+    }
+
+    host(|host| {
+        if account != host.hook_account && !host.grants.contains(account) {
+            return NOT_AUTHORIZED;
+        }
+
+        host.set_state(account, namespace, key, value);
+
+        value.len() as i64
+    })
+}
+
+#[cfg(feature = "api-v2")]
+#[no_mangle]
+extern "C" fn state_foreign(
+    write_ptr: Ptr,
+    write_len: u32,
+    kread_ptr: Ptr,
+    kread_len: u32,
+    nread_ptr: Ptr,
+    nread_len: u32,
+    aread_ptr: Ptr,
+    aread_len: u32,
+) -> i64 {
+    let namespace = read(nread_ptr, nread_len);
+    if namespace.len() != 32 {
+        return INVALID_ARGUMENT;
+    }
+
+    foreign_state(
+        write_ptr,
+        write_len,
+        read(kread_ptr, kread_len),
+        namespace,
+        read(aread_ptr, aread_len),
+    )
+}
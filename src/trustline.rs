@@ -0,0 +1,276 @@
+use crate::api::*;
+use crate::codec::{u32_from_buf, u64_from_buf};
+use crate::objects::*;
+use crate::uninit_buf;
+
+/// Trust line seen from one of its accounts
+///
+/// Wraps the [RippleState] with the side of the account,
+/// so balances and limits read the same for the low and the high account.
+///
+/// ``` txt
+/// match trust_line(&holder, &ISSUER, &USD) {
+///     Ok(Some(line)) if line.is_frozen() => rollback(b"frozen", 1),
+///     Ok(Some(line)) => line.balance(),
+///     Ok(None) => rollback(b"no trust line", 2),
+///     Err(e) => rollback(b"error", e.code() as _),
+/// }
+/// ```
+#[derive(Clone, Copy)]
+pub struct TrustLine {
+    state: RippleState,
+    low: bool,
+}
+
+impl TrustLine {
+    /// Trust line of `account`, which must be the low or the high account of the line
+    ///
+    /// Returns [Error::InvalidAccount] otherwise.
+    #[inline(always)]
+    pub fn new(state: RippleState, account: &AccountId) -> Result<Self> {
+        let low = if is_same(account, &state.low_account) {
+            true
+        } else if is_same(account, &state.high_account) {
+            false
+        } else {
+            return Err(Error::InvalidAccount);
+        };
+
+        Ok(TrustLine { state, low })
+    }
+
+    /// The ledger object
+    #[inline(always)]
+    pub const fn state(&self) -> &RippleState {
+        &self.state
+    }
+
+    /// The account is the low account of the line
+    #[inline(always)]
+    pub const fn is_low(&self) -> bool {
+        self.low
+    }
+
+    /// The other account, the issuer for a holder
+    #[inline(always)]
+    pub const fn peer(&self) -> &AccountId {
+        if self.low {
+            &self.state.high_account
+        } else {
+            &self.state.low_account
+        }
+    }
+
+    /// Balance of the account, negative if it owes the peer
+    #[inline(always)]
+    pub fn balance(&self) -> XFL {
+        if self.low {
+            self.state.balance
+        } else {
+            negate(self.state.balance)
+        }
+    }
+
+    /// Limit the account set, how much of the peer's currency it trusts
+    #[inline(always)]
+    pub const fn limit(&self) -> XFL {
+        if self.low {
+            self.state.low_limit
+        } else {
+            self.state.high_limit
+        }
+    }
+
+    /// Limit the peer set
+    #[inline(always)]
+    pub const fn peer_limit(&self) -> XFL {
+        if self.low {
+            self.state.high_limit
+        } else {
+            self.state.low_limit
+        }
+    }
+
+    /// The account froze the line
+    #[inline(always)]
+    pub const fn is_freezing(&self) -> bool {
+        self.flag(LSF_LOW_FREEZE, LSF_HIGH_FREEZE)
+    }
+
+    /// The peer froze the line, an issuer freezing the holder
+    #[inline(always)]
+    pub const fn is_frozen(&self) -> bool {
+        self.flag(LSF_HIGH_FREEZE, LSF_LOW_FREEZE)
+    }
+
+    /// The peer authorized the account, needed if the issuer requires authorization
+    #[inline(always)]
+    pub const fn is_authorized(&self) -> bool {
+        self.flag(LSF_HIGH_AUTH, LSF_LOW_AUTH)
+    }
+
+    /// The account disabled rippling through the line
+    #[inline(always)]
+    pub const fn is_no_ripple(&self) -> bool {
+        self.flag(LSF_LOW_NO_RIPPLE, LSF_HIGH_NO_RIPPLE)
+    }
+
+    /// Flag of the low side if the account is low, of the high side otherwise
+    #[inline(always)]
+    const fn flag(&self, low: u32, high: u32) -> bool {
+        let flag = if self.low { low } else { high };
+
+        self.state.flags & flag != 0
+    }
+}
+
+/// Trust line between `account` and `issuer` for the currency, `None` if there is no line
+///
+/// Uses a slot and frees it before returning.
+#[inline(always)]
+pub fn trust_line(
+    account: &AccountId,
+    issuer: &AccountId,
+    currency: &CurrencyCode,
+) -> Result<Option<TrustLine>> {
+    let (high, low) = if is_lower(account, issuer) {
+        (issuer, account)
+    } else {
+        (account, issuer)
+    };
+
+    let mut keylet: Keylet = [0; KEYLET_LEN];
+
+    match util_keylet(&mut keylet, KeyletType::Line(high, low, currency)) {
+        Err(e) => return Err(e),
+        Ok(_) => {}
+    }
+
+    let line_slot = match slot_set(&keylet, 0) {
+        Ok(slot_no) => slot_no as u32,
+        Err(Error::DoesntExist) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let mut buf: [u8; LEDGER_OBJECT_BUF_LEN] = uninit_buf!();
+    let state = RippleState::from_slot(line_slot, &mut buf);
+    let _ = slot_clear(line_slot);
+
+    match state {
+        Ok(state) => match TrustLine::new(state, account) {
+            Ok(line) => Ok(Some(line)),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+/// Balance of `account` in the currency of `issuer`, `None` if there is no trust line
+///
+/// Negative if the account owes the issuer. See [trust_line].
+#[inline(always)]
+pub fn trustline_balance(
+    account: &AccountId,
+    issuer: &AccountId,
+    currency: &CurrencyCode,
+) -> Result<Option<XFL>> {
+    match trust_line(account, issuer, currency) {
+        Ok(line) => Ok(line.map(|line| line.balance())),
+        Err(e) => Err(e),
+    }
+}
+
+/// Negate an XFL without a host call, zero stays zero
+#[inline(always)]
+fn negate(float: XFL) -> XFL {
+    if float.0 == 0 {
+        float
+    } else {
+        XFL(float.0 ^ (1 << 62))
+    }
+}
+
+/// Account ids as big-endian numbers, without a loop
+#[inline(always)]
+fn account_parts(account: &AccountId) -> (u64, u64, u32) {
+    let a = account;
+
+    (
+        u64_from_buf(&[a[0], a[1], a[2], a[3], a[4], a[5], a[6], a[7]]),
+        u64_from_buf(&[a[8], a[9], a[10], a[11], a[12], a[13], a[14], a[15]]),
+        u32_from_buf(&[a[16], a[17], a[18], a[19]]),
+    )
+}
+
+#[inline(always)]
+fn is_lower(a: &AccountId, b: &AccountId) -> bool {
+    account_parts(a) < account_parts(b)
+}
+
+#[inline(always)]
+fn is_same(a: &AccountId, b: &AccountId) -> bool {
+    account_parts(a) == account_parts(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOW: AccountId = [1; ACC_ID_LEN];
+    const HIGH: AccountId = [2; ACC_ID_LEN];
+
+    // 5 * 10^0 as XFL, positive
+    const FIVE: XFL = XFL(0x5491_C379_37E0_8000);
+
+    fn line_state() -> RippleState {
+        RippleState {
+            balance: FIVE,
+            currency: [0; CURRENCY_CODE_SIZE],
+            low_account: LOW,
+            low_limit: FIVE,
+            high_account: HIGH,
+            high_limit: XFL(0),
+            flags: LSF_HIGH_FREEZE | LSF_HIGH_AUTH,
+        }
+    }
+
+    #[test]
+    fn sides() {
+        let low = match TrustLine::new(line_state(), &LOW) {
+            Ok(line) => line,
+            Err(_) => panic!("low side"),
+        };
+        let high = match TrustLine::new(line_state(), &HIGH) {
+            Ok(line) => line,
+            Err(_) => panic!("high side"),
+        };
+
+        assert!(low.is_low());
+        assert_eq!(low.balance().0, FIVE.0);
+        assert_eq!(high.balance().0, FIVE.0 ^ (1 << 62));
+        assert_eq!(low.limit().0, FIVE.0);
+        assert_eq!(high.limit().0, 0);
+        assert_eq!(high.peer_limit().0, FIVE.0);
+        assert_eq!(low.peer(), &HIGH);
+
+        assert!(low.is_frozen() && !low.is_freezing());
+        assert!(high.is_freezing() && !high.is_frozen());
+        assert!(low.is_authorized() && !high.is_authorized());
+
+        assert!(TrustLine::new(line_state(), &[3; ACC_ID_LEN]).is_err());
+    }
+
+    #[test]
+    fn ordering() {
+        let mut a = [0; ACC_ID_LEN];
+        let mut b = [0; ACC_ID_LEN];
+        a[19] = 1;
+        b[0] = 1;
+
+        assert!(is_lower(&a, &b));
+        assert!(!is_lower(&b, &a));
+        assert!(!is_lower(&a, &a));
+        assert!(is_same(&a, &a));
+        assert_eq!(negate(XFL(0)).0, 0);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::amount::TypedAmount;
+use crate::api::*;
+use crate::fields::*;
+use crate::memo::{otxn_memos, Memos};
+
+/// Typed view of the originating transaction
+///
+/// Optional fields are `Ok(None)` when the transaction doesn't have them.
+///
+/// ``` txt
+/// let otxn = OriginatingTxn;
+///
+/// match otxn.destination_tag() {
+///     Ok(Some(tag)) => ...,
+///     Ok(None) => rollback(b"destination tag required", 1),
+///     Err(e) => rollback(b"error", e.code() as _),
+/// }
+/// ```
+#[derive(Clone, Copy)]
+pub struct OriginatingTxn;
+
+impl OriginatingTxn {
+    /// `Account` field, the sender
+    #[inline(always)]
+    pub fn account(&self) -> Result<AccountId> {
+        required(account_field(self, FieldId::Account))
+    }
+
+    /// `Destination` field
+    #[inline(always)]
+    pub fn destination(&self) -> Result<Option<AccountId>> {
+        account_field(self, FieldId::Destination)
+    }
+
+    /// `Amount` field
+    #[inline(always)]
+    pub fn amount(&self) -> Result<Option<TypedAmount>> {
+        amount_field(self, FieldId::Amount)
+    }
+
+    /// `SendMax` field
+    #[inline(always)]
+    pub fn send_max(&self) -> Result<Option<TypedAmount>> {
+        amount_field(self, FieldId::SendMax)
+    }
+
+    /// `Fee` field in drops
+    #[inline(always)]
+    pub fn fee(&self) -> Result<u64> {
+        required(drops_field(self, FieldId::Fee))
+    }
+
+    /// `Sequence` field
+    #[inline(always)]
+    pub fn sequence(&self) -> Result<u32> {
+        required(u32_field(self, FieldId::Sequence))
+    }
+
+    /// `Flags` field, zero if absent
+    #[inline(always)]
+    pub fn flags(&self) -> Result<u32> {
+        match u32_field(self, FieldId::Flags) {
+            Ok(flags) => Ok(flags.unwrap_or(0)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// `DestinationTag` field
+    #[inline(always)]
+    pub fn destination_tag(&self) -> Result<Option<u32>> {
+        u32_field(self, FieldId::DestinationTag)
+    }
+
+    /// `SourceTag` field
+    #[inline(always)]
+    pub fn source_tag(&self) -> Result<Option<u32>> {
+        u32_field(self, FieldId::SourceTag)
+    }
+
+    /// `LastLedgerSequence` field
+    #[inline(always)]
+    pub fn last_ledger_sequence(&self) -> Result<Option<u32>> {
+        u32_field(self, FieldId::LastLedgerSequence)
+    }
+
+    /// `InvoiceID` field
+    #[inline(always)]
+    pub fn invoice_id(&self) -> Result<Option<Hash>> {
+        hash_field(self, FieldId::InvoiceID)
+    }
+
+    /// Transaction type, see [otxn_type]
+    #[inline(always)]
+    pub fn txn_type(&self) -> Result<TxnType> {
+        otxn_type()
+    }
+
+    /// Canonical hash of the transaction
+    #[inline(always)]
+    pub fn hash(&self) -> Result<Hash> {
+        let mut hash = [0; HASH_LEN];
+
+        #[cfg(feature = "api-v2")]
+        let res = otxn_id(&mut hash, 0);
+        #[cfg(not(feature = "api-v2"))]
+        let res = otxn_id(&mut hash);
+
+        match res {
+            Ok(_) => Ok(hash),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Memos of the transaction, serialized into `buf`
+    ///
+    /// See [otxn_memos].
+    #[inline(always)]
+    pub fn memos<'a>(&self, buf: &'a mut [u8]) -> Result<Memos<'a>> {
+        otxn_memos(buf)
+    }
+
+    /// Generation of the transaction, see [otxn_generation]
+    #[inline(always)]
+    pub fn generation(&self) -> i64 {
+        otxn_generation()
+    }
+
+    /// Burden of the transaction, see [otxn_burden]
+    #[inline(always)]
+    pub fn burden(&self) -> i64 {
+        otxn_burden()
+    }
+}
+
+impl FieldSource for OriginatingTxn {
+    #[inline(always)]
+    fn field<'a>(&'a self, field_id: FieldId, buf: &'a mut [u8]) -> Result<Option<&'a [u8]>> {
+        match otxn_field(buf, field_id) {
+            Ok(len) => Ok(Some(&buf[..len as usize])),
+            Err(Error::DoesntExist) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Sto};
+
+    const SENDER: AccountId = [0x51; ACC_ID_LEN];
+    const RECEIVER: AccountId = [0x52; ACC_ID_LEN];
+    const USD: CurrencyCode = [
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, b'U', b'S', b'D', 0, 0, 0, 0, 0,
+    ];
+    // 5
+    const FIVE: i64 = 0x5491_C379_37E0_8000;
+
+    fn payment() {
+        let txn = Sto::new()
+            .u16(FieldId::TransactionType as _, 0)
+            .u32(FieldId::Sequence as _, 17)
+            .u32(FieldId::DestinationTag as _, 99)
+            .u32(FieldId::LastLedgerSequence as _, 1_000)
+            .fixed(FieldId::InvoiceID as _, &[0x1D; HASH_LEN])
+            .drops(FieldId::Amount as _, 25_000_000)
+            .drops(FieldId::Fee as _, 12)
+            .iou(FieldId::SendMax as _, FIVE, &USD, &RECEIVER)
+            .vl_field(FieldId::Account as _, &SENDER)
+            .vl_field(FieldId::Destination as _, &RECEIVER)
+            .build();
+
+        testing::host(|host| host.otxn = txn);
+    }
+
+    #[test]
+    fn required_fields() {
+        payment();
+        let otxn = OriginatingTxn;
+
+        assert!(matches!(otxn.account(), Ok(account) if account == SENDER));
+        assert!(matches!(otxn.sequence(), Ok(17)));
+        assert!(matches!(otxn.fee(), Ok(12)));
+        assert!(matches!(otxn.txn_type(), Ok(TxnType::Payment)));
+        assert!(matches!(otxn.hash(), Ok(hash) if hash != [0; HASH_LEN]));
+        // absent flags read as zero
+        assert!(matches!(otxn.flags(), Ok(0)));
+    }
+
+    #[test]
+    fn optional_fields() {
+        payment();
+        let otxn = OriginatingTxn;
+
+        assert!(matches!(otxn.destination(), Ok(Some(account)) if account == RECEIVER));
+        assert!(matches!(otxn.destination_tag(), Ok(Some(99))));
+        assert!(matches!(otxn.source_tag(), Ok(None)));
+        assert!(matches!(otxn.last_ledger_sequence(), Ok(Some(1_000))));
+        assert!(matches!(otxn.invoice_id(), Ok(Some([0x1D, ..]))));
+        assert!(matches!(
+            otxn.amount(),
+            Ok(Some(TypedAmount::Xrp(25_000_000)))
+        ));
+        assert!(matches!(
+            otxn.send_max(),
+            Ok(Some(TypedAmount::Iou { value, currency, issuer }))
+                if value.0 == FIVE && currency == USD && issuer == RECEIVER
+        ));
+
+        let mut buf = [0; 64];
+        assert!(matches!(otxn.memos(&mut buf), Ok(memos) if memos.iter::<1>(1).next().is_none()));
+        assert!(!testing::guard_violated());
+    }
+
+    #[test]
+    fn missing_required_fields() {
+        testing::host(|host| host.otxn = Sto::new().drops(FieldId::Amount as _, 1).build());
+        let otxn = OriginatingTxn;
+
+        assert!(matches!(otxn.account(), Err(Error::DoesntExist)));
+        assert!(matches!(otxn.fee(), Err(Error::DoesntExist)));
+        assert!(matches!(otxn.sequence(), Err(Error::DoesntExist)));
+    }
+
+    #[test]
+    fn short_fields() {
+        testing::host(|host| {
+            host.otxn = Sto::new()
+                .vl_field(FieldId::Account as _, &SENDER[..19])
+                .build()
+        });
+        let otxn = OriginatingTxn;
+
+        // the host writes fewer bytes than the field holds
+        assert!(matches!(otxn.account(), Err(Error::InvalidField)));
+    }
+
+    #[test]
+    fn txn_type_errors() {
+        // the stub fails without a `TransactionType`
+        testing::host(|host| host.otxn.clear());
+        assert!(matches!(otxn_type(), Err(Error::DoesntExist)));
+
+        testing::host(|host| host.otxn = Sto::new().u16(FieldId::TransactionType as _, 22).build());
+        assert!(matches!(otxn_type(), Ok(TxnType::HookSet)));
+    }
+}